@@ -0,0 +1,190 @@
+//! Optional local HTTP API for scripting against Oatmeal.
+//!
+//! Bound to 127.0.0.1 only, off by default (`api_enabled`), and every request must carry
+//! `Authorization: Bearer <token>` where the token comes from `get_api_token`.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use tauri::Manager;
+use tokio::sync::oneshot;
+
+use crate::database::SessionRecord;
+use crate::llm;
+use crate::AppState;
+use log::{error, info};
+
+type ApiError = (StatusCode, String);
+
+#[derive(Clone)]
+struct ApiState {
+    app_handle: tauri::AppHandle,
+    token: Arc<String>,
+}
+
+/// A running server; dropping `shutdown` (or sending on it) stops it.
+pub struct ApiHandle {
+    pub port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl ApiHandle {
+    pub fn stop(self) {
+        let _ = self.shutdown.send(());
+    }
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<i32>,
+}
+
+fn authorize(api: &ApiState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let provided = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if !provided.is_empty() && constant_time_eq(provided.as_bytes(), api.token.as_bytes()) {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "Missing or invalid API token".to_string()))
+    }
+}
+
+/// Compare without stopping at the first differing byte, so response times don't reveal
+/// how much of a guessed token is right. Only the length can leak, and it isn't secret.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+async fn load_session(api: &ApiState, id: &str) -> Result<SessionRecord, ApiError> {
+    let state = api.app_handle.state::<AppState>();
+    let db_guard = state.database.lock().await;
+    let database = db_guard
+        .as_ref()
+        .ok_or((StatusCode::SERVICE_UNAVAILABLE, "Database not initialized".to_string()))?;
+    database
+        .get_session(id)
+        .await
+        .map_err(internal)?
+        // Same visibility as the list endpoints: no trashed sessions or unsaved drafts
+        .filter(|s| s.deleted_at.is_none() && !s.is_draft)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Session {} not found", id)))
+}
+
+async fn list_sessions(
+    State(api): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<SessionRecord>>, ApiError> {
+    authorize(&api, &headers)?;
+    let state = api.app_handle.state::<AppState>();
+    let db_guard = state.database.lock().await;
+    let database = db_guard
+        .as_ref()
+        .ok_or((StatusCode::SERVICE_UNAVAILABLE, "Database not initialized".to_string()))?;
+    let sessions = database.list_sessions(query.limit).await.map_err(internal)?;
+    Ok(Json(sessions))
+}
+
+async fn get_session(
+    State(api): State<ApiState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<SessionRecord>, ApiError> {
+    authorize(&api, &headers)?;
+    Ok(Json(load_session(&api, &id).await?))
+}
+
+async fn get_transcript(
+    State(api): State<ApiState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<String, ApiError> {
+    authorize(&api, &headers)?;
+    Ok(load_session(&api, &id).await?.transcript.unwrap_or_default())
+}
+
+async fn summarize_session(
+    State(api): State<ApiState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    authorize(&api, &headers)?;
+    let session = load_session(&api, &id).await?;
+    let transcript = session.transcript.clone().unwrap_or_default();
+    if transcript.trim().is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "Session has no transcript".to_string()));
+    }
+
+    let state = api.app_handle.state::<AppState>();
+    let settings = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard
+            .as_ref()
+            .ok_or((StatusCode::SERVICE_UNAVAILABLE, "Database not initialized".to_string()))?;
+        database.get_settings().await.map_err(internal)?
+    };
+    let engine = llm::engine_from_settings(&settings)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+        .ok_or((StatusCode::BAD_REQUEST, "Summary engine is set to 'none'".to_string()))?;
+    let fallback = llm::fallback_engine_from_settings(&settings);
+    let (prompt, content) = llm::summary_request(&settings, &session.title, session.duration, &transcript)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let (summary, used) = llm::summarize_with_fallback(engine.as_ref(), fallback.as_deref(), &content, &prompt, &|_| {}, |_| {})
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+
+    let usage = llm::TokenUsage::of(used, &[&prompt, &content], &summary);
+
+    let db_guard = state.database.lock().await;
+    if let Some(database) = db_guard.as_ref() {
+        if let Err(e) = database.record_token_usage(Some(&id), used.name(), used.model(), &usage).await {
+            error!("Failed to record token usage: {}", e);
+        }
+        database.update_session_summary(&id, &summary).await.map_err(internal)?;
+        database
+            .update_session_summary_engine(&id, used.name(), used.model())
+            .await
+            .map_err(internal)?;
+    }
+    Ok(Json(serde_json::json!({ "session_id": id, "summary": summary, "engine": used.name() })))
+}
+
+/// Start the API on 127.0.0.1:`port`.
+pub async fn start(app_handle: tauri::AppHandle, port: u16, token: String) -> Result<ApiHandle, String> {
+    let api = ApiState { app_handle, token: Arc::new(token) };
+    let router = Router::new()
+        .route("/v1/sessions", get(list_sessions))
+        .route("/v1/sessions/:id", get(get_session))
+        .route("/v1/sessions/:id/transcript", get(get_transcript))
+        .route("/v1/sessions/:id/summary", post(summarize_session))
+        .with_state(api);
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = axum::Server::try_bind(&addr)
+        .map_err(|e| format!("Failed to bind local API on {}: {}", addr, e))?
+        .serve(router.into_make_service());
+
+    let (shutdown, rx) = oneshot::channel::<()>();
+    tauri::async_runtime::spawn(async move {
+        let graceful = server.with_graceful_shutdown(async {
+            let _ = rx.await;
+        });
+        if let Err(e) = graceful.await {
+            error!("Local API server error: {}", e);
+        }
+    });
+    info!("🔌 Local API listening on http://{}", addr);
+    Ok(ApiHandle { port, shutdown })
+}
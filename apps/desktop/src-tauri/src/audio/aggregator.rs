@@ -0,0 +1,198 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel as channel;
+
+use super::frame::{emit_frame, FrameEncoding};
+use super::mixer::soft_limit;
+use super::recorder::WavRecorder;
+
+const DEFAULT_FRAME_MS: u32 = 20;
+// Shorter frames flood the event bridge; longer ones make level meters and captions lag
+const MIN_FRAME_MS: u32 = 10;
+const MAX_FRAME_MS: u32 = 100;
+const MIN_GAIN: f32 = 0.5;
+const MAX_GAIN: f32 = 4.0;
+
+/// Shared by every aggregator of a recording: calls `on_silence` once when none of them
+/// has produced a non-silent frame for `limit`. Any voiced frame, or a pause, restarts
+/// the clock, so only sustained silence counts.
+pub struct SilenceWatch {
+  limit_ms: u64,
+  started: Instant,
+  last_voice_ms: AtomicU64,
+  fired: AtomicBool,
+  on_silence: Box<dyn Fn() + Send + Sync>,
+}
+
+impl SilenceWatch {
+  pub fn new(limit: Duration, on_silence: impl Fn() + Send + Sync + 'static) -> Arc<Self> {
+    Arc::new(Self {
+      limit_ms: limit.as_millis() as u64,
+      started: Instant::now(),
+      last_voice_ms: AtomicU64::new(0),
+      fired: AtomicBool::new(false),
+      on_silence: Box::new(on_silence),
+    })
+  }
+
+  fn elapsed_ms(&self) -> u64 {
+    self.started.elapsed().as_millis() as u64
+  }
+
+  fn reset(&self) {
+    self.last_voice_ms.store(self.elapsed_ms(), Ordering::Relaxed);
+  }
+
+  fn observe(&self, frame: &[f32]) {
+    if !crate::transcribe::is_silent(frame) {
+      self.reset();
+      return;
+    }
+    let silent_for = self.elapsed_ms().saturating_sub(self.last_voice_ms.load(Ordering::Relaxed));
+    if silent_for >= self.limit_ms && !self.fired.swap(true, Ordering::SeqCst) {
+      (self.on_silence)();
+    }
+  }
+}
+
+impl fmt::Debug for SilenceWatch {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("SilenceWatch").field("limit_ms", &self.limit_ms).finish()
+  }
+}
+
+/// Re-chunks arbitrary-sized sample blocks into fixed-length `audio:frame` events
+/// (20ms unless configured with `with_frame_ms`).
+pub struct FrameAggregator {
+  app_handle: tauri::AppHandle,
+  encoding: FrameEncoding,
+  sample_rate: u32,
+  frame_len: usize,
+  gain: f32,
+  paused: Arc<AtomicBool>,
+  buf: Vec<f32>,
+  source: Option<&'static str>,
+  track: Option<u8>,
+  recorder: Option<WavRecorder>,
+  silence: Option<Arc<SilenceWatch>>,
+}
+
+impl FrameAggregator {
+  pub fn new(app_handle: tauri::AppHandle, encoding: FrameEncoding, sample_rate: u32) -> Self {
+    let frame_len = Self::frame_len(sample_rate, DEFAULT_FRAME_MS);
+    Self { app_handle, encoding, sample_rate, frame_len, gain: 1.0, paused: Arc::default(), buf: Vec::with_capacity(frame_len * 2), source: None, track: None, recorder: None, silence: None }
+  }
+
+  fn frame_len(sample_rate: u32, frame_ms: u32) -> usize {
+    let frame_ms = frame_ms.clamp(MIN_FRAME_MS, MAX_FRAME_MS);
+    (sample_rate as usize * frame_ms as usize / 1000).max(1)
+  }
+
+  /// Emit frames of `frame_ms` milliseconds, clamped to 10-100ms.
+  pub fn with_frame_ms(mut self, frame_ms: u32) -> Self {
+    self.frame_len = Self::frame_len(self.sample_rate, frame_ms);
+    self
+  }
+
+  /// Multiply every sample by `gain` (clamped to 0.5-4.0) before it is recorded or
+  /// framed, soft-limiting the result so boosted peaks don't clip.
+  pub fn with_gain(mut self, gain: f32) -> Self {
+    self.gain = if gain.is_finite() { gain.clamp(MIN_GAIN, MAX_GAIN) } else { 1.0 };
+    self
+  }
+
+  /// Discard pushed samples (nothing framed or recorded) while `paused` is set.
+  pub fn with_pause(mut self, paused: Arc<AtomicBool>) -> Self {
+    self.paused = paused;
+    self
+  }
+
+  /// Tag every emitted frame with a stream label.
+  pub fn with_source(mut self, source: &'static str) -> Self {
+    self.source = Some(source);
+    self
+  }
+
+  /// Tag every emitted frame with its track number in a two-track recording.
+  pub fn with_track(mut self, track: u8) -> Self {
+    self.track = Some(track);
+    self
+  }
+
+  /// Report every frame to `watch` so sustained silence can end the recording.
+  pub fn with_silence_watch(mut self, watch: Option<Arc<SilenceWatch>>) -> Self {
+    self.silence = watch;
+    self
+  }
+
+  /// Also write every sample pushed through this aggregator to a WAV file.
+  pub fn with_recorder(mut self, recorder: Option<WavRecorder>) -> Self {
+    self.recorder = recorder;
+    self
+  }
+
+  pub fn push(&mut self, samples: &[f32]) {
+    if self.paused.load(Ordering::Relaxed) {
+      // Time spent paused isn't silence in the recording
+      if let Some(watch) = &self.silence {
+        watch.reset();
+      }
+      return;
+    }
+    let boosted: Vec<f32>;
+    let samples = if self.gain == 1.0 {
+      samples
+    } else {
+      boosted = samples.iter().map(|&s| soft_limit(s * self.gain)).collect();
+      &boosted
+    };
+    if let Some(recorder) = self.recorder.as_mut() {
+      recorder.write(samples);
+    }
+    self.buf.extend_from_slice(samples);
+    while self.buf.len() >= self.frame_len {
+      let frame: Vec<f32> = self.buf.drain(0..self.frame_len).collect();
+      if let Some(watch) = &self.silence {
+        watch.observe(&frame);
+      }
+      emit_frame(&self.app_handle, self.encoding, &frame, self.sample_rate, self.source, self.track);
+    }
+  }
+
+  /// Emit whatever is left as a short final frame and close the recording, if any.
+  pub fn flush(&mut self) {
+    if !self.buf.is_empty() {
+      emit_frame(&self.app_handle, self.encoding, &self.buf, self.sample_rate, self.source, self.track);
+      self.buf.clear();
+    }
+    if let Some(recorder) = self.recorder.take() {
+      recorder.finalize();
+    }
+  }
+}
+
+/// Drain sample chunks from `rx` into `aggregator` on a background thread until
+/// `is_running` returns false or every sender is dropped.
+pub fn spawn_aggregator<F>(
+  mut aggregator: FrameAggregator,
+  rx: channel::Receiver<Vec<f32>>,
+  is_running: F,
+) -> thread::JoinHandle<()>
+where
+  F: Fn() -> bool + Send + 'static,
+{
+  thread::spawn(move || {
+    while is_running() {
+      match rx.recv_timeout(Duration::from_millis(50)) {
+        Ok(chunk) => aggregator.push(&chunk),
+        Err(channel::RecvTimeoutError::Timeout) => continue,
+        Err(channel::RecvTimeoutError::Disconnected) => break,
+      }
+    }
+    aggregator.flush();
+  })
+}
@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel as channel;
+use tauri::Manager;
+use log::warn;
+
+/// Counts audio chunks the realtime callbacks had to throw away because the consumer
+/// (aggregator/mixer) fell behind and the channel was full. One per recording.
+#[derive(Debug, Default)]
+pub struct DropCounter {
+  chunks: AtomicU64,
+  samples: AtomicU64,
+  warned: AtomicBool,
+}
+
+impl DropCounter {
+  fn record(&self, source: &str, samples: usize) {
+    self.chunks.fetch_add(1, Ordering::Relaxed);
+    self.samples.fetch_add(samples as u64, Ordering::Relaxed);
+    if !self.warned.swap(true, Ordering::Relaxed) {
+      warn!("⚠️ Dropping {} audio: the capture pipeline can't keep up (machine overloaded?)", source);
+    }
+  }
+
+  pub fn chunks(&self) -> u64 {
+    self.chunks.load(Ordering::Relaxed)
+  }
+
+  pub fn samples(&self) -> u64 {
+    self.samples.load(Ordering::Relaxed)
+  }
+}
+
+/// The sending end a capture callback writes into. Never blocks; full-channel drops
+/// are counted instead of silently discarded.
+#[derive(Debug, Clone)]
+pub struct SampleSink {
+  tx: channel::Sender<Vec<f32>>,
+  drops: Arc<DropCounter>,
+  source: &'static str,
+}
+
+impl SampleSink {
+  pub fn new(tx: channel::Sender<Vec<f32>>, drops: Arc<DropCounter>, source: &'static str) -> Self {
+    Self { tx, drops, source }
+  }
+
+  pub fn push(&self, chunk: Vec<f32>) {
+    if let Err(channel::TrySendError::Full(chunk)) = self.tx.try_send(chunk) {
+      self.drops.record(self.source, chunk.len());
+    }
+  }
+}
+
+/// Emit `audio:dropped` at most once a second while new drops keep happening.
+pub fn spawn_drop_reporter<F>(app_handle: tauri::AppHandle, drops: Arc<DropCounter>, is_running: F)
+where
+  F: Fn() -> bool + Send + 'static,
+{
+  thread::spawn(move || {
+    let mut reported = 0u64;
+    while is_running() {
+      thread::sleep(Duration::from_secs(1));
+      let total = drops.chunks();
+      if total > reported {
+        let _ = app_handle.emit_all("audio:dropped", serde_json::json!({
+          "dropped": total - reported,
+          "total": total,
+          "total_samples": drops.samples(),
+        }));
+        reported = total;
+      }
+    }
+  });
+}
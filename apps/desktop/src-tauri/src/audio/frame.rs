@@ -0,0 +1,63 @@
+use base64::Engine;
+use tauri::Manager;
+
+/// How `audio:frame` payloads carry their samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameEncoding {
+  /// `data` is a JSON array of floats (original format)
+  Json,
+  /// `data_b64` is base64 of the raw little-endian f32 bytes
+  Base64,
+}
+
+impl FrameEncoding {
+  pub fn from_setting(value: &str) -> Self {
+    match value.trim().to_lowercase().as_str() {
+      "base64" | "binary" | "f32le" => FrameEncoding::Base64,
+      _ => FrameEncoding::Json,
+    }
+  }
+}
+
+impl Default for FrameEncoding {
+  fn default() -> Self { FrameEncoding::Json }
+}
+
+fn now_ms() -> u128 {
+  std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .unwrap()
+    .as_millis()
+}
+
+/// Emit one `audio:frame` event in the requested encoding. `source` ("mic" | "system",
+/// or "track1" | "track2") is only set when streams are emitted separately; `track` only
+/// in two-track recordings.
+pub fn emit_frame(app_handle: &tauri::AppHandle, encoding: FrameEncoding, frame: &[f32], sample_rate: u32, source: Option<&str>, track: Option<u8>) {
+  let mut payload = match encoding {
+    FrameEncoding::Json => serde_json::json!({
+      "data": frame,
+      "timestamp": now_ms(),
+      "sample_rate": sample_rate
+    }),
+    FrameEncoding::Base64 => {
+      let mut bytes = Vec::with_capacity(frame.len() * 4);
+      for sample in frame {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+      }
+      serde_json::json!({
+        "data_b64": base64::engine::general_purpose::STANDARD.encode(&bytes),
+        "encoding": "f32le",
+        "timestamp": now_ms(),
+        "sample_rate": sample_rate
+      })
+    }
+  };
+  if let Some(source) = source {
+    payload["source"] = serde_json::Value::from(source);
+  }
+  if let Some(track) = track {
+    payload["track"] = serde_json::Value::from(track);
+  }
+  let _ = app_handle.emit_all("audio:frame", payload);
+}
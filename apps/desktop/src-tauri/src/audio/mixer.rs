@@ -0,0 +1,275 @@
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel as channel;
+
+use super::aggregator::FrameAggregator;
+use super::resample::StreamResampler;
+use log::info;
+
+/// Everything is mixed at Whisper's native rate.
+pub const MIX_SAMPLE_RATE: u32 = 16_000;
+
+// If one side has produced nothing for this long (muted mic, paused system audio),
+// let the other through against silence instead of waiting for alignment.
+const MAX_LAG_SAMPLES: usize = (MIX_SAMPLE_RATE as usize) / 4;
+
+/// One-pole high-pass (~80 Hz at 16 kHz) to strip rumble before Whisper.
+#[derive(Default)]
+struct HighPass {
+  prev_in: f32,
+  prev_out: f32,
+}
+
+impl HighPass {
+  const ALPHA: f32 = 0.97;
+
+  fn process(&mut self, x: f32) -> f32 {
+    let y = Self::ALPHA * (self.prev_out + x - self.prev_in);
+    self.prev_in = x;
+    self.prev_out = y;
+    y
+  }
+}
+
+/// Tunables for the mixed path. The defaults reproduce the original fixed behaviour:
+/// instant gain changes, no makeup gain, limiter knee at 0.95.
+#[derive(Debug, Clone, Copy)]
+pub struct AudioProcessingConfig {
+  /// Mic samples below this are gated to silence; system audio uses 60% of it
+  pub gate_threshold: f32,
+  /// How fast the voice/system gains drop when a block gets loud (0 = instant)
+  pub attack_ms: f32,
+  /// How fast they recover when it gets quiet again (0 = instant)
+  pub release_ms: f32,
+  /// Applied to the mix before the limiter
+  pub makeup_gain: f32,
+  /// Where the soft limiter starts to bend; output never exceeds 1.0
+  pub limiter_ceiling: f32,
+  /// Weight of the mic in the mix (1.0 = unchanged)
+  pub mic_level: f32,
+  /// Weight of system audio in the mix (1.0 = unchanged)
+  pub system_level: f32,
+  /// Boost quiet speech and duck loud system audio on top of the levels
+  pub auto_gain: bool,
+}
+
+impl Default for AudioProcessingConfig {
+  fn default() -> Self {
+    Self {
+      gate_threshold: 0.005,
+      attack_ms: 0.0,
+      release_ms: 0.0,
+      makeup_gain: 1.0,
+      limiter_ceiling: DEFAULT_LIMITER_CEILING,
+      mic_level: 1.0,
+      system_level: 1.0,
+      auto_gain: true,
+    }
+  }
+}
+
+impl AudioProcessingConfig {
+  /// Clamp user-supplied values into ranges that can't silence or blow up the mix.
+  pub fn sanitized(self) -> Self {
+    Self {
+      gate_threshold: self.gate_threshold.clamp(0.0, 0.1),
+      attack_ms: self.attack_ms.clamp(0.0, 1000.0),
+      release_ms: self.release_ms.clamp(0.0, 5000.0),
+      makeup_gain: self.makeup_gain.clamp(0.25, 4.0),
+      limiter_ceiling: self.limiter_ceiling.clamp(0.5, 1.0),
+      mic_level: self.mic_level.clamp(0.0, 2.0),
+      system_level: self.system_level.clamp(0.0, 2.0),
+      auto_gain: self.auto_gain,
+    }
+  }
+}
+
+const DEFAULT_LIMITER_CEILING: f32 = 0.95;
+
+/// Per-sample smoothing coefficient for a time constant of `ms` (0 jumps straight to target).
+fn smoothing_coef(ms: f32) -> f32 {
+  if ms <= 0.0 { return 0.0; }
+  (-1.0 / (ms / 1000.0 * MIX_SAMPLE_RATE as f32)).exp()
+}
+
+/// A gain that follows its target with separate attack (falling) and release (rising) times.
+struct SmoothedGain {
+  current: f32,
+  attack: f32,
+  release: f32,
+}
+
+impl SmoothedGain {
+  fn new(initial: f32, config: &AudioProcessingConfig) -> Self {
+    Self {
+      current: initial,
+      attack: smoothing_coef(config.attack_ms),
+      release: smoothing_coef(config.release_ms),
+    }
+  }
+
+  fn next(&mut self, target: f32) -> f32 {
+    let coef = if target < self.current { self.attack } else { self.release };
+    self.current = target + (self.current - target) * coef;
+    self.current
+  }
+}
+
+fn rms(samples: &[f32]) -> f32 {
+  if samples.is_empty() { return 0.0; }
+  (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+// Base weights before the user's mix levels: voice sits well above system audio
+const VOICE_BASE_GAIN: f32 = 1.2;
+const SYSTEM_BASE_GAIN: f32 = 0.4;
+
+/// Mixes a voice (mic) stream with a system-audio stream into one mono signal.
+/// Voice gets priority: each side is weighted by its mix level and, with auto gain,
+/// quiet speech is boosted and loud system audio ducked. There's a noise gate on each
+/// input and a soft limiter on the output.
+pub struct Mixer {
+  voice_filter: HighPass,
+  system_filter: HighPass,
+  voice_gain: SmoothedGain,
+  system_gain: SmoothedGain,
+  config: AudioProcessingConfig,
+}
+
+impl Mixer {
+  pub fn new(config: AudioProcessingConfig) -> Self {
+    let config = config.sanitized();
+    Self {
+      voice_filter: HighPass::default(),
+      system_filter: HighPass::default(),
+      voice_gain: SmoothedGain::new(VOICE_BASE_GAIN * config.mic_level, &config),
+      system_gain: SmoothedGain::new(SYSTEM_BASE_GAIN * config.system_level, &config),
+      config,
+    }
+  }
+
+  /// Mix two equally-clocked blocks. The shorter block is zero-padded.
+  pub fn mix(&mut self, voice: &[f32], system: &[f32]) -> Vec<f32> {
+    let len = voice.len().max(system.len());
+    let rms_voice = rms(voice);
+    let rms_system = rms(system);
+
+    // Dynamic gain control - boost quiet signals, limit loud ones
+    let (voice_gain, system_gain) = if self.config.auto_gain {
+      (
+        if rms_voice < 0.01 { 2.0 } else if rms_voice > 0.5 { 0.6 } else { VOICE_BASE_GAIN },
+        if rms_system > 0.3 { 0.2 } else { SYSTEM_BASE_GAIN },
+      )
+    } else {
+      (VOICE_BASE_GAIN, SYSTEM_BASE_GAIN)
+    };
+    let voice_gain = voice_gain * self.config.mic_level;
+    let system_gain = system_gain * self.config.system_level;
+
+    let mut out = Vec::with_capacity(len);
+    for i in 0..len {
+      let mut v = voice.get(i).copied().unwrap_or(0.0);
+      let mut s = system.get(i).copied().unwrap_or(0.0);
+
+      // Noise gate - drop very quiet background noise
+      if v.abs() < self.config.gate_threshold { v = 0.0; }
+      if s.abs() < self.config.gate_threshold * 0.6 { s = 0.0; }
+
+      let v = self.voice_filter.process(v) * self.voice_gain.next(voice_gain);
+      let s = self.system_filter.process(s) * self.system_gain.next(system_gain);
+      out.push(soft_limit_at((v + s) * self.config.makeup_gain, self.config.limiter_ceiling));
+    }
+    out
+  }
+}
+
+/// Soft knee above 0.95 to prevent clipping.
+pub(super) fn soft_limit(x: f32) -> f32 {
+  soft_limit_at(x, DEFAULT_LIMITER_CEILING)
+}
+
+/// Soft knee above `ceiling`, approaching but never passing 1.0.
+fn soft_limit_at(x: f32, ceiling: f32) -> f32 {
+  if x.abs() > ceiling {
+    x.signum() * (ceiling + (1.0 - ceiling) * (1.0 - (-20.0 * (x.abs() - ceiling)).exp()))
+  } else {
+    x
+  }
+}
+
+/// Resample the mic and system streams to 16 kHz, align them sample-for-sample and
+/// emit one mixed `audio:frame` stream through `aggregator` (which must be clocked at
+/// `MIX_SAMPLE_RATE`). Runs until `is_running` returns false or both inputs disconnect.
+pub fn spawn_mixer<F>(
+  mut aggregator: FrameAggregator,
+  processing: AudioProcessingConfig,
+  mic_rx: channel::Receiver<Vec<f32>>,
+  mic_rate: u32,
+  system_rx: channel::Receiver<Vec<f32>>,
+  system_rate: u32,
+  is_running: F,
+) -> thread::JoinHandle<()>
+where
+  F: Fn() -> bool + Send + 'static,
+{
+  thread::spawn(move || {
+    let mut mixer = Mixer::new(processing);
+    let mut mic_resampler = StreamResampler::new(mic_rate, MIX_SAMPLE_RATE);
+    let mut system_resampler = StreamResampler::new(system_rate, MIX_SAMPLE_RATE);
+    let mut mic_buf: Vec<f32> = Vec::new();
+    let mut system_buf: Vec<f32> = Vec::new();
+    let mut mic_open = true;
+    let mut system_open = true;
+
+    info!("🎵 Mixer started: mic {} Hz + system {} Hz -> {} Hz", mic_rate, system_rate, MIX_SAMPLE_RATE);
+
+    while is_running() && (mic_open || system_open) {
+      let mut received = false;
+      loop {
+        match mic_rx.try_recv() {
+          Ok(chunk) => { mic_buf.extend(mic_resampler.process(&chunk)); received = true; }
+          Err(channel::TryRecvError::Empty) => break,
+          Err(channel::TryRecvError::Disconnected) => { mic_open = false; break; }
+        }
+      }
+      loop {
+        match system_rx.try_recv() {
+          Ok(chunk) => { system_buf.extend(system_resampler.process(&chunk)); received = true; }
+          Err(channel::TryRecvError::Empty) => break,
+          Err(channel::TryRecvError::Disconnected) => { system_open = false; break; }
+        }
+      }
+
+      // Mix the aligned overlap
+      let n = mic_buf.len().min(system_buf.len());
+      if n > 0 {
+        let mixed = mixer.mix(&mic_buf[..n], &system_buf[..n]);
+        mic_buf.drain(..n);
+        system_buf.drain(..n);
+        aggregator.push(&mixed);
+      }
+
+      // One side is silent or gone: don't hold the other hostage
+      if system_buf.is_empty() && (mic_buf.len() > MAX_LAG_SAMPLES || !system_open) && !mic_buf.is_empty() {
+        let mixed = mixer.mix(&mic_buf, &[]);
+        mic_buf.clear();
+        aggregator.push(&mixed);
+      }
+      if mic_buf.is_empty() && (system_buf.len() > MAX_LAG_SAMPLES || !mic_open) && !system_buf.is_empty() {
+        let mixed = mixer.mix(&[], &system_buf);
+        system_buf.clear();
+        aggregator.push(&mixed);
+      }
+
+      if !received {
+        thread::sleep(Duration::from_millis(5));
+      }
+    }
+
+    let tail = mixer.mix(&mic_buf, &system_buf);
+    aggregator.push(&tail);
+    aggregator.flush();
+    info!("🎵 Mixer stopped");
+  })
+}
@@ -1,6 +1,101 @@
-pub mod simple;
-pub mod real;
+pub mod aggregator;
+pub mod drops;
+pub mod frame;
+pub mod mixer;
+pub mod recorder;
+pub mod resample;
 pub mod runtime;
-pub mod simple_runtime;
+pub mod stream;
+pub mod transport;
 
-pub use simple_runtime::{AudioRuntime, AudioSource};
+pub use aggregator::SilenceWatch;
+pub use drops::{DropCounter, SampleSink};
+pub use frame::FrameEncoding;
+pub use mixer::AudioProcessingConfig;
+pub use runtime::{input_device_names, mic_check, system_audio_check, AudioRuntime, AudioSource, MicCheck, SourceLevels};
+
+/// Called when capture ends on its own: the input went away, nothing could replace it and
+/// there's no system audio to carry on with. Lets the app stop the recording as it would
+/// on a manual stop, whether or not a window is there to notice.
+#[derive(Clone)]
+pub struct CaptureLost(std::sync::Arc<dyn Fn() + Send + Sync>);
+
+impl CaptureLost {
+  pub fn new(on_lost: impl Fn() + Send + Sync + 'static) -> Self {
+    Self(std::sync::Arc::new(on_lost))
+  }
+
+  pub fn notify(&self) {
+    (self.0)()
+  }
+}
+
+impl std::fmt::Debug for CaptureLost {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("CaptureLost")
+  }
+}
+
+/// A system-audio feed (ScreenCaptureKit) to mix with the microphone.
+#[derive(Debug, Clone)]
+pub struct SystemAudioInput {
+  pub rx: crossbeam_channel::Receiver<Vec<f32>>,
+  pub sample_rate: u32,
+}
+
+/// The input a recording ended up on, remembered so the next one opens the same device.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InputChoice {
+  pub device_name: String,
+  /// Whether the device was mixed with a system-audio feed rather than used on its own
+  pub mixed: bool,
+}
+
+/// The inputs of a two-track recording (capture_mode "tracks"), e.g. interviewer and
+/// interviewee on their own mics. Each is framed, saved and transcribed on its own.
+#[derive(Debug, Clone, Default)]
+pub struct TrackInputs {
+  /// Device for track 1
+  pub first: String,
+  /// Device for track 2
+  pub second: String,
+  /// Save track 2 here; track 1 goes to `CaptureOptions::record_path`
+  pub second_record_path: Option<std::path::PathBuf>,
+}
+
+/// Per-recording options read from settings when capture starts.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureOptions {
+  pub force_microphone: bool,
+  /// Use the built-in mic instead of a Bluetooth headset when system audio is captured
+  pub prefer_builtin_mic: bool,
+  /// Input from the last successful recording; tried first when its `mixed` matches this one
+  pub remembered_input: Option<InputChoice>,
+  pub frame_encoding: FrameEncoding,
+  /// `audio:frame` length in ms for every stream; `None` keeps the 20ms default
+  pub frame_ms: Option<u32>,
+  /// Pre-amp applied to every stream before framing and recording; `None` leaves levels alone
+  pub input_gain: Option<f32>,
+  /// Gate/compressor/limiter settings for the mixed mic + system path
+  pub processing: AudioProcessingConfig,
+  /// When set, the mic is mixed with this feed in Rust and emitted as one 16 kHz stream
+  pub system_audio: Option<SystemAudioInput>,
+  /// Why SCKit couldn't provide `system_audio`, reported in `recording:system-audio-unavailable`
+  pub system_audio_error: Option<String>,
+  /// Emit mic and system as separate `audio:frame` streams tagged with `source` instead of mixing
+  pub separate_sources: bool,
+  /// Record two inputs as separate tracks instead of one mic (no system audio)
+  pub tracks: Option<TrackInputs>,
+  /// Save the captured audio (the mixed stream, or the mic in separate mode) to this WAV file
+  pub record_path: Option<std::path::PathBuf>,
+  /// Also save the mic as captured, before gain, processing and mixing (`debug_save_audio`)
+  pub debug_raw_path: Option<std::path::PathBuf>,
+  /// Shared with the SCKit callback so mic and system drops are reported together
+  pub drops: std::sync::Arc<DropCounter>,
+  /// Stops the recording after sustained silence across every stream; `None` disables it
+  pub silence_watch: Option<std::sync::Arc<SilenceWatch>>,
+  /// Told when the input is lost for good and capture has stopped
+  pub on_capture_lost: Option<CaptureLost>,
+  /// Set by the runtime; while true every stream discards what it captures
+  pub paused: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
@@ -0,0 +1,142 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::Receiver;
+use log::{error, info};
+
+/// Streams mono samples to a 16-bit PCM WAV file while recording.
+pub struct WavRecorder {
+  writer: hound::WavWriter<BufWriter<File>>,
+  path: PathBuf,
+}
+
+impl WavRecorder {
+  pub fn create(path: &Path, sample_rate: u32) -> Result<Self, String> {
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create recordings dir: {}", e))?;
+    }
+    let spec = hound::WavSpec {
+      channels: 1,
+      sample_rate,
+      bits_per_sample: 16,
+      sample_format: hound::SampleFormat::Int,
+    };
+    let writer = hound::WavWriter::create(path, spec)
+      .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    Ok(Self { writer, path: path.to_path_buf() })
+  }
+
+  pub fn write(&mut self, samples: &[f32]) {
+    for &s in samples {
+      let v = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+      if self.writer.write_sample(v).is_err() {
+        return;
+      }
+    }
+  }
+
+  /// Rewrite the header so the file is playable as it stands, without finalizing.
+  pub fn flush(&mut self) {
+    if let Err(e) = self.writer.flush() {
+      error!("Failed to flush {}: {}", self.path.display(), e);
+    }
+  }
+
+  pub fn finalize(self) {
+    let path = self.path;
+    if let Err(e) = self.writer.finalize() {
+      error!("Failed to finalize {}: {}", path.display(), e);
+    } else {
+      info!("💾 Saved recording to {}", path.display());
+    }
+  }
+}
+
+/// Pass everything from `rx` through unchanged, writing it to `recorder` on the way
+/// (except while `paused`). The file is finalized once the sending side hangs up.
+pub fn spawn_tee(
+  rx: Receiver<Vec<f32>>,
+  mut recorder: WavRecorder,
+  paused: Arc<AtomicBool>,
+) -> Receiver<Vec<f32>> {
+  let (tx, tee_rx) = crossbeam_channel::bounded::<Vec<f32>>(rx.capacity().unwrap_or(256));
+  thread::spawn(move || {
+    for samples in rx {
+      if !paused.load(Ordering::Relaxed) {
+        recorder.write(&samples);
+      }
+      if tx.send(samples).is_err() {
+        break;
+      }
+    }
+    recorder.finalize();
+  });
+  tee_rx
+}
+
+/// Write `first` (if any) to `out`, pad it with silence up to `offset_ms`, then append
+/// `second`. The files must share a format, and only integer PCM is supported.
+pub fn concat_wav(first: Option<&Path>, second: &Path, offset_ms: u64, out: &Path) -> Result<(), String> {
+  let open = |path: &Path| hound::WavReader::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e));
+  let read_error = |path: &Path, e: hound::Error| format!("Failed to read {}: {}", path.display(), e);
+  let write_error = |e: hound::Error| format!("Failed to write {}: {}", out.display(), e);
+
+  let mut second_reader = open(second)?;
+  let spec = second_reader.spec();
+  if spec.sample_format != hound::SampleFormat::Int {
+    return Err(format!("{} is not integer PCM", second.display()));
+  }
+  let mut first_reader = match first {
+    Some(path) => {
+      let reader = open(path)?;
+      if reader.spec() != spec {
+        return Err(format!("{} and {} have different formats", path.display(), second.display()));
+      }
+      Some((path, reader))
+    }
+    None => None,
+  };
+
+  let mut writer = hound::WavWriter::create(out, spec).map_err(|e| format!("Failed to create {}: {}", out.display(), e))?;
+  let mut written: u64 = 0;
+  if let Some((path, reader)) = first_reader.as_mut() {
+    for sample in reader.samples::<i32>() {
+      writer.write_sample(sample.map_err(|e| read_error(path, e))?).map_err(write_error)?;
+      written += 1;
+    }
+  }
+  let offset = offset_ms * spec.sample_rate as u64 / 1000 * spec.channels as u64;
+  while written < offset {
+    writer.write_sample(0i32).map_err(write_error)?;
+    written += 1;
+  }
+  for sample in second_reader.samples::<i32>() {
+    writer.write_sample(sample.map_err(|e| read_error(second, e))?).map_err(write_error)?;
+  }
+  writer.finalize().map_err(write_error)
+}
+
+/// Read a mono (or downmixed) WAV file as f32 samples plus its sample rate.
+pub fn read_wav(path: &Path) -> Result<(Vec<f32>, u32), String> {
+  let mut reader = hound::WavReader::open(path)
+    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let spec = reader.spec();
+  let channels = spec.channels.max(1) as usize;
+  let interleaved: Vec<f32> = match spec.sample_format {
+    hound::SampleFormat::Float => reader.samples::<f32>().filter_map(|s| s.ok()).collect(),
+    hound::SampleFormat::Int => {
+      let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+      reader.samples::<i32>().filter_map(|s| s.ok()).map(|s| s as f32 / scale).collect()
+    }
+  };
+  let samples = if channels == 1 {
+    interleaved
+  } else {
+    interleaved.chunks_exact(channels).map(|f| f.iter().sum::<f32>() / channels as f32).collect()
+  };
+  Ok((samples, spec.sample_rate))
+}
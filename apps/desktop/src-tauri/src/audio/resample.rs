@@ -0,0 +1,39 @@
+/// Streaming linear resampler. Keeps the last input sample and fractional read
+/// position between calls so block boundaries don't click.
+pub struct StreamResampler {
+  step: f64,
+  pos: f64,
+  prev: f32,
+  passthrough: bool,
+}
+
+impl StreamResampler {
+  pub fn new(src_rate: u32, dst_rate: u32) -> Self {
+    Self {
+      step: src_rate.max(1) as f64 / dst_rate.max(1) as f64,
+      pos: 1.0,
+      prev: 0.0,
+      passthrough: src_rate == dst_rate,
+    }
+  }
+
+  pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+    if self.passthrough || input.is_empty() {
+      return input.to_vec();
+    }
+    // Index space: 0 = previous block's last sample, k = input[k - 1]
+    let len = input.len() as f64;
+    let mut out = Vec::with_capacity((len / self.step) as usize + 1);
+    while self.pos < len {
+      let i = self.pos.floor() as usize;
+      let frac = (self.pos - i as f64) as f32;
+      let a = if i == 0 { self.prev } else { input[i - 1] };
+      let b = input[i];
+      out.push(a + (b - a) * frac);
+      self.pos += self.step;
+    }
+    self.pos -= len;
+    self.prev = input[input.len() - 1];
+    out
+  }
+}
@@ -1,26 +1,21 @@
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Sender, Receiver};
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use crossbeam_channel as channel;
 use tauri::Manager;
 
-fn detect_headphones_or_external_audio(host: &cpal::Host) -> bool {
-  if let Some(default_output) = host.default_output_device() {
-    if let Ok(device_name) = default_output.name() {
-      let name_lower = device_name.to_lowercase();
-      println!("Default output device: {}", device_name);
-      
-      // Simple check - if not built-in speakers, assume headphones/external audio
-      return !name_lower.contains("built-in") && 
-             !name_lower.contains("internal") && 
-             !name_lower.contains("macbook");
-    }
-  }
-  false
-}
+use super::aggregator::{spawn_aggregator, FrameAggregator};
+use super::drops::{spawn_drop_reporter, SampleSink};
+use super::recorder::{spawn_tee, WavRecorder};
+use super::mixer::{spawn_mixer, MIX_SAMPLE_RATE};
+use super::stream::build_mono_input_stream;
+use super::transport::{device_kind, Transport};
+use super::{CaptureOptions, InputChoice};
+use log::{debug, error, info, warn};
 
 #[derive(Debug, Clone)]
 pub enum AudioSource {
@@ -28,14 +23,473 @@ pub enum AudioSource {
   SystemAudio,
 }
 
+/// Reports whether capture actually started.
+type StartReply = tokio::sync::oneshot::Sender<Result<Option<InputChoice>, String>>;
+
 enum Command {
-  Start(tauri::AppHandle, bool /* force_microphone */),
+  Start(tauri::AppHandle, CaptureOptions, StartReply),
   Stop,
+  /// Sent from the stream error callback when the input device goes away; carries the
+  /// stream generation so late duplicates for an already-replaced stream are ignored
+  DeviceLost(u64),
+}
+
+// Opening a device normally takes well under a second; longer means the driver is stuck
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+
+// A replacement device (e.g. the built-in mic after AirPods drop) can take a moment to
+// become the default input
+const RECOVERY_ATTEMPTS: u32 = 5;
+const RECOVERY_DELAY: Duration = Duration::from_millis(500);
+
+/// What's needed to rebuild the mic stream mid-recording without restarting the
+/// aggregator/mixer behind it.
+struct LiveInput {
+  app_handle: tauri::AppHandle,
+  sink: SampleSink,
+  /// Rate the downstream pipeline was set up for; a new device is resampled to it
+  sample_rate: u32,
+  force_microphone: bool,
+  prefer_builtin_mic: bool,
+  has_system_audio: bool,
+  device_name: String,
+  generation: u64,
+  on_capture_lost: Option<super::CaptureLost>,
+}
+
+// Each check asks Core Audio for the device's transport type first and only falls back
+// to matching the (English) name when that isn't available
+
+fn is_loopback_device(name: &str) -> bool {
+  if let Some(kind) = device_kind(name) {
+    return matches!(kind.transport, Transport::Virtual | Transport::Aggregate);
+  }
+  let nl = name.to_lowercase();
+  nl.contains("blackhole") || nl.contains("soundflower") || nl.contains("loopback")
+    || nl.contains("aggregate") || nl.contains("multi-output")
+}
+
+fn is_bluetooth_headset(name: &str) -> bool {
+  if let Some(kind) = device_kind(name) {
+    return kind.transport == Transport::Bluetooth;
+  }
+  let nl = name.to_lowercase();
+  nl.contains("airpods") || nl.contains("beats") || nl.contains("bluetooth")
+    || nl.contains("headset") || nl.contains("buds")
+}
+
+fn is_headphones(name: &str) -> bool {
+  let nl = name.to_lowercase();
+  let named_headphones = nl.contains("headphone") || nl.contains("headset") || nl.contains("earpods") || nl.contains("earphone");
+  match device_kind(name) {
+    Some(kind) => match kind.transport {
+      Transport::Bluetooth => true,
+      Transport::BuiltIn => kind.headphones,
+      // Speakers, display audio and loopback devices can all be heard by the mic
+      Transport::Virtual | Transport::Aggregate | Transport::Display => false,
+      // USB covers both headsets and desk speakers; only the name tells them apart
+      Transport::Usb | Transport::Other => named_headphones,
+    },
+    None => is_bluetooth_headset(name) || named_headphones,
+  }
+}
+
+fn is_builtin_mic(name: &str) -> bool {
+  if let Some(kind) = device_kind(name) {
+    return kind.transport == Transport::BuiltIn;
+  }
+  let nl = name.to_lowercase();
+  nl.contains("macbook") || nl.contains("built-in") || nl.contains("imac")
+}
+
+/// Opening a Bluetooth headset's mic makes macOS drop it from A2DP to the low-quality
+/// SCO/HFP profile, which degrades both the voice we capture and whatever it's playing.
+/// When `prefer_builtin_mic` is set and the default input is such a headset, use the
+/// built-in mic instead. The tradeoff: the built-in mic picks up more room noise.
+fn builtin_mic_instead_of_headset(host: &cpal::Host, default_name: &str) -> Option<cpal::Device> {
+  if !is_bluetooth_headset(default_name) {
+    return None;
+  }
+  host.input_devices().ok()?.find(|d| d.name().map(|n| is_builtin_mic(&n)).unwrap_or(false))
+}
+
+/// Pick the capture device: a loopback system-audio device (BlackHole/Loopback/aggregate)
+/// when one is installed, otherwise the default input. `force_microphone` skips loopback.
+/// A `preferred` device that is still present wins over both, unless it's a Bluetooth
+/// headset and `prefer_builtin_mic` asks for the built-in mic instead.
+fn select_input_device(
+  host: &cpal::Host,
+  force_microphone: bool,
+  prefer_builtin_mic: bool,
+  preferred: Option<&str>,
+) -> Option<(cpal::Device, AudioSource)> {
+  debug!("=== AVAILABLE AUDIO DEVICES ===");
+  if let Ok(input_devices) = host.input_devices() {
+    for device in input_devices {
+      if let Ok(name) = device.name() {
+        debug!("Input device: {}", name);
+      }
+    }
+  }
+  if let Some(output_device) = host.default_output_device() {
+    if let Ok(output_name) = output_device.name() {
+      debug!("Default output device: {}", output_name);
+    }
+  }
+  debug!("================================");
+
+  if let Some(preferred) = preferred {
+    // The setting may have been turned on since this device was remembered
+    if prefer_builtin_mic {
+      if let Some(builtin) = builtin_mic_instead_of_headset(host, preferred) {
+        info!("🎙️ Using built-in mic instead of remembered {} to keep it in A2DP", preferred);
+        return Some((builtin, AudioSource::Microphone));
+      }
+    }
+    let loopback = is_loopback_device(preferred);
+    let found = host
+      .input_devices()
+      .ok()
+      .and_then(|mut devices| devices.find(|d| d.name().map(|n| n == preferred).unwrap_or(false)));
+    match found {
+      Some(_) if loopback && force_microphone => {
+        info!("Remembered input {} is a loopback device but the mic is required; choosing again", preferred)
+      }
+      Some(device) => {
+        info!("🔁 Using remembered input device: {}", preferred);
+        let source = if loopback { AudioSource::SystemAudio } else { AudioSource::Microphone };
+        return Some((device, source));
+      }
+      None => info!("Remembered input {} is gone; choosing again", preferred),
+    }
+  }
+
+  if !force_microphone {
+    if let Ok(input_devices) = host.input_devices() {
+      for device in input_devices {
+        if let Ok(name) = device.name() {
+          if is_loopback_device(&name) {
+            info!("🎛️ Using system audio device: {}", name);
+            return Some((device, AudioSource::SystemAudio));
+          }
+        }
+      }
+    }
+  }
+
+  let device = host.default_input_device()?;
+  let name = device.name().unwrap_or_default();
+  debug!("Default input device (mic): {}", name);
+  if prefer_builtin_mic {
+    if let Some(builtin) = builtin_mic_instead_of_headset(host, &name) {
+      info!("🎙️ Using built-in mic instead of {} to keep it in A2DP", name);
+      return Some((builtin, AudioSource::Microphone));
+    }
+  }
+  Some((device, AudioSource::Microphone))
+}
+
+const LOOPBACK_SETUP_URL: &str = "https://existential.audio/blackhole/";
+
+/// Tell the UI a recording is mic-only, with a machine-readable `reason`:
+/// `screen_capture_failed` (SCKit didn't start), `headphones_no_loopback` (the other side
+/// plays through headphones the mic can't hear) or `no_loopback_device`.
+fn report_system_audio_unavailable(app_handle: &tauri::AppHandle, host: &cpal::Host, screen_capture_error: Option<&str>) {
+  let output_device = host.default_output_device().and_then(|d| d.name().ok());
+  let headphones = output_device.as_deref().map_or(false, is_headphones);
+  let (reason, hint) = match (screen_capture_error, headphones) {
+    (Some(_), _) => (
+      "screen_capture_failed",
+      "System audio can't be captured. Allow Oatmeal under Privacy & Security → Screen Recording, or set up a Multi-Output device with a loopback driver.",
+    ),
+    (None, true) => (
+      "headphones_no_loopback",
+      "Audio is playing through headphones, so only your side is recorded. Set up a Multi-Output device with a loopback driver to capture the other side.",
+    ),
+    (None, false) => (
+      "no_loopback_device",
+      "Only the microphone is recorded. Set up a Multi-Output device with a loopback driver to capture system audio.",
+    ),
+  };
+  warn!("⚠️ System audio unavailable ({}): recording microphone only", reason);
+  let _ = app_handle.emit_all("recording:system-audio-unavailable", serde_json::json!({
+    "reason": reason,
+    "hint": hint,
+    "url": LOOPBACK_SETUP_URL,
+    "output_device": output_device,
+    "error": screen_capture_error,
+  }));
+}
+
+/// Names of the connected input devices, e.g. for choosing the inputs of a two-track
+/// recording.
+pub fn input_device_names() -> Vec<String> {
+  cpal::default_host()
+    .input_devices()
+    .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+    .unwrap_or_default()
+}
+
+/// The input device called `name` and its default config, if it's connected.
+fn find_input(host: &cpal::Host, name: &str) -> Option<(cpal::Device, cpal::SupportedStreamConfig)> {
+  let device = host.input_devices().ok()?.find(|d| d.name().map(|n| n == name).unwrap_or(false))?;
+  match device.default_input_config() {
+    Ok(config) => Some((device, config)),
+    Err(e) => {
+      error!("Failed to get default input config for {}: {}", name, e);
+      None
+    }
+  }
+}
+
+/// Selected input device and its default config.
+fn open_input(
+  host: &cpal::Host,
+  force_microphone: bool,
+  prefer_builtin_mic: bool,
+  preferred: Option<&str>,
+) -> Option<(cpal::Device, cpal::SupportedStreamConfig)> {
+  select_input_device(host, force_microphone, prefer_builtin_mic, preferred).and_then(|(device, _source)| {
+    match device.default_input_config() {
+      Ok(config) => Some((device, config)),
+      Err(e) => {
+        error!("Failed to get default input config: {}", e);
+        None
+      }
+    }
+  })
+}
+
+/// Result of a `mic_check`; levels are of the mono downmix, in 0.0-1.0.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MicCheck {
+  pub device_name: String,
+  pub sample_rate: u32,
+  pub channels: u16,
+  pub peak: f32,
+  pub rms: f32,
+  pub clipped_samples: u64,
 }
 
+/// Levels of the system-audio feed during a `system_audio_check`, in 0.0-1.0.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SourceLevels {
+  pub sample_rate: u32,
+  pub peak: f32,
+  pub rms: f32,
+  pub clipped_samples: u64,
+}
+
+// Samples at or above this are counted as clipped
+const CLIP_LEVEL: f32 = 0.99;
+
+/// Everything that arrives on `rx` within `duration`. Drains as it goes; the channel would
+/// fill up long before a 15s check ends.
+fn collect_samples(rx: &channel::Receiver<Vec<f32>>, duration: Duration) -> Vec<f32> {
+  let deadline = std::time::Instant::now() + duration;
+  let mut samples: Vec<f32> = Vec::new();
+  while let Some(remaining) = deadline.checked_duration_since(std::time::Instant::now()) {
+    if let Ok(chunk) = rx.recv_timeout(remaining) {
+      samples.extend(chunk);
+    }
+  }
+  samples
+}
+
+/// (peak, rms, clipped samples), or None for no samples at all.
+fn measure_levels(samples: &[f32]) -> Option<(f32, f32, u64)> {
+  if samples.is_empty() {
+    return None;
+  }
+  let (mut peak, mut sum_squares, mut clipped_samples) = (0.0f32, 0.0f64, 0u64);
+  for &s in samples {
+    let level = s.abs();
+    peak = peak.max(level);
+    sum_squares += (s as f64) * (s as f64);
+    if level >= CLIP_LEVEL { clipped_samples += 1; }
+  }
+  Some((peak, (sum_squares / samples.len() as f64).sqrt() as f32, clipped_samples))
+}
+
+/// Measure a system-audio feed that's already running (its frames arrive on `rx`) for
+/// `duration`. Blocks the calling thread.
+pub fn system_audio_check(rx: channel::Receiver<Vec<f32>>, sample_rate: u32, duration: Duration) -> Result<SourceLevels, String> {
+  let samples = collect_samples(&rx, duration);
+  let (peak, rms, clipped_samples) = measure_levels(&samples)
+    .ok_or_else(|| "No system audio was received; check Screen Recording permission".to_string())?;
+  Ok(SourceLevels { sample_rate, peak, rms, clipped_samples })
+}
+
+/// Open the input a recording would use (the mic, never a loopback device), capture for
+/// `duration` and report levels. Blocks the calling thread; nothing is emitted or saved,
+/// and the stream is dropped before returning.
+pub fn mic_check(prefer_builtin_mic: bool, duration: Duration) -> Result<MicCheck, String> {
+  let host = cpal::default_host();
+  let (device, config) = open_input(&host, true, prefer_builtin_mic, None)
+    .ok_or_else(|| "No microphone or other input device is available".to_string())?;
+  let device_name = device.name().unwrap_or_else(|_| "Unknown device".to_string());
+  let sample_format = config.sample_format();
+  let config: cpal::StreamConfig = config.into();
+  let sample_rate = config.sample_rate.0;
+
+  let (tx, rx) = channel::bounded::<Vec<f32>>(256);
+  let is_capturing = Arc::new(AtomicBool::new(true));
+  let stream = build_mono_input_stream(
+    &device,
+    &config,
+    sample_format,
+    sample_rate,
+    is_capturing.clone(),
+    SampleSink::new(tx, Arc::default(), "mic"),
+    || {},
+  )?;
+  stream.play().map_err(|e| format!("Failed to start input stream: {}", e))?;
+
+  let samples = collect_samples(&rx, duration);
+  is_capturing.store(false, Ordering::Relaxed);
+  drop(stream);
+
+  let (peak, rms, clipped_samples) = measure_levels(&samples)
+    .ok_or_else(|| format!("No audio was received from {}; check microphone permission", device_name))?;
+  Ok(MicCheck {
+    device_name,
+    sample_rate,
+    channels: config.channels,
+    peak,
+    rms,
+    clipped_samples,
+  })
+}
+
+fn device_lost_notifier(tx: &Sender<Command>, generation: u64) -> impl Fn() + Send + 'static {
+  let tx = tx.clone();
+  move || {
+    let _ = tx.send(Command::DeviceLost(generation));
+  }
+}
+
+/// Try to bring capture back on whatever input is now available, feeding the existing
+/// pipeline. The lost device is skipped on all but the last attempt, since it can linger
+/// as the default for a moment after disconnecting.
+fn recover_input(
+  live: &LiveInput,
+  is_capturing: Arc<AtomicBool>,
+  commands: &Sender<Command>,
+  generation: u64,
+) -> Result<(cpal::Stream, String), String> {
+  let host = cpal::default_host();
+  let mut last_error = "No input device available".to_string();
+  for attempt in 0..RECOVERY_ATTEMPTS {
+    if attempt > 0 {
+      thread::sleep(RECOVERY_DELAY);
+    }
+    let (device, config) = match open_input(&host, live.force_microphone, live.prefer_builtin_mic, None) {
+      Some(selected) => selected,
+      None => continue,
+    };
+    let name = device.name().unwrap_or_else(|_| "Unknown device".to_string());
+    if name == live.device_name && attempt + 1 < RECOVERY_ATTEMPTS {
+      continue;
+    }
+    let sample_format = config.sample_format();
+    let config: cpal::StreamConfig = config.into();
+    let built = build_mono_input_stream(
+      &device,
+      &config,
+      sample_format,
+      live.sample_rate,
+      is_capturing.clone(),
+      live.sink.clone(),
+      device_lost_notifier(commands, generation),
+    );
+    match built.and_then(|stream| stream.play().map(|_| stream).map_err(|e| e.to_string())) {
+      Ok(stream) => return Ok((stream, name)),
+      Err(e) => last_error = e,
+    }
+  }
+  Err(last_error)
+}
+
+/// Build the aggregator for one output stream. `record` marks the stream that is saved
+/// to `options.record_path`, if recording is enabled.
+fn make_aggregator(
+  app_handle: tauri::AppHandle,
+  options: &CaptureOptions,
+  sample_rate: u32,
+  source: Option<&'static str>,
+  record: bool,
+) -> FrameAggregator {
+  let mut aggregator = FrameAggregator::new(app_handle, options.frame_encoding, sample_rate);
+  if let Some(frame_ms) = options.frame_ms {
+    aggregator = aggregator.with_frame_ms(frame_ms);
+  }
+  if let Some(gain) = options.input_gain {
+    aggregator = aggregator.with_gain(gain);
+  }
+  aggregator = aggregator.with_pause(options.paused.clone());
+  aggregator = aggregator.with_silence_watch(options.silence_watch.clone());
+  if let Some(source) = source {
+    aggregator = aggregator.with_source(source);
+  }
+  if let (true, Some(path)) = (record, options.record_path.as_deref()) {
+    match WavRecorder::create(path, sample_rate) {
+      Ok(recorder) => aggregator = aggregator.with_recorder(Some(recorder)),
+      Err(e) => warn!("Audio will not be saved: {}", e),
+    }
+  }
+  aggregator
+}
+
+/// Start track 2 of a two-track recording: a stream of its own, framed as `track2` and
+/// saved to its own WAV, never mixed with track 1.
+fn start_second_track(
+  app_handle: tauri::AppHandle,
+  options: &CaptureOptions,
+  device: &cpal::Device,
+  config: cpal::SupportedStreamConfig,
+  is_capturing: Arc<AtomicBool>,
+) -> Result<cpal::Stream, String> {
+  let name = device.name().unwrap_or_else(|_| "Unknown device".to_string());
+  let sample_format = config.sample_format();
+  let config: cpal::StreamConfig = config.into();
+  let sample_rate = config.sample_rate.0;
+  let (tx_samples, rx_samples) = channel::bounded::<Vec<f32>>(256);
+
+  let mut aggregator = make_aggregator(app_handle, options, sample_rate, Some("track2"), false).with_track(2);
+  if let Some(path) = options.tracks.as_ref().and_then(|t| t.second_record_path.as_deref()) {
+    match WavRecorder::create(path, sample_rate) {
+      Ok(recorder) => aggregator = aggregator.with_recorder(Some(recorder)),
+      Err(e) => warn!("Track 2 audio will not be saved: {}", e),
+    }
+  }
+  let is_capturing_emit = is_capturing.clone();
+  spawn_aggregator(aggregator, rx_samples, move || is_capturing_emit.load(Ordering::Relaxed));
+
+  // Track 1 keeps recording if this one goes away; there's no sensible replacement to pick
+  let lost_name = name.clone();
+  let stream = build_mono_input_stream(
+    device,
+    &config,
+    sample_format,
+    sample_rate,
+    is_capturing,
+    SampleSink::new(tx_samples, options.drops.clone(), "track2"),
+    move || warn!("Track 2 input {} was disconnected", lost_name),
+  )?;
+  stream.play().map_err(|e| format!("Failed to start track 2 input: {}", e))?;
+  info!("🎙️ Track 2 capturing from {}", name);
+  Ok(stream)
+}
+
+/// Owns the cpal stream on a dedicated thread (cpal streams are not `Send`) and
+/// emits `audio:frame` events while capturing. When a system-audio feed is passed in
+/// `CaptureOptions`, mic and system are mixed here and emitted as a single stream.
 pub struct AudioRuntime {
   tx: Sender<Command>,
   is_capturing: Arc<AtomicBool>,
+  paused: Arc<AtomicBool>,
+  /// The current (or last) recording's input disconnected at some point
+  input_lost: Arc<AtomicBool>,
 }
 
 impl AudioRuntime {
@@ -43,379 +497,312 @@ impl AudioRuntime {
     let (tx, rx): (Sender<Command>, Receiver<Command>) = mpsc::channel();
     let is_capturing = Arc::new(AtomicBool::new(false));
     let is_capturing_worker = is_capturing.clone();
+    let paused = Arc::new(AtomicBool::new(false));
+    let paused_worker = paused.clone();
+    let input_lost = Arc::new(AtomicBool::new(false));
+    let input_lost_worker = input_lost.clone();
+
+    let commands = tx.clone();
 
     thread::spawn(move || {
-      // State owned by the worker thread only
-      enum ActiveStream { Single(cpal::Stream), Mixed(cpal::Stream, cpal::Stream) }
-      let mut stream: Option<ActiveStream> = None;
+      let mut stream: Option<cpal::Stream> = None;
+      // Track 2 of a two-track recording
+      let mut second_stream: Option<cpal::Stream> = None;
+      let mut live: Option<LiveInput> = None;
+      let mut generation: u64 = 0;
 
-      // Inner function to start capture with given app handle
-      const ENABLE_MIXED_CAPTURE: bool = false; // Disabled for now - using SCKit + AirPods Pro separately
       let start_capture = |app_handle: tauri::AppHandle,
-                           force_microphone: bool,
+                           mut options: CaptureOptions,
                            is_capturing_flag: Arc<AtomicBool>,
-                           stream_slot: &mut Option<ActiveStream>| {
+                           stream_slot: &mut Option<cpal::Stream>,
+                           second_slot: &mut Option<cpal::Stream>,
+                           live_slot: &mut Option<LiveInput>,
+                           generation: u64| -> Result<Option<InputChoice>, String> {
         if is_capturing_flag.load(Ordering::Relaxed) {
-          return; // already capturing
+          return Err("Audio capture is already running".to_string());
         }
         is_capturing_flag.store(true, Ordering::Relaxed);
+        paused_worker.store(false, Ordering::Relaxed);
+        options.paused = paused_worker.clone();
+
+        let is_capturing_drops = is_capturing_flag.clone();
+        spawn_drop_reporter(app_handle.clone(), options.drops.clone(), move || is_capturing_drops.load(Ordering::Relaxed));
 
-        // Automatic device selection based on Mac's current audio setup
         let host = cpal::default_host();
-        
-        // First, check if there are headphones or external audio devices connected
-        // Allow override via settings (force microphone)
-        // Discover devices - prioritize aggregate/system audio devices
-        let mic_device = host.default_input_device();
-        let mut loopback_device = None;
-        let mut airpods_mic_device = None;
-        
-        if let Ok(devices) = host.input_devices() {
-          // Single pass through devices to find what we need
-          for device in devices {
-            if let Ok(name) = device.name() {
-              let nl = name.to_lowercase();
-              println!("Available input device: {}", name);
-              
-              // Check for AirPods Pro microphone
-              if nl.contains("airpods") && airpods_mic_device.is_none() {
-                println!("🎧 Found AirPods microphone: {}", name);
-                airpods_mic_device = Some(device.clone());
-              }
-              
-              // Look for system audio devices (prioritize aggregate, AVOID BlackHole)
-              if loopback_device.is_none() && !nl.contains("blackhole") {
-                if nl.contains("aggregate") {
-                  println!("Found aggregate device (preferred): {}", name);
-                  loopback_device = Some(device);
-                } else if nl.contains("multi-output") || nl.contains("soundflower") || 
-                         nl.contains("loopback") || nl.contains("virtual") || nl.contains("system") {
-                  println!("Found system audio device: {}", name);
-                  loopback_device = Some(device);
-                }
-              }
-              
-              // Explicitly skip BlackHole devices
-              if nl.contains("blackhole") {
-                println!("❌ Skipping BlackHole device: {}", name);
+        // With a system-audio feed to mix in, the cpal side must be the mic, not a loopback device
+        let force_microphone = options.force_microphone || options.system_audio.is_some();
+        // Only worth it when SCKit has the call audio; otherwise the headset mic is the only voice path
+        let prefer_builtin_mic = options.prefer_builtin_mic && options.system_audio.is_some();
+        // A device picked for a mixed recording isn't necessarily right for a single-source one
+        let remembered = options
+          .remembered_input
+          .as_ref()
+          .filter(|choice| choice.mixed == options.system_audio.is_some())
+          .map(|choice| choice.device_name.as_str());
+        // Both tracks must be there before anything starts; half an interview isn't useful
+        let (selected, second_track) = match options.tracks.as_ref() {
+          Some(tracks) => {
+            let first = find_input(&host, &tracks.first);
+            let second = find_input(&host, &tracks.second);
+            for (number, name, found) in [(1, &tracks.first, first.is_some()), (2, &tracks.second, second.is_some())] {
+              if !found {
+                is_capturing_flag.store(false, Ordering::Relaxed);
+                return Err(format!("Track {} input {} is not connected", number, name));
               }
             }
+            (first, second)
           }
-        }
-        
-        // Check if we should use system audio vs microphone
-        // For AirPods Pro: we want MIXED capture (both mic + system audio)
-        let headphones_detected = detect_headphones_or_external_audio(&host);
-        let mut should_use_system_audio = headphones_detected && !force_microphone;
-        
-        // For mixed capture: prioritize AirPods Pro mic + system audio via SCKit
-        println!("🎧 Audio setup: AirPods Pro detected={}, System audio device available={}", 
-                 airpods_mic_device.is_some(), loopback_device.is_some());
-        
-        // If no loopback device found but headphones are detected, provide helpful guidance
-        if should_use_system_audio && loopback_device.is_none() {
-          println!("🎧 Headphones detected. System audio capture will use ScreenCaptureKit when available.");
-          println!("If system capture is unavailable or denied, falling back to microphone.");
-        }
+          None => (open_input(&host, force_microphone, prefer_builtin_mic, remembered), None),
+        };
 
-        // Simple approach: Use AirPods Pro microphone when available
-        // SCKit will handle system audio separately, and UI will mix them
-        if airpods_mic_device.is_some() && should_use_system_audio {
-          println!("🎵 Using AirPods Pro microphone + ScreenCaptureKit system audio (mixed in UI)");
-          should_use_system_audio = false; // Use microphone for CPAL, system audio via SCKit
-        }
+        // Callback -> aggregator/mixer; ~2s of headroom at typical callback sizes
+        let (tx_samples, rx_samples) = channel::bounded::<Vec<f32>>(256);
 
-        // Single-device selection fallback
-        let (device, _actual_source) = if should_use_system_audio {
-          match loopback_device {
-            Some(d) => {
-              if let Ok(name) = d.name() {
-                println!("✅ Using system audio device: {}", name);
-                if name.to_lowercase().contains("aggregate") {
-                  println!("🎯 Perfect! Aggregate device will capture system audio + mic together");
-                }
+        let (device, config) = match selected {
+          Some(selected) => selected,
+          None => match options.system_audio.clone() {
+            Some(system) => {
+              // No usable mic: still capture the call audio on its own
+              warn!("No input device available; capturing system audio only");
+              drop(tx_samples);
+              let is_capturing_system = is_capturing_flag.clone();
+              let is_running = move || is_capturing_system.load(Ordering::Relaxed);
+              if options.separate_sources {
+                let aggregator = make_aggregator(app_handle, &options, system.sample_rate, Some("system"), true);
+                spawn_aggregator(aggregator, system.rx, is_running);
+              } else {
+                let aggregator = make_aggregator(app_handle, &options, MIX_SAMPLE_RATE, None, true);
+                spawn_mixer(aggregator, options.processing, rx_samples, MIX_SAMPLE_RATE, system.rx, system.sample_rate, is_running);
               }
-              (d, AudioSource::SystemAudio)
+              return Ok(None);
             }
             None => {
-              println!("Headphones detected but no system audio capture available - using microphone only");
-              println!("To capture both your voice AND system audio (for calls/meetings):");
-              println!("1. Open Audio MIDI Setup (Spotlight -> 'Audio MIDI Setup')");
-              println!("2. Click '+' and create 'Multi-Output Device'");
-              println!("3. Check both 'BlackHole 16ch' and your headphones");
-              println!("4. Set this Multi-Output as your system output in System Preferences");
-              println!("5. In Oatmeal, BlackHole will capture system audio while your headphones play it");
-              match mic_device {
-                Some(d) => (d, AudioSource::Microphone),
-                None => { eprintln!("No input device available"); is_capturing_flag.store(false, Ordering::Relaxed); return; }
-              }
-            }
-          }
-        } else {
-          println!("Audio going to speakers or using AirPods Pro microphone");
-          // Prefer AirPods Pro microphone if available, otherwise default mic
-          match airpods_mic_device.or(mic_device) {
-            Some(d) => {
-              if let Ok(name) = d.name() {
-                println!("✅ Using microphone: {}", name);
-              }
-              (d, AudioSource::Microphone)
-            },
-            None => { 
-              eprintln!("No input device available"); 
-              is_capturing_flag.store(false, Ordering::Relaxed); 
-              return; 
+              error!("No default input device available");
+              is_capturing_flag.store(false, Ordering::Relaxed);
+              return Err("No microphone or other input device is available".to_string());
             }
-          }
+          },
         };
 
-        // Config selection (prefer 16k mono if supported)
-        let desired_rate = cpal::SampleRate(16000);
-        let mut chosen_config: Option<cpal::SupportedStreamConfig> = None;
-        if let Ok(configs) = device.supported_input_configs() {
-          for cfg in configs {
-            if cfg.channels() == 1
-              && cfg.min_sample_rate() <= desired_rate
-              && cfg.max_sample_rate() >= desired_rate
-            {
-              chosen_config = Some(cfg.with_sample_rate(desired_rate));
-              break;
-            }
-          }
-        }
-        if chosen_config.is_none() {
-          if let Ok(default_cfg) = device.default_input_config() {
-            chosen_config = Some(default_cfg);
+        let device_name = device.name().unwrap_or_else(|_| "Unknown device".to_string());
+        let sample_format = config.sample_format();
+        let config: cpal::StreamConfig = config.into();
+        let sample_rate = config.sample_rate.0;
+        debug!("Audio config: {} Hz, {} channels", sample_rate, config.channels);
+        let has_system_audio = options.system_audio.is_some();
+        let rx_samples = match options.debug_raw_path.as_deref().map(|path| WavRecorder::create(path, sample_rate)) {
+          Some(Ok(recorder)) => spawn_tee(rx_samples, recorder, options.paused.clone()),
+          Some(Err(e)) => {
+            warn!("Raw debug audio will not be saved: {}", e);
+            rx_samples
           }
+          None => rx_samples,
+        };
+        let choice = InputChoice { device_name: device_name.clone(), mixed: has_system_audio };
+        // Forcing the mic or recording tracks is an explicit choice; don't nag about it
+        if !has_system_audio && !options.force_microphone && options.tracks.is_none() && !is_loopback_device(&device_name) {
+          report_system_audio_unavailable(&app_handle, &host, options.system_audio_error.as_deref());
         }
-        let supported = match chosen_config {
-          Some(c) => c,
-          None => {
-            eprintln!("Failed to determine input config");
-            is_capturing_flag.store(false, Ordering::Relaxed);
-            return;
-          }
+        let live_input = LiveInput {
+          app_handle: app_handle.clone(),
+          sink: SampleSink::new(tx_samples.clone(), options.drops.clone(), "mic"),
+          sample_rate,
+          force_microphone,
+          prefer_builtin_mic,
+          has_system_audio,
+          device_name,
+          generation,
+          on_capture_lost: options.on_capture_lost.clone(),
         };
 
-        let sample_format = supported.sample_format();
-        let config: cpal::StreamConfig = supported.into();
-        let channels = config.channels as usize;
-        let sample_rate = config.sample_rate.0 as usize;
-
-        // Channel to move samples out of realtime callback
-        let (tx_samp, rx_samp) = channel::bounded::<f32>(sample_rate * 2);
-
-        // Aggregator thread to form ~20ms frames and emit
         let is_capturing_emit = is_capturing_flag.clone();
-        let app_handle_emit = app_handle.clone();
-        thread::spawn(move || {
-          let frame_len = (sample_rate / 50).max(1);
-          let mut buf: Vec<f32> = Vec::with_capacity(frame_len * 2);
-          let mut frames_emitted = 0u64;
-          let mut samples_received = 0u64;
-          
-          println!("📡 Aggregator started: frame_len={}, target_rate={}", frame_len, sample_rate);
-
-          while is_capturing_emit.load(Ordering::Relaxed) {
-            match rx_samp.recv() {
-              Ok(s) => {
-                buf.push(s);
-                samples_received += 1;
-              },
-              Err(_) => break,
-            }
-            while let Ok(s) = rx_samp.try_recv() {
-              buf.push(s);
-              samples_received += 1;
-              if buf.len() >= frame_len { break; }
-            }
-            while buf.len() >= frame_len {
-              let frame: Vec<f32> = buf.drain(0..frame_len).collect();
-              
-              // Check if frame has any activity
-              let max_amplitude = frame.iter().map(|&s| s.abs()).fold(0.0f32, f32::max);
-              
-              frames_emitted += 1;
-              if frames_emitted % 50 == 0 {
-                println!("📡 Aggregator: {} frames emitted, {} samples received, last frame max amplitude: {:.4}", 
-                         frames_emitted, samples_received, max_amplitude);
-              }
-              
-              let _ = app_handle_emit.emit_all(
-                "audio:frame",
-                serde_json::json!({
-                  "data": frame,
-                  "timestamp": std::time::SystemTime::now()
-                      .duration_since(std::time::UNIX_EPOCH)
-                      .unwrap()
-                      .as_millis(),
-                  "sample_rate": sample_rate as u32
-                }),
-              );
-            }
+        let is_running = move || is_capturing_emit.load(Ordering::Relaxed);
+        let track_app_handle = app_handle.clone();
+        match options.system_audio.clone() {
+          Some(system) if options.separate_sources => {
+            // "Me" vs "them": each stream is framed and transcribed independently
+            let is_capturing_system = is_capturing_flag.clone();
+            let system_aggregator = make_aggregator(app_handle.clone(), &options, system.sample_rate, Some("system"), false);
+            spawn_aggregator(system_aggregator, system.rx, move || is_capturing_system.load(Ordering::Relaxed));
+            let mic_aggregator = make_aggregator(app_handle, &options, sample_rate, Some("mic"), true);
+            spawn_aggregator(mic_aggregator, rx_samples, is_running);
           }
-          // flush
-          if !buf.is_empty() {
-            let _ = app_handle_emit.emit_all(
-              "audio:frame",
-              serde_json::json!({
-                "data": buf,
-                "timestamp": std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis(),
-                "sample_rate": sample_rate as u32
-              }),
-            );
+          Some(system) => {
+            let aggregator = make_aggregator(app_handle, &options, MIX_SAMPLE_RATE, None, true);
+            spawn_mixer(aggregator, options.processing, rx_samples, sample_rate, system.rx, system.sample_rate, is_running);
           }
-        });
-
-        // Build CPAL stream with debug logging
-        let build_stream = |sample_format: cpal::SampleFormat| -> Result<cpal::Stream, String> {
-          match sample_format {
-            cpal::SampleFormat::F32 => {
-              let is_capturing_cb = is_capturing_flag.clone();
-              let sample_count = Arc::new(AtomicU64::new(0));
-              let non_zero_samples = Arc::new(AtomicU64::new(0));
-              let sample_count_cb = sample_count.clone();
-              let non_zero_samples_cb = non_zero_samples.clone();
-              device
-              .build_input_stream(
-                &config,
-                move |data: &[f32], _| {
-                  if !is_capturing_cb.load(Ordering::Relaxed) { return; }
-                  let prev_count = sample_count_cb.fetch_add(data.len() as u64, Ordering::Relaxed);
-                  let new_count = prev_count + data.len() as u64;
-                  
-                  if channels == 1 {
-                    for &s in data { 
-                      if s.abs() > 0.001 { 
-                        non_zero_samples_cb.fetch_add(1, Ordering::Relaxed);
-                      }
-                      let _ = tx_samp.try_send(s); 
-                    }
-                  } else {
-                    for frame in data.chunks_exact(channels) {
-                      let sum: f32 = frame.iter().copied().sum();
-                      let avg = sum / channels as f32;
-                      if avg.abs() > 0.001 { 
-                        non_zero_samples_cb.fetch_add(1, Ordering::Relaxed);
-                      }
-                      let _ = tx_samp.try_send(avg);
-                    }
-                  }
-                  
-                  // Log every 16000 samples (1 second at 16kHz)
-                  if new_count / 16000 > prev_count / 16000 {
-                    let nz = non_zero_samples_cb.load(Ordering::Relaxed);
-                    println!("🎤 Audio samples: {} total, {} non-zero (activity: {:.1}%)", 
-                             new_count, nz, 
-                             (nz as f32 / new_count as f32) * 100.0);
-                  }
-                },
-                move |err| { eprintln!("Audio input stream error: {}", err); },
-                None,
-              )
-              .map_err(|e| format!("build_input_stream (f32) failed: {}", e))
-            },
-            cpal::SampleFormat::I16 => {
-              let is_capturing_cb = is_capturing_flag.clone();
-              device
-              .build_input_stream(
-                &config,
-                move |data: &[i16], _| {
-                  if !is_capturing_cb.load(Ordering::Relaxed) { return; }
-                  if channels == 1 {
-                    for &s in data { let _ = tx_samp.try_send(s as f32 / i16::MAX as f32); }
-                  } else {
-                    for frame in data.chunks_exact(channels) {
-                      let mut sum = 0.0f32;
-                      for &s in frame { sum += s as f32 / i16::MAX as f32; }
-                      let avg = sum / channels as f32;
-                      let _ = tx_samp.try_send(avg);
-                    }
-                  }
-                },
-                move |err| { eprintln!("Audio input stream error: {}", err); },
-                None,
-              )
-              .map_err(|e| format!("build_input_stream (i16) failed: {}", e))
-            },
-            cpal::SampleFormat::U16 => {
-              let is_capturing_cb = is_capturing_flag.clone();
-              device
-              .build_input_stream(
-                &config,
-                move |data: &[u16], _| {
-                  if !is_capturing_cb.load(Ordering::Relaxed) { return; }
-                  let to_f32 = |v: u16| (v as f32 / u16::MAX as f32) * 2.0 - 1.0;
-                  if channels == 1 {
-                    for &s in data { let _ = tx_samp.try_send(to_f32(s)); }
-                  } else {
-                    for frame in data.chunks_exact(channels) {
-                      let mut sum = 0.0f32;
-                      for &s in frame { sum += to_f32(s); }
-                      let avg = sum / channels as f32;
-                      let _ = tx_samp.try_send(avg);
-                    }
-                  }
-                },
-                move |err| { eprintln!("Audio input stream error: {}", err); },
-                None,
-              )
-              .map_err(|e| format!("build_input_stream (u16) failed: {}", e))
-            },
-            _ => Err("Unsupported sample format".to_string()),
+          None if options.tracks.is_some() => {
+            let aggregator = make_aggregator(app_handle, &options, sample_rate, Some("track1"), true).with_track(1);
+            spawn_aggregator(aggregator, rx_samples, is_running);
           }
-        };
+          None => {
+            let source = if options.separate_sources { Some("mic") } else { None };
+            let aggregator = make_aggregator(app_handle, &options, sample_rate, source, true);
+            spawn_aggregator(aggregator, rx_samples, is_running);
+          }
+        }
+
+        let stream_result = build_mono_input_stream(
+          &device,
+          &config,
+          sample_format,
+          sample_rate,
+          is_capturing_flag.clone(),
+          SampleSink::new(tx_samples, options.drops.clone(), "mic"),
+          device_lost_notifier(&commands, generation),
+        );
 
-        match build_stream(sample_format) {
+        match stream_result {
           Ok(s) => {
             if let Err(e) = s.play() {
-              eprintln!("Failed to start input stream: {}", e);
+              error!("Failed to start input stream: {}", e);
               is_capturing_flag.store(false, Ordering::Relaxed);
-              return;
+              return Err(format!("Failed to start input stream: {}", e));
             }
-            *stream_slot = Some(ActiveStream::Single(s));
-            println!("Started real audio capture ({} Hz, {} ch)", sample_rate, channels);
+            *stream_slot = Some(s);
+            *live_slot = Some(live_input);
+            if let Some((device, config)) = second_track {
+              match start_second_track(track_app_handle, &options, &device, config, is_capturing_flag.clone()) {
+                Ok(s) => *second_slot = Some(s),
+                Err(e) => {
+                  error!("Failed to start track 2: {}", e);
+                  is_capturing_flag.store(false, Ordering::Relaxed);
+                  *stream_slot = None;
+                  *live_slot = None;
+                  return Err(e);
+                }
+              }
+              info!("Two-track capture started successfully");
+              // Track inputs are chosen in settings, not remembered
+              return Ok(None);
+            }
+            info!("Audio capture started successfully");
+            Ok(Some(choice))
           }
           Err(e) => {
-            eprintln!("{}", e);
+            error!("Failed to build input stream: {}", e);
             is_capturing_flag.store(false, Ordering::Relaxed);
-            return;
+            Err(e)
           }
         }
       };
 
-      let stop_capture = |is_capturing_flag: Arc<AtomicBool>, stream_slot: &mut Option<ActiveStream>| {
+      let stop_capture = |is_capturing_flag: Arc<AtomicBool>,
+                          stream_slot: &mut Option<cpal::Stream>,
+                          second_slot: &mut Option<cpal::Stream>,
+                          live_slot: &mut Option<LiveInput>| {
         is_capturing_flag.store(false, Ordering::Relaxed);
-        *stream_slot = None; // drop stream; aggregator will also stop
-        println!("Stopped real audio capture");
+        *stream_slot = None; // drop stream; aggregator flushes and exits
+        *second_slot = None;
+        *live_slot = None;
+        info!("Audio capture stopped");
+      };
+
+      // Rebuild the mic stream after a disconnect. Without a replacement, keep going on
+      // system audio alone if there is any, otherwise stop so the UI isn't left "recording".
+      let handle_device_lost = |is_capturing_flag: Arc<AtomicBool>,
+                                stream_slot: &mut Option<cpal::Stream>,
+                                live_slot: &mut Option<LiveInput>,
+                                generation: u64| {
+        let lost = match live_slot.as_mut() {
+          Some(lost) if is_capturing_flag.load(Ordering::Relaxed) => lost,
+          _ => return,
+        };
+        warn!("Input device lost: {}", lost.device_name);
+        *stream_slot = None;
+
+        match recover_input(lost, is_capturing_flag.clone(), &commands, generation) {
+          Ok((new_stream, name)) => {
+            info!("Recovered audio capture on: {}", name);
+            let _ = lost.app_handle.emit_all("recording:device-lost", serde_json::json!({
+              "device": lost.device_name,
+              "recovered": true,
+              "new_device": name,
+              "capturing": true,
+            }));
+            lost.device_name = name;
+            lost.generation = generation;
+            *stream_slot = Some(new_stream);
+          }
+          Err(e) => {
+            error!("Could not re-acquire an input device: {}", e);
+            // Dropping our sender closes the mic channel; a mixer carries on with system audio
+            let lost = live_slot.take().unwrap();
+            if !lost.has_system_audio {
+              is_capturing_flag.store(false, Ordering::Relaxed);
+            }
+            let _ = lost.app_handle.emit_all("recording:device-lost", serde_json::json!({
+              "device": lost.device_name,
+              "recovered": false,
+              "error": e,
+              "capturing": lost.has_system_audio,
+            }));
+            if !lost.has_system_audio {
+              if let Some(on_lost) = &lost.on_capture_lost {
+                on_lost.notify();
+              }
+            }
+          }
+        }
       };
 
       // Command loop
       while let Ok(cmd) = rx.recv() {
         match cmd {
-          Command::Start(app_handle, force_mic) => start_capture(app_handle, force_mic, is_capturing_worker.clone(), &mut stream),
-          Command::Stop => stop_capture(is_capturing_worker.clone(), &mut stream),
+          Command::Start(app_handle, options, reply) => {
+            generation += 1;
+            input_lost_worker.store(false, Ordering::Relaxed);
+            let result = start_capture(app_handle, options, is_capturing_worker.clone(), &mut stream, &mut second_stream, &mut live, generation);
+            let _ = reply.send(result);
+          }
+          Command::Stop => stop_capture(is_capturing_worker.clone(), &mut stream, &mut second_stream, &mut live),
+          Command::DeviceLost(lost_generation) => {
+            if live.as_ref().map(|l| l.generation) != Some(lost_generation) {
+              continue;
+            }
+            generation += 1;
+            input_lost_worker.store(true, Ordering::Relaxed);
+            handle_device_lost(is_capturing_worker.clone(), &mut stream, &mut live, generation);
+          }
         }
       }
     });
 
-    Self { tx, is_capturing }
+    Self { tx, is_capturing, paused, input_lost }
   }
 
-  pub fn start(&self, app_handle: tauri::AppHandle, force_microphone: bool) -> Result<(), String> {
-    let _ = self.tx.send(Command::Start(app_handle, force_microphone)).map_err(|e| e.to_string())?;
-    Ok(())
+  /// Start capturing; resolves once the input stream is running or has failed to. A
+  /// device that doesn't open within `START_TIMEOUT` is reported as a failure too. Returns
+  /// the input that was opened, or `None` when only system audio is captured.
+  pub async fn start(&self, app_handle: tauri::AppHandle, options: CaptureOptions) -> Result<Option<InputChoice>, String> {
+    let (reply, started) = tokio::sync::oneshot::channel();
+    self.tx.send(Command::Start(app_handle, options, reply)).map_err(|e| e.to_string())?;
+    match tokio::time::timeout(START_TIMEOUT, started).await {
+      Ok(result) => result.map_err(|_| "Audio thread exited".to_string())?,
+      Err(_) => {
+        // Queued behind the start, so capture is torn down if the device opens late
+        let _ = self.tx.send(Command::Stop);
+        Err(format!("Audio input did not start within {}s", START_TIMEOUT.as_secs()))
+      }
+    }
+  }
+
+  /// While paused the streams stay open but captured audio is discarded.
+  pub fn set_paused(&self, paused: bool) {
+    self.paused.store(paused, Ordering::Relaxed);
   }
 
   pub fn stop(&self) -> Result<(), String> {
-    let _ = self.tx.send(Command::Stop).map_err(|e| e.to_string())?;
+    self.tx.send(Command::Stop).map_err(|e| e.to_string())?;
     Ok(())
   }
 
   pub fn is_capturing(&self) -> bool {
-    self.is_capturing.load(std::sync::atomic::Ordering::Relaxed)
+    self.is_capturing.load(Ordering::Relaxed)
+  }
+
+  /// Whether the most recent recording's input device disconnected while it ran.
+  pub fn input_lost(&self) -> bool {
+    self.input_lost.load(Ordering::Relaxed)
   }
 }
@@ -0,0 +1,79 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use cpal::traits::DeviceTrait;
+use super::drops::SampleSink;
+use super::resample::StreamResampler;
+use log::error;
+
+/// Average interleaved channels down to mono f32.
+fn downmix<T: Copy>(data: &[T], channels: usize, to_f32: impl Fn(T) -> f32) -> Vec<f32> {
+  if channels <= 1 {
+    return data.iter().map(|&s| to_f32(s)).collect();
+  }
+  data
+    .chunks_exact(channels)
+    .map(|frame| frame.iter().map(|&s| to_f32(s)).sum::<f32>() / channels as f32)
+    .collect()
+}
+
+/// Build a cpal input stream that downmixes every callback to mono f32 at `target_rate`
+/// (resampling if the device runs at a different rate) and hands the chunk to `sink`.
+/// Chunks are dropped (not blocked on) when the consumer falls behind, since the
+/// callback runs on the realtime audio thread; the sink counts them. `on_device_lost`
+/// fires if the device disappears (unplugged, Bluetooth disconnect) while running.
+pub fn build_mono_input_stream(
+  device: &cpal::Device,
+  config: &cpal::StreamConfig,
+  sample_format: cpal::SampleFormat,
+  target_rate: u32,
+  is_capturing: Arc<AtomicBool>,
+  sink: SampleSink,
+  on_device_lost: impl Fn() + Send + 'static,
+) -> Result<cpal::Stream, String> {
+  let channels = config.channels as usize;
+  let mut resampler = StreamResampler::new(config.sample_rate.0, target_rate);
+  let on_error = move |err| {
+    error!("Input stream error: {}", err);
+    if let cpal::StreamError::DeviceNotAvailable = err {
+      on_device_lost();
+    }
+  };
+
+  match sample_format {
+    cpal::SampleFormat::F32 => device
+      .build_input_stream(
+        config,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+          if !is_capturing.load(Ordering::Relaxed) { return; }
+          sink.push(resampler.process(&downmix(data, channels, |s| s)));
+        },
+        on_error,
+        None,
+      )
+      .map_err(|e| format!("build_input_stream (f32) failed: {}", e)),
+    cpal::SampleFormat::I16 => device
+      .build_input_stream(
+        config,
+        move |data: &[i16], _: &cpal::InputCallbackInfo| {
+          if !is_capturing.load(Ordering::Relaxed) { return; }
+          sink.push(resampler.process(&downmix(data, channels, |s| s as f32 / i16::MAX as f32)));
+        },
+        on_error,
+        None,
+      )
+      .map_err(|e| format!("build_input_stream (i16) failed: {}", e)),
+    cpal::SampleFormat::U16 => device
+      .build_input_stream(
+        config,
+        move |data: &[u16], _: &cpal::InputCallbackInfo| {
+          if !is_capturing.load(Ordering::Relaxed) { return; }
+          sink.push(resampler.process(&downmix(data, channels, |s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0)));
+        },
+        on_error,
+        None,
+      )
+      .map_err(|e| format!("build_input_stream (u16) failed: {}", e)),
+    other => Err(format!("Unsupported sample format: {:?}", other)),
+  }
+}
@@ -0,0 +1,168 @@
+//! What kind of hardware an audio device is, from Core Audio's transport type rather than
+//! its name. Names are localized ("MacBook Pro-Mikrofon", "Micrófono de MacBook Air") and
+//! third-party devices call themselves anything, so name matching is only the fallback
+//! when Core Audio can't answer (other platforms, or a device that vanished).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+  BuiltIn,
+  Bluetooth,
+  Usb,
+  /// Software devices: BlackHole, Loopback, Soundflower
+  Virtual,
+  /// Aggregate and Multi-Output devices
+  Aggregate,
+  /// HDMI/DisplayPort audio of an external display
+  Display,
+  Other,
+}
+
+/// Core Audio's view of a device, looked up by the name cpal reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceKind {
+  pub transport: Transport,
+  /// A built-in output currently routed to the headphone jack
+  pub headphones: bool,
+}
+
+#[cfg(target_os = "macos")]
+pub fn device_kind(name: &str) -> Option<DeviceKind> {
+  coreaudio::device_kind(name)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn device_kind(_name: &str) -> Option<DeviceKind> {
+  None
+}
+
+#[cfg(target_os = "macos")]
+mod coreaudio {
+  use std::ffi::c_void;
+  use std::os::raw::c_char;
+
+  use super::{DeviceKind, Transport};
+
+  const fn code(c: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*c)
+  }
+
+  // AudioHardware.h / AudioHardwareBase.h
+  const SYSTEM_OBJECT: u32 = 1;
+  const PROPERTY_DEVICES: u32 = code(b"dev#");
+  const PROPERTY_NAME: u32 = code(b"lnam");
+  const PROPERTY_TRANSPORT_TYPE: u32 = code(b"tran");
+  const PROPERTY_DATA_SOURCE: u32 = code(b"ssrc");
+  const SCOPE_GLOBAL: u32 = code(b"glob");
+  const SCOPE_OUTPUT: u32 = code(b"outp");
+  const ELEMENT_MAIN: u32 = 0;
+  const DATA_SOURCE_HEADPHONES: u32 = code(b"hdpn");
+  const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+  #[repr(C)]
+  struct PropertyAddress {
+    selector: u32,
+    scope: u32,
+    element: u32,
+  }
+
+  #[link(name = "CoreAudio", kind = "framework")]
+  extern "C" {
+    fn AudioObjectGetPropertyDataSize(
+      object: u32,
+      address: *const PropertyAddress,
+      qualifier_size: u32,
+      qualifier: *const c_void,
+      out_size: *mut u32,
+    ) -> i32;
+    fn AudioObjectGetPropertyData(
+      object: u32,
+      address: *const PropertyAddress,
+      qualifier_size: u32,
+      qualifier: *const c_void,
+      io_size: *mut u32,
+      out_data: *mut c_void,
+    ) -> i32;
+  }
+
+  #[link(name = "CoreFoundation", kind = "framework")]
+  extern "C" {
+    fn CFStringGetCString(string: *const c_void, buffer: *mut c_char, buffer_size: isize, encoding: u32) -> u8;
+    fn CFRelease(cf: *const c_void);
+  }
+
+  fn address(selector: u32, scope: u32) -> PropertyAddress {
+    PropertyAddress { selector, scope, element: ELEMENT_MAIN }
+  }
+
+  fn get_u32(object: u32, selector: u32, scope: u32) -> Option<u32> {
+    let address = address(selector, scope);
+    let mut value = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+      AudioObjectGetPropertyData(object, &address, 0, std::ptr::null(), &mut size, &mut value as *mut u32 as *mut c_void)
+    };
+    if status == 0 {
+      Some(value)
+    } else {
+      None
+    }
+  }
+
+  fn device_ids() -> Vec<u32> {
+    let address = address(PROPERTY_DEVICES, SCOPE_GLOBAL);
+    let mut size = 0u32;
+    if unsafe { AudioObjectGetPropertyDataSize(SYSTEM_OBJECT, &address, 0, std::ptr::null(), &mut size) } != 0 {
+      return Vec::new();
+    }
+    let mut ids = vec![0u32; size as usize / std::mem::size_of::<u32>()];
+    let status = unsafe {
+      AudioObjectGetPropertyData(SYSTEM_OBJECT, &address, 0, std::ptr::null(), &mut size, ids.as_mut_ptr() as *mut c_void)
+    };
+    if status != 0 {
+      return Vec::new();
+    }
+    ids.truncate(size as usize / std::mem::size_of::<u32>());
+    ids
+  }
+
+  fn device_name(device: u32) -> Option<String> {
+    let address = address(PROPERTY_NAME, SCOPE_GLOBAL);
+    let mut string: *const c_void = std::ptr::null();
+    let mut size = std::mem::size_of::<*const c_void>() as u32;
+    let status = unsafe {
+      AudioObjectGetPropertyData(device, &address, 0, std::ptr::null(), &mut size, &mut string as *mut *const c_void as *mut c_void)
+    };
+    if status != 0 || string.is_null() {
+      return None;
+    }
+    let mut buffer = [0 as c_char; 256];
+    let ok = unsafe { CFStringGetCString(string, buffer.as_mut_ptr(), buffer.len() as isize, CF_STRING_ENCODING_UTF8) };
+    // The property hands back a retained string
+    unsafe { CFRelease(string) };
+    if ok == 0 {
+      return None;
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+  }
+
+  fn transport(raw: u32) -> Transport {
+    match &raw.to_be_bytes() {
+      b"bltn" => Transport::BuiltIn,
+      b"blue" | b"blea" => Transport::Bluetooth,
+      b"usb " => Transport::Usb,
+      b"virt" => Transport::Virtual,
+      b"grup" => Transport::Aggregate,
+      b"hdmi" | b"dprt" => Transport::Display,
+      _ => Transport::Other,
+    }
+  }
+
+  pub fn device_kind(name: &str) -> Option<DeviceKind> {
+    let device = device_ids().into_iter().find(|&id| device_name(id).as_deref() == Some(name))?;
+    let transport = transport(get_u32(device, PROPERTY_TRANSPORT_TYPE, SCOPE_GLOBAL)?);
+    let headphones = transport == Transport::BuiltIn
+      && get_u32(device, PROPERTY_DATA_SOURCE, SCOPE_OUTPUT) == Some(DATA_SOURCE_HEADPHONES);
+    Some(DeviceKind { transport, headphones })
+  }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, FolderRecord, SessionRecord};
+use crate::export::{self, Artifacts, SessionExport};
+
+const BACKUP_VERSION: u32 = 1;
+
+/// On-disk backup: every folder and session in the canonical export format.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    #[serde(default)]
+    pub folders: Vec<FolderRecord>,
+    #[serde(default)]
+    pub sessions: Vec<SessionExport>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BackupReport {
+    pub sessions: usize,
+    pub folders: usize,
+    pub skipped: usize,
+}
+
+fn session_record(session: &SessionExport, folder_id: Option<String>) -> Result<SessionRecord, String> {
+    let segments = if session.segments.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&session.segments).map_err(|e| format!("Failed to encode segments: {}", e))?)
+    };
+    let artifacts = Artifacts {
+        action_items: session.action_items.clone(),
+        decisions: session.decisions.clone(),
+        questions: session.questions.clone(),
+    };
+    let artifacts = Some(artifacts).filter(|a| !a.is_empty());
+    Ok(SessionRecord {
+        id: session.id.clone(),
+        title: session.title.clone(),
+        date: session.date.clone(),
+        duration: session.duration,
+        transcript: session.transcript.clone(),
+        summary: session.summary.clone(),
+        artifacts,
+        folder_id,
+        segments,
+        // Audio files aren't part of the backup
+        audio_path: None,
+        summary_engine: session.summary_engine.clone(),
+        summary_model: session.summary_model.clone(),
+        transcription_model: session.transcription_model.clone(),
+        language: session.language.clone(),
+        chapters: if session.chapters.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&session.chapters).map_err(|e| format!("Failed to encode chapters: {}", e))?)
+        },
+        // Unredacted transcripts stay on this machine
+        raw_transcript: None,
+        is_favorite: session.is_favorite,
+        notes: session.notes.clone(),
+        debug_raw_audio_path: None,
+        debug_whisper_audio_path: None,
+        track2_audio_path: None,
+        translated: session.translated,
+        deleted_at: None,
+        is_draft: false,
+        created_at: session.created_at.clone(),
+        updated_at: session.updated_at.clone(),
+    })
+}
+
+/// Write all folders and sessions to a single JSON file.
+pub async fn export_all(database: &Database, path: &Path) -> Result<BackupReport, String> {
+    let folders = database.list_folders().await.map_err(|e| format!("Failed to list folders: {}", e))?;
+    // SQLite treats a negative LIMIT as "no limit"
+    let sessions = database.list_sessions(Some(-1)).await.map_err(|e| format!("Failed to list sessions: {}", e))?;
+
+    let by_id: HashMap<&str, &FolderRecord> = folders.iter().map(|f| (f.id.as_str(), f)).collect();
+    let sessions: Vec<SessionExport> = sessions
+        .iter()
+        .map(|s| export::session_export(s, s.folder_id.as_deref().and_then(|id| by_id.get(id).copied())))
+        .collect();
+
+    let report = BackupReport { sessions: sessions.len(), folders: folders.len(), skipped: 0 };
+    let backup = Backup { version: BACKUP_VERSION, folders, sessions };
+    let json = serde_json::to_vec_pretty(&backup).map_err(|e| format!("Failed to encode backup: {}", e))?;
+    tokio::fs::write(path, json)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(report)
+}
+
+/// Merge a backup into the database. Sessions whose id already exists are skipped;
+/// folders are matched by name so an existing folder is reused rather than duplicated.
+pub async fn import(database: &Database, path: &Path) -> Result<BackupReport, String> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let backup: Backup = serde_json::from_slice(&bytes).map_err(|e| format!("Invalid backup file: {}", e))?;
+    if backup.version > BACKUP_VERSION {
+        return Err(format!("Backup version {} is newer than this app supports", backup.version));
+    }
+
+    let mut report = BackupReport::default();
+    let existing = database.list_folders().await.map_err(|e| format!("Failed to list folders: {}", e))?;
+    let mut local_ids: HashMap<String, String> = HashMap::new();
+    for folder in &backup.folders {
+        if let Some(local) = existing.iter().find(|f| f.id == folder.id || f.name == folder.name) {
+            local_ids.insert(folder.id.clone(), local.id.clone());
+            continue;
+        }
+        if database.insert_folder_record(folder).await.map_err(|e| format!("Failed to import folder: {}", e))? {
+            report.folders += 1;
+        }
+        local_ids.insert(folder.id.clone(), folder.id.clone());
+    }
+
+    for session in &backup.sessions {
+        let folder_id = session
+            .folder
+            .as_ref()
+            .and_then(|f| local_ids.get(&f.id).cloned());
+        let record = session_record(session, folder_id)?;
+        if database.insert_session_record(&record).await.map_err(|e| format!("Failed to import session: {}", e))? {
+            report.sessions += 1;
+        } else {
+            report.skipped += 1;
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,72 @@
+//! One-file archive of a single session: audio, transcript, Markdown notes and the
+//! canonical JSON, for handing a meeting off or keeping it outside the app.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::database::{FolderRecord, SessionRecord};
+use crate::export;
+
+/// Write `path` as a zip with `transcript.txt`, `summary.md`, `segments.json`,
+/// `metadata.json` and, when the session's audio was saved, `audio.wav`. Pieces the
+/// session doesn't have are left out. The zip is built next to `path` and only moved
+/// there once complete, so a failed export leaves nothing behind (nor clobbers an
+/// existing file). Blocking: call from `spawn_blocking`.
+pub fn write_bundle(session: &SessionRecord, folder: Option<&FolderRecord>, path: &Path) -> Result<PathBuf, String> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    let file = File::create(&partial).map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+    let written = write_zip(session, folder, file).and_then(|()| {
+        std::fs::rename(&partial, path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    Ok(path.to_path_buf())
+}
+
+fn write_zip(session: &SessionRecord, folder: Option<&FolderRecord>, file: File) -> Result<(), String> {
+    let mut zip = ZipWriter::new(file);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut add = |name: &str, bytes: &[u8]| -> Result<(), String> {
+        zip.start_file(name, deflated).map_err(|e| format!("Failed to add {}: {}", name, e))?;
+        zip.write_all(bytes).map_err(|e| format!("Failed to write {}: {}", name, e))
+    };
+
+    if let Some(transcript) = session.transcript.as_deref().filter(|t| !t.trim().is_empty()) {
+        add("transcript.txt", transcript.as_bytes())?;
+    }
+    add("summary.md", export::session_markdown(session).as_bytes())?;
+    let segments = export::parse_segments(session);
+    if !segments.is_empty() {
+        let json = serde_json::to_vec_pretty(&segments).map_err(|e| format!("Failed to encode segments: {}", e))?;
+        add("segments.json", &json)?;
+    }
+    let metadata = export::session_export(session, folder);
+    let json = serde_json::to_vec_pretty(&metadata).map_err(|e| format!("Failed to encode metadata: {}", e))?;
+    add("metadata.json", &json)?;
+
+    match session.audio_path.as_deref().map(File::open) {
+        Some(Ok(mut audio)) => {
+            // WAV barely compresses; storing it keeps large recordings fast to bundle
+            let stored = FileOptions::default().compression_method(CompressionMethod::Stored).large_file(true);
+            zip.start_file("audio.wav", stored).map_err(|e| format!("Failed to add audio.wav: {}", e))?;
+            io::copy(&mut audio, &mut zip).map_err(|e| format!("Failed to write audio.wav: {}", e))?;
+        }
+        Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => {
+            return Err(format!("Failed to read session audio: {}", e));
+        }
+        // Audio wasn't saved, or has since been deleted
+        _ => {}
+    }
+
+    zip.finish().map_err(|e| format!("Failed to finish the bundle: {}", e))?;
+    Ok(())
+}
@@ -1,10 +1,30 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{SqlitePool, Row};
-use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow, SqliteSynchronous};
 use std::str::FromStr;
 use std::path::Path;
+use std::time::Duration;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::export::Artifacts;
+use crate::llm::TokenUsage;
+
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+// SQLite allows one writer at a time regardless, so extra connections only help concurrent reads
+const DEFAULT_POOL_SIZE: u32 = 5;
+/// Deleted sessions stay restorable from the trash for this long.
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// Max pool connections, overridable with OATMEAL_DB_POOL_SIZE.
+fn pool_size() -> u32 {
+    std::env::var("OATMEAL_DB_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_POOL_SIZE)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub enable_telemetry: bool,
     pub retention_days: i32,
@@ -17,6 +37,57 @@ pub struct Settings {
     pub ollama_model: String,
     pub ollama_host: String,
     pub force_microphone: bool,
+    pub audio_frame_encoding: String, // 'json' | 'base64' (f32le)
+    pub capture_mode: String, // 'mixed' | 'separate' (mic and system transcribed independently) | 'tracks' (two inputs, no system audio)
+    pub enable_diarization: bool, // cluster segments into S1/S2 speakers (extra CPU per chunk)
+    pub save_audio: bool, // keep a WAV of each recording for later re-transcription
+    pub api_enabled: bool, // serve the local HTTP API on 127.0.0.1
+    pub api_port: i32,
+    pub webhook_url: String, // POSTed on session save/summary; empty disables
+    pub frame_ms: i32, // audio:frame length, clamped to 10-100ms
+    pub chunk_overlap_ms: i32, // audio carried into the next live chunk for context
+    pub language: String, // Whisper language code, or 'auto' to detect per chunk
+    pub encrypt_database: bool, // SQLCipher-encrypt the database; the key lives in the OS keychain
+    pub input_gain: f32, // pre-amp applied to captured audio before framing (0.5-4.0)
+    pub transcription_engine: String, // 'local' (Whisper) or 'none' to only record audio
+    pub prefer_builtin_mic: bool, // with system audio on, record voice from the built-in mic so Bluetooth headsets stay in A2DP
+    pub audio_gate_threshold: f32, // mixed-path noise gate; system audio uses 60% of it
+    pub audio_attack_ms: f32, // mixer gain attack time, 0 = instant
+    pub audio_release_ms: f32, // mixer gain release time, 0 = instant
+    pub audio_makeup_gain: f32, // applied to the mix before the limiter
+    pub audio_limiter_ceiling: f32, // soft limiter knee
+    pub summary_prompt_template: String, // '' = default prompt, a built-in template name, or custom text with {{transcript}}
+    pub summary_fallback_engine: String, // engine tried when the primary can't be reached; '' = none
+    pub usage_price_input_per_1k: f64, // USD per 1K input tokens for cloud engines, used for cost estimates
+    pub usage_price_output_per_1k: f64, // USD per 1K output tokens for cloud engines
+    pub embedding_engine: String, // 'none' | 'ollama' | 'openai' for semantic search
+    pub embedding_model: String, // '' picks the engine's default embedding model
+    pub whisper_model: String, // active Whisper model label, e.g. 'small.en'; '' picks automatically
+    pub redact_transcript: bool, // replace emails, phone and card numbers in stored transcripts with [REDACTED]
+    pub keep_raw_transcript: bool, // with redaction on, also keep the unredacted transcript in raw_transcript
+    pub normalize_numbers: bool, // write spelled-out numbers, money and percentages in live transcripts as digits
+    pub system_audio_app: String, // bundle id of the app whose audio to capture (macOS); '' captures all system audio
+    pub system_audio_sample_rate: i32, // ScreenCaptureKit capture rate; falls back to 48000 if rejected
+    pub system_audio_channels: i32, // ScreenCaptureKit channels (1-2), downmixed to mono
+    pub auto_stop_silence_seconds: f32, // stop recording after this much continuous silence (min 15s); 0 = never
+    pub openai_base_url: String, // OpenAI-compatible API root (gateways, Azure, LiteLLM); paths like /chat/completions are appended
+    pub anthropic_base_url: String, // Anthropic API root; /messages is appended
+    pub min_transcribe_ms: i32, // live chunks shorter than this are skipped instead of transcribed
+    pub live_confidence: bool, // attach whisper word probabilities to transcript:partial for caption shading
+    pub log_level: String, // off, error, warn, info, debug or trace
+    pub debug_save_audio: bool, // also keep the raw mic and the audio Whisper heard, per session
+    pub adaptive_chunking: bool, // merge queued chunks and skip quiet ones when whisper falls behind
+    pub track1_device: String, // capture_mode 'tracks': input for track 1
+    pub track2_device: String, // capture_mode 'tracks': input for track 2
+    pub translate_to_english: bool, // Whisper translates speech to English instead of transcribing it
+    pub mic_mix_level: f32, // mic weight in the mixed path, 0.0-2.0
+    pub system_mix_level: f32, // system audio weight in the mixed path, 0.0-2.0
+    pub mix_auto_gain: bool, // RMS-based boost/duck on top of the mix levels
+    pub transcription_preset: String, // 'fast' | 'balanced' | 'accurate'
+    pub low_power_mode: bool, // Battery saver for live transcription: smallest model, CPU only, fewer threads, longer chunks
+    pub whisper_temperature: Option<f32>, // advanced: first-pass decoding temperature; NULL = the preset's
+    pub whisper_entropy_thold: Option<f32>, // advanced: retry a decode whose entropy is above this; NULL = the preset's
+    pub whisper_logprob_thold: Option<f32>, // advanced: retry a decode whose average log-probability is below this; NULL = the preset's
 }
 
 impl Default for Settings {
@@ -33,6 +104,103 @@ impl Default for Settings {
             ollama_model: "llama3.1:8b-instruct-q4_K_M".to_string(),
             ollama_host: "http://127.0.0.1:11434".to_string(),
             force_microphone: false,
+            audio_frame_encoding: "json".to_string(),
+            capture_mode: "mixed".to_string(),
+            enable_diarization: false,
+            save_audio: false,
+            api_enabled: false,
+            api_port: 8765,
+            webhook_url: String::new(),
+            frame_ms: 20,
+            chunk_overlap_ms: 500,
+            language: "en".to_string(),
+            encrypt_database: false,
+            input_gain: 1.0,
+            transcription_engine: "local".to_string(),
+            prefer_builtin_mic: false,
+            audio_gate_threshold: 0.005,
+            audio_attack_ms: 0.0,
+            audio_release_ms: 0.0,
+            audio_makeup_gain: 1.0,
+            audio_limiter_ceiling: 0.95,
+            summary_prompt_template: String::new(),
+            summary_fallback_engine: String::new(),
+            usage_price_input_per_1k: 0.003,
+            usage_price_output_per_1k: 0.015,
+            embedding_engine: "none".to_string(),
+            embedding_model: String::new(),
+            whisper_model: String::new(),
+            redact_transcript: false,
+            keep_raw_transcript: false,
+            normalize_numbers: false,
+            system_audio_app: String::new(),
+            system_audio_sample_rate: 16000,
+            system_audio_channels: 1,
+            auto_stop_silence_seconds: 0.0,
+            openai_base_url: "https://api.openai.com/v1".to_string(),
+            anthropic_base_url: "https://api.anthropic.com/v1".to_string(),
+            min_transcribe_ms: 300,
+            live_confidence: false,
+            log_level: "info".to_string(),
+            debug_save_audio: false,
+            adaptive_chunking: false,
+            track1_device: String::new(),
+            track2_device: String::new(),
+            translate_to_english: false,
+            mic_mix_level: 1.0,
+            system_mix_level: 1.0,
+            mix_auto_gain: true,
+            transcription_preset: "balanced".to_string(),
+            low_power_mode: false,
+            whisper_temperature: None,
+            whisper_entropy_thold: None,
+            whisper_logprob_thold: None,
+        }
+    }
+}
+
+impl Settings {
+    /// These settings with the fields named in `changes` (a JSON object keyed like `Settings`)
+    /// replaced. Fields the caller didn't send keep their current values, so a screen that only
+    /// knows about some settings can't reset the rest.
+    pub fn merged(&self, changes: &serde_json::Value) -> Result<Settings, String> {
+        let changes = changes
+            .as_object()
+            .ok_or_else(|| "Settings update must be an object of setting names".to_string())?;
+        let mut value = serde_json::to_value(self).map_err(|e| format!("Failed to encode settings: {}", e))?;
+        if let Some(fields) = value.as_object_mut() {
+            for (key, change) in changes {
+                if !fields.contains_key(key) {
+                    return Err(format!("Unknown setting '{}'", key));
+                }
+                fields.insert(key.clone(), change.clone());
+            }
+        }
+        serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))
+    }
+}
+
+/// The slice of `Settings` that picks how summaries are generated. `model` and `host` are
+/// the active engine's own: `ollama_model`/`ollama_host` for Ollama, `model` and the
+/// provider's base URL for Anthropic and OpenAI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryConfig {
+    pub engine: String,
+    pub model: String,
+    pub host: String,
+}
+
+impl SummaryConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let (model, host) = match settings.summary_engine.as_str() {
+            "anthropic" => (&settings.model, &settings.anthropic_base_url),
+            "openai" => (&settings.model, &settings.openai_base_url),
+            _ => (&settings.ollama_model, &settings.ollama_host),
+        };
+        Self {
+            engine: settings.summary_engine.clone(),
+            model: model.clone(),
+            host: host.clone(),
         }
     }
 }
@@ -42,7 +210,9 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn new(db_path: &str) -> Result<Self, sqlx::Error> {
+    /// Open (creating if needed) the database at `db_path`. Pass `key` to open a
+    /// SQLCipher-encrypted file; see `encryption`.
+    pub async fn new(db_path: &str, key: Option<&str>) -> Result<Self, sqlx::Error> {
         // Ensure parent directory exists
         if let Some(parent) = Path::new(db_path).parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
@@ -50,11 +220,27 @@ impl Database {
             })?;
         }
 
-        // Use explicit connect options to ensure file is created and path is handled correctly
-        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path))
+        // Use explicit connect options to ensure file is created and path is handled correctly.
+        // WAL lets the history list keep reading while a large transcript is being written;
+        // the cost is two extra files (-wal/-shm) next to the database and the db no longer
+        // being safe on network filesystems, neither of which matters for a local app dir.
+        // With WAL, synchronous=NORMAL only risks losing the last commits on power loss
+        // (never corruption) and avoids an fsync per write. busy_timeout makes a second
+        // writer wait instead of failing immediately with SQLITE_BUSY.
+        let mut options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path))
             .map_err(|e| sqlx::Error::Protocol(format!("invalid sqlite path: {}", e).into()))?
-            .create_if_missing(true);
-        let pool = SqlitePool::connect_with(options).await?;
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT);
+        if let Some(key) = key {
+            // sqlx issues `key` before any other pragma, as SQLCipher requires
+            options = options.pragma("key", format!("'{}'", key.replace('\'', "''")));
+        }
+        let pool = SqlitePoolOptions::new()
+            .max_connections(pool_size())
+            .connect_with(options)
+            .await?;
         
         // Create tables
         sqlx::query(r#"
@@ -71,6 +257,57 @@ impl Database {
                 ollama_model TEXT DEFAULT 'llama3.1:8b-instruct-q4_K_M',
                 ollama_host TEXT DEFAULT 'http://127.0.0.1:11434',
                 force_microphone BOOLEAN DEFAULT 0,
+                audio_frame_encoding TEXT DEFAULT 'json',
+                capture_mode TEXT DEFAULT 'mixed',
+                enable_diarization BOOLEAN DEFAULT 0,
+                save_audio BOOLEAN DEFAULT 0,
+                api_enabled BOOLEAN DEFAULT 0,
+                api_port INTEGER DEFAULT 8765,
+                webhook_url TEXT DEFAULT '',
+                frame_ms INTEGER DEFAULT 20,
+                chunk_overlap_ms INTEGER DEFAULT 500,
+                language TEXT DEFAULT 'en',
+                encrypt_database BOOLEAN DEFAULT 0,
+                input_gain REAL DEFAULT 1.0,
+                transcription_engine TEXT DEFAULT 'local',
+                prefer_builtin_mic BOOLEAN DEFAULT 0,
+                audio_gate_threshold REAL DEFAULT 0.005,
+                audio_attack_ms REAL DEFAULT 0,
+                audio_release_ms REAL DEFAULT 0,
+                audio_makeup_gain REAL DEFAULT 1.0,
+                audio_limiter_ceiling REAL DEFAULT 0.95,
+                summary_prompt_template TEXT DEFAULT '',
+                summary_fallback_engine TEXT DEFAULT '',
+                usage_price_input_per_1k REAL DEFAULT 0.003,
+                usage_price_output_per_1k REAL DEFAULT 0.015,
+                embedding_engine TEXT DEFAULT 'none',
+                embedding_model TEXT DEFAULT '',
+                whisper_model TEXT DEFAULT '',
+                redact_transcript BOOLEAN DEFAULT 0,
+                keep_raw_transcript BOOLEAN DEFAULT 0,
+                normalize_numbers BOOLEAN DEFAULT 0,
+                system_audio_app TEXT DEFAULT '',
+                system_audio_sample_rate INTEGER DEFAULT 16000,
+                system_audio_channels INTEGER DEFAULT 1,
+                auto_stop_silence_seconds REAL DEFAULT 0,
+                openai_base_url TEXT DEFAULT 'https://api.openai.com/v1',
+                anthropic_base_url TEXT DEFAULT 'https://api.anthropic.com/v1',
+                min_transcribe_ms INTEGER DEFAULT 300,
+                live_confidence BOOLEAN DEFAULT 0,
+                log_level TEXT NOT NULL DEFAULT 'info',
+                debug_save_audio BOOLEAN DEFAULT 0,
+                adaptive_chunking BOOLEAN DEFAULT 0,
+                track1_device TEXT DEFAULT '',
+                track2_device TEXT DEFAULT '',
+                translate_to_english BOOLEAN DEFAULT 0,
+                mic_mix_level REAL DEFAULT 1.0,
+                system_mix_level REAL DEFAULT 1.0,
+                mix_auto_gain BOOLEAN DEFAULT 1,
+                transcription_preset TEXT DEFAULT 'balanced',
+                low_power_mode BOOLEAN DEFAULT 0,
+                whisper_temperature REAL,
+                whisper_entropy_thold REAL,
+                whisper_logprob_thold REAL,
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
                 updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
             )
@@ -92,6 +329,159 @@ impl Database {
         let _ = sqlx::query("ALTER TABLE settings ADD COLUMN force_microphone BOOLEAN DEFAULT 0")
             .execute(&pool)
             .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN audio_frame_encoding TEXT DEFAULT 'json'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN capture_mode TEXT DEFAULT 'mixed'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN enable_diarization BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN save_audio BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN api_enabled BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN api_port INTEGER DEFAULT 8765")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN webhook_url TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN frame_ms INTEGER DEFAULT 20")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN chunk_overlap_ms INTEGER DEFAULT 500")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN language TEXT DEFAULT 'en'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN encrypt_database BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN input_gain REAL DEFAULT 1.0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN transcription_engine TEXT DEFAULT 'local'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN prefer_builtin_mic BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN audio_gate_threshold REAL DEFAULT 0.005")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN audio_attack_ms REAL DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN audio_release_ms REAL DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN audio_makeup_gain REAL DEFAULT 1.0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN audio_limiter_ceiling REAL DEFAULT 0.95")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN summary_prompt_template TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN summary_fallback_engine TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN usage_price_input_per_1k REAL DEFAULT 0.003")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN usage_price_output_per_1k REAL DEFAULT 0.015")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN embedding_engine TEXT DEFAULT 'none'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN embedding_model TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN whisper_model TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN redact_transcript BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN keep_raw_transcript BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN normalize_numbers BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN system_audio_app TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN system_audio_sample_rate INTEGER DEFAULT 16000")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN system_audio_channels INTEGER DEFAULT 1")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN auto_stop_silence_seconds REAL DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN openai_base_url TEXT DEFAULT 'https://api.openai.com/v1'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN anthropic_base_url TEXT DEFAULT 'https://api.anthropic.com/v1'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN min_transcribe_ms INTEGER DEFAULT 300")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN live_confidence BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN log_level TEXT NOT NULL DEFAULT 'info'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN debug_save_audio BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN adaptive_chunking BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN track1_device TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN track2_device TEXT DEFAULT ''")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN translate_to_english BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN mic_mix_level REAL DEFAULT 1.0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN system_mix_level REAL DEFAULT 1.0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN mix_auto_gain BOOLEAN DEFAULT 1")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN transcription_preset TEXT DEFAULT 'balanced'")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN low_power_mode BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN whisper_temperature REAL")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN whisper_entropy_thold REAL")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE settings ADD COLUMN whisper_logprob_thold REAL")
+            .execute(&pool)
+            .await;
 
         sqlx::query(r#"
             CREATE TABLE IF NOT EXISTS sessions (
@@ -111,6 +501,71 @@ impl Database {
         let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN folder_id TEXT")
             .execute(&pool)
             .await;
+        // JSON array of timed, source-tagged transcript segments
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN segments TEXT")
+            .execute(&pool)
+            .await;
+        // Saved WAV for re-transcription, when save_audio was on
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN audio_path TEXT")
+            .execute(&pool)
+            .await;
+        // Which engines produced the summary and transcript (NULL for older sessions)
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN summary_engine TEXT")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN summary_model TEXT")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN transcription_model TEXT")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN language TEXT")
+            .execute(&pool)
+            .await;
+        // Soft delete: set while the session sits in the trash
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN deleted_at TEXT")
+            .execute(&pool)
+            .await;
+        // Autosaved copy of a recording that hasn't been saved yet (crash recovery)
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN is_draft BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN chapters TEXT")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN raw_transcript TEXT")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN is_favorite BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+        // The user's own commentary, separate from the summary and quick notes
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN notes TEXT")
+            .execute(&pool)
+            .await;
+        // Written only with `debug_save_audio` on
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN debug_raw_audio_path TEXT")
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN debug_whisper_audio_path TEXT")
+            .execute(&pool)
+            .await;
+        // Second input of a two-track recording; audio_path holds track 1
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN track2_audio_path TEXT")
+            .execute(&pool)
+            .await;
+        // Transcript is Whisper's English translation rather than the spoken language
+        let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN translated BOOLEAN DEFAULT 0")
+            .execute(&pool)
+            .await;
+
+        // Small key/value store for app-managed values that aren't user settings
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )
+        "#).execute(&pool).await?;
 
         // Folders table
         sqlx::query(r#"
@@ -122,6 +577,61 @@ impl Database {
             )
         "#).execute(&pool).await?;
 
+        // Quick notes; session_id is NULL until the note is attached to a saved session
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS notes (
+                id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+                session_id TEXT,
+                text TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )
+        "#).execute(&pool).await?;
+        let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_notes_session ON notes(session_id)")
+            .execute(&pool)
+            .await;
+
+        // One row per LLM request; kept when the session is deleted so totals keep adding up
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS token_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT,
+                engine TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                estimated BOOLEAN DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )
+        "#).execute(&pool).await?;
+        let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_token_usage_session ON token_usage(session_id)")
+            .execute(&pool)
+            .await;
+
+        // Semantic search vectors, one row per transcript chunk (see embeddings.rs)
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS session_embeddings (
+                session_id TEXT NOT NULL,
+                chunk_index INTEGER NOT NULL,
+                model TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                PRIMARY KEY (session_id, chunk_index)
+            )
+        "#).execute(&pool).await?;
+
+        // People heard in each session. source is 'label' for speaker labels from capture or
+        // diarization ("me", "them", "Speaker 2") and 'llm' for names found in the transcript
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS participants (
+                session_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                source TEXT NOT NULL,
+                PRIMARY KEY (session_id, name)
+            )
+        "#).execute(&pool).await?;
+        let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_participants_name ON participants(name)")
+            .execute(&pool)
+            .await;
+
         Ok(Self { pool })
     }
 
@@ -143,6 +653,57 @@ impl Database {
                 ollama_model: row.try_get("ollama_model").unwrap_or("llama3.1:8b-instruct-q4_K_M".to_string()),
                 ollama_host: row.try_get("ollama_host").unwrap_or("http://127.0.0.1:11434".to_string()),
                 force_microphone: row.try_get("force_microphone").unwrap_or(false),
+                audio_frame_encoding: row.try_get("audio_frame_encoding").unwrap_or("json".to_string()),
+                capture_mode: row.try_get("capture_mode").unwrap_or("mixed".to_string()),
+                enable_diarization: row.try_get("enable_diarization").unwrap_or(false),
+                save_audio: row.try_get("save_audio").unwrap_or(false),
+                api_enabled: row.try_get("api_enabled").unwrap_or(false),
+                api_port: row.try_get("api_port").unwrap_or(8765),
+                webhook_url: row.try_get("webhook_url").unwrap_or(String::new()),
+                frame_ms: row.try_get("frame_ms").unwrap_or(20),
+                chunk_overlap_ms: row.try_get("chunk_overlap_ms").unwrap_or(500),
+                language: row.try_get("language").unwrap_or("en".to_string()),
+                encrypt_database: row.try_get("encrypt_database").unwrap_or(false),
+                input_gain: row.try_get("input_gain").unwrap_or(1.0),
+                transcription_engine: row.try_get("transcription_engine").unwrap_or("local".to_string()),
+                prefer_builtin_mic: row.try_get("prefer_builtin_mic").unwrap_or(false),
+                audio_gate_threshold: row.try_get("audio_gate_threshold").unwrap_or(0.005),
+                audio_attack_ms: row.try_get("audio_attack_ms").unwrap_or(0.0),
+                audio_release_ms: row.try_get("audio_release_ms").unwrap_or(0.0),
+                audio_makeup_gain: row.try_get("audio_makeup_gain").unwrap_or(1.0),
+                audio_limiter_ceiling: row.try_get("audio_limiter_ceiling").unwrap_or(0.95),
+                summary_prompt_template: row.try_get("summary_prompt_template").unwrap_or(String::new()),
+                summary_fallback_engine: row.try_get("summary_fallback_engine").unwrap_or(String::new()),
+                usage_price_input_per_1k: row.try_get("usage_price_input_per_1k").unwrap_or(0.003),
+                usage_price_output_per_1k: row.try_get("usage_price_output_per_1k").unwrap_or(0.015),
+                embedding_engine: row.try_get("embedding_engine").unwrap_or("none".to_string()),
+                embedding_model: row.try_get("embedding_model").unwrap_or(String::new()),
+                whisper_model: row.try_get("whisper_model").unwrap_or(String::new()),
+                redact_transcript: row.try_get("redact_transcript").unwrap_or(false),
+                keep_raw_transcript: row.try_get("keep_raw_transcript").unwrap_or(false),
+                normalize_numbers: row.try_get("normalize_numbers").unwrap_or(false),
+                system_audio_app: row.try_get("system_audio_app").unwrap_or(String::new()),
+                system_audio_sample_rate: row.try_get("system_audio_sample_rate").unwrap_or(16000),
+                system_audio_channels: row.try_get("system_audio_channels").unwrap_or(1),
+                auto_stop_silence_seconds: row.try_get("auto_stop_silence_seconds").unwrap_or(0.0),
+                openai_base_url: row.try_get("openai_base_url").unwrap_or("https://api.openai.com/v1".to_string()),
+                anthropic_base_url: row.try_get("anthropic_base_url").unwrap_or("https://api.anthropic.com/v1".to_string()),
+                min_transcribe_ms: row.try_get("min_transcribe_ms").unwrap_or(300),
+                live_confidence: row.try_get("live_confidence").unwrap_or(false),
+                log_level: row.try_get("log_level").unwrap_or("info".to_string()),
+                debug_save_audio: row.try_get("debug_save_audio").unwrap_or(false),
+                adaptive_chunking: row.try_get("adaptive_chunking").unwrap_or(false),
+                track1_device: row.try_get("track1_device").unwrap_or(String::new()),
+                track2_device: row.try_get("track2_device").unwrap_or(String::new()),
+                translate_to_english: row.try_get("translate_to_english").unwrap_or(false),
+                mic_mix_level: row.try_get("mic_mix_level").unwrap_or(1.0),
+                system_mix_level: row.try_get("system_mix_level").unwrap_or(1.0),
+                mix_auto_gain: row.try_get("mix_auto_gain").unwrap_or(true),
+                transcription_preset: row.try_get("transcription_preset").unwrap_or("balanced".to_string()),
+                low_power_mode: row.try_get("low_power_mode").unwrap_or(false),
+                whisper_temperature: row.try_get("whisper_temperature").unwrap_or(None),
+                whisper_entropy_thold: row.try_get("whisper_entropy_thold").unwrap_or(None),
+                whisper_logprob_thold: row.try_get("whisper_logprob_thold").unwrap_or(None),
             }),
             None => {
                 // Insert default settings
@@ -173,6 +734,57 @@ impl Database {
                     ollama_model = ?,
                     ollama_host = ?,
                     force_microphone = ?,
+                    audio_frame_encoding = ?,
+                    capture_mode = ?,
+                    enable_diarization = ?,
+                    save_audio = ?,
+                    api_enabled = ?,
+                    api_port = ?,
+                    webhook_url = ?,
+                    frame_ms = ?,
+                    chunk_overlap_ms = ?,
+                    language = ?,
+                    encrypt_database = ?,
+                    input_gain = ?,
+                    transcription_engine = ?,
+                    prefer_builtin_mic = ?,
+                    audio_gate_threshold = ?,
+                    audio_attack_ms = ?,
+                    audio_release_ms = ?,
+                    audio_makeup_gain = ?,
+                    audio_limiter_ceiling = ?,
+                    summary_prompt_template = ?,
+                    summary_fallback_engine = ?,
+                    usage_price_input_per_1k = ?,
+                    usage_price_output_per_1k = ?,
+                    embedding_engine = ?,
+                    embedding_model = ?,
+                    whisper_model = ?,
+                    redact_transcript = ?,
+                    keep_raw_transcript = ?,
+                    normalize_numbers = ?,
+                    system_audio_app = ?,
+                    system_audio_sample_rate = ?,
+                    system_audio_channels = ?,
+                    auto_stop_silence_seconds = ?,
+                    openai_base_url = ?,
+                    anthropic_base_url = ?,
+                    min_transcribe_ms = ?,
+                    live_confidence = ?,
+                    log_level = ?,
+                    debug_save_audio = ?,
+                    adaptive_chunking = ?,
+                    track1_device = ?,
+                    track2_device = ?,
+                    translate_to_english = ?,
+                    mic_mix_level = ?,
+                    system_mix_level = ?,
+                    mix_auto_gain = ?,
+                    transcription_preset = ?,
+                    low_power_mode = ?,
+                    whisper_temperature = ?,
+                    whisper_entropy_thold = ?,
+                    whisper_logprob_thold = ?,
                     updated_at = CURRENT_TIMESTAMP
                 WHERE id = ?
             "#)
@@ -187,6 +799,57 @@ impl Database {
             .bind(&settings.ollama_model)
             .bind(&settings.ollama_host)
             .bind(&settings.force_microphone)
+            .bind(&settings.audio_frame_encoding)
+            .bind(&settings.capture_mode)
+            .bind(&settings.enable_diarization)
+            .bind(&settings.save_audio)
+            .bind(&settings.api_enabled)
+            .bind(&settings.api_port)
+            .bind(&settings.webhook_url)
+            .bind(&settings.frame_ms)
+            .bind(&settings.chunk_overlap_ms)
+            .bind(&settings.language)
+            .bind(&settings.encrypt_database)
+            .bind(&settings.input_gain)
+            .bind(&settings.transcription_engine)
+            .bind(&settings.prefer_builtin_mic)
+            .bind(&settings.audio_gate_threshold)
+            .bind(&settings.audio_attack_ms)
+            .bind(&settings.audio_release_ms)
+            .bind(&settings.audio_makeup_gain)
+            .bind(&settings.audio_limiter_ceiling)
+            .bind(&settings.summary_prompt_template)
+            .bind(&settings.summary_fallback_engine)
+            .bind(&settings.usage_price_input_per_1k)
+            .bind(&settings.usage_price_output_per_1k)
+            .bind(&settings.embedding_engine)
+            .bind(&settings.embedding_model)
+            .bind(&settings.whisper_model)
+            .bind(&settings.redact_transcript)
+            .bind(&settings.keep_raw_transcript)
+            .bind(&settings.normalize_numbers)
+            .bind(&settings.system_audio_app)
+            .bind(&settings.system_audio_sample_rate)
+            .bind(&settings.system_audio_channels)
+            .bind(&settings.auto_stop_silence_seconds)
+            .bind(&settings.openai_base_url)
+            .bind(&settings.anthropic_base_url)
+            .bind(&settings.min_transcribe_ms)
+            .bind(&settings.live_confidence)
+            .bind(&settings.log_level)
+            .bind(&settings.debug_save_audio)
+            .bind(&settings.adaptive_chunking)
+            .bind(&settings.track1_device)
+            .bind(&settings.track2_device)
+            .bind(&settings.translate_to_english)
+            .bind(&settings.mic_mix_level)
+            .bind(&settings.system_mix_level)
+            .bind(&settings.mix_auto_gain)
+            .bind(&settings.transcription_preset)
+            .bind(&settings.low_power_mode)
+            .bind(&settings.whisper_temperature)
+            .bind(&settings.whisper_entropy_thold)
+            .bind(&settings.whisper_logprob_thold)
             .bind(&id)
             .execute(&self.pool)
             .await?;
@@ -194,9 +857,9 @@ impl Database {
             let id = uuid::Uuid::new_v4().to_string();
             sqlx::query(r#"
                 INSERT INTO settings (
-                    id, enable_telemetry, retention_days, use_gpu, model, enable_hubspot, enable_gmail, chunk_seconds, summary_engine, ollama_model, ollama_host, force_microphone, created_at, updated_at
+                    id, enable_telemetry, retention_days, use_gpu, model, enable_hubspot, enable_gmail, chunk_seconds, summary_engine, ollama_model, ollama_host, force_microphone, audio_frame_encoding, capture_mode, enable_diarization, save_audio, api_enabled, api_port, webhook_url, frame_ms, chunk_overlap_ms, language, encrypt_database, input_gain, transcription_engine, prefer_builtin_mic, audio_gate_threshold, audio_attack_ms, audio_release_ms, audio_makeup_gain, audio_limiter_ceiling, summary_prompt_template, summary_fallback_engine, usage_price_input_per_1k, usage_price_output_per_1k, embedding_engine, embedding_model, whisper_model, redact_transcript, keep_raw_transcript, normalize_numbers, system_audio_app, system_audio_sample_rate, system_audio_channels, auto_stop_silence_seconds, openai_base_url, anthropic_base_url, min_transcribe_ms, live_confidence, log_level, debug_save_audio, adaptive_chunking, track1_device, track2_device, translate_to_english, mic_mix_level, system_mix_level, mix_auto_gain, transcription_preset, low_power_mode, whisper_temperature, whisper_entropy_thold, whisper_logprob_thold, created_at, updated_at
                 ) VALUES (
-                    ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+                    ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
                 )
             "#)
             .bind(&id)
@@ -211,6 +874,57 @@ impl Database {
             .bind(&settings.ollama_model)
             .bind(&settings.ollama_host)
             .bind(&settings.force_microphone)
+            .bind(&settings.audio_frame_encoding)
+            .bind(&settings.capture_mode)
+            .bind(&settings.enable_diarization)
+            .bind(&settings.save_audio)
+            .bind(&settings.api_enabled)
+            .bind(&settings.api_port)
+            .bind(&settings.webhook_url)
+            .bind(&settings.frame_ms)
+            .bind(&settings.chunk_overlap_ms)
+            .bind(&settings.language)
+            .bind(&settings.encrypt_database)
+            .bind(&settings.input_gain)
+            .bind(&settings.transcription_engine)
+            .bind(&settings.prefer_builtin_mic)
+            .bind(&settings.audio_gate_threshold)
+            .bind(&settings.audio_attack_ms)
+            .bind(&settings.audio_release_ms)
+            .bind(&settings.audio_makeup_gain)
+            .bind(&settings.audio_limiter_ceiling)
+            .bind(&settings.summary_prompt_template)
+            .bind(&settings.summary_fallback_engine)
+            .bind(&settings.usage_price_input_per_1k)
+            .bind(&settings.usage_price_output_per_1k)
+            .bind(&settings.embedding_engine)
+            .bind(&settings.embedding_model)
+            .bind(&settings.whisper_model)
+            .bind(&settings.redact_transcript)
+            .bind(&settings.keep_raw_transcript)
+            .bind(&settings.normalize_numbers)
+            .bind(&settings.system_audio_app)
+            .bind(&settings.system_audio_sample_rate)
+            .bind(&settings.system_audio_channels)
+            .bind(&settings.auto_stop_silence_seconds)
+            .bind(&settings.openai_base_url)
+            .bind(&settings.anthropic_base_url)
+            .bind(&settings.min_transcribe_ms)
+            .bind(&settings.live_confidence)
+            .bind(&settings.log_level)
+            .bind(&settings.debug_save_audio)
+            .bind(&settings.adaptive_chunking)
+            .bind(&settings.track1_device)
+            .bind(&settings.track2_device)
+            .bind(&settings.translate_to_english)
+            .bind(&settings.mic_mix_level)
+            .bind(&settings.system_mix_level)
+            .bind(&settings.mix_auto_gain)
+            .bind(&settings.transcription_preset)
+            .bind(&settings.low_power_mode)
+            .bind(&settings.whisper_temperature)
+            .bind(&settings.whisper_entropy_thold)
+            .bind(&settings.whisper_logprob_thold)
             .execute(&self.pool)
             .await?;
             // Remove any legacy extra rows, keep only the one we just inserted
@@ -234,6 +948,71 @@ impl Database {
         Ok(())
     }
 
+    /// Switch the summary engine and its model/host in one statement, leaving every other
+    /// setting as stored. With "none" only the engine changes.
+    pub async fn update_summary_config(&self, config: &SummaryConfig) -> Result<(), sqlx::Error> {
+        // Creates the settings row on first run
+        self.get_settings().await?;
+        let columns = match config.engine.as_str() {
+            "none" => None,
+            "anthropic" => Some(("model", "anthropic_base_url")),
+            "openai" => Some(("model", "openai_base_url")),
+            _ => Some(("ollama_model", "ollama_host")),
+        };
+        match columns {
+            Some((model, host)) => {
+                let sql = format!(
+                    "UPDATE settings SET summary_engine = ?, {} = ?, {} = ?, updated_at = CURRENT_TIMESTAMP",
+                    model, host
+                );
+                sqlx::query(&sql)
+                    .bind(&config.engine)
+                    .bind(&config.model)
+                    .bind(&config.host)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            None => {
+                sqlx::query("UPDATE settings SET summary_engine = ?, updated_at = CURRENT_TIMESTAMP")
+                    .bind(&config.engine)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn get_meta(&self, key: &str) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT value FROM meta WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn set_meta(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO meta (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value")
+            .bind(key)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_meta(&self, key: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM meta WHERE key = ?").bind(key).execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The local API token, generated on first use.
+    pub async fn api_token(&self) -> Result<String, sqlx::Error> {
+        if let Some(token) = self.get_meta("api_token").await? {
+            return Ok(token);
+        }
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        self.set_meta("api_token", &token).await?;
+        Ok(token)
+    }
+
     pub async fn create_session(&self, title: &str, duration: i32) -> Result<String, sqlx::Error> {
         let id = uuid::Uuid::new_v4().to_string();
         
@@ -249,22 +1028,58 @@ impl Database {
         Ok(id)
     }
 
-    pub async fn save_session(&self, title: &str, duration: i32, transcript: &str) -> Result<String, sqlx::Error> {
+    pub async fn save_session(&self, title: &str, duration: i32, transcript: &str, segments: Option<&str>, audio_path: Option<&str>, transcription_model: Option<&str>) -> Result<String, sqlx::Error> {
         let id = uuid::Uuid::new_v4().to_string();
         
         sqlx::query(r#"
-            INSERT INTO sessions (id, title, duration, transcript) VALUES (?, ?, ?, ?)
+            INSERT INTO sessions (id, title, duration, transcript, segments, audio_path, transcription_model) VALUES (?, ?, ?, ?, ?, ?, ?)
         "#)
         .bind(&id)
         .bind(title)
         .bind(duration)
         .bind(transcript)
+        .bind(segments)
+        .bind(audio_path)
+        .bind(transcription_model)
         .execute(&self.pool)
         .await?;
 
         Ok(id)
     }
 
+    /// Insert a session exactly as given (id and timestamps included). Returns false
+    /// without touching anything if a session with that id already exists.
+    pub async fn insert_session_record(&self, session: &SessionRecord) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(r#"
+            INSERT OR IGNORE INTO sessions (id, title, date, duration, transcript, summary, artifacts, folder_id, segments, audio_path, summary_engine, summary_model, transcription_model, language, chapters, is_favorite, notes, translated, deleted_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(&session.id)
+        .bind(&session.title)
+        .bind(&session.date)
+        .bind(session.duration)
+        .bind(&session.transcript)
+        .bind(&session.summary)
+        .bind(session.artifacts.as_ref().map(Artifacts::to_json))
+        .bind(&session.folder_id)
+        .bind(&session.segments)
+        .bind(&session.audio_path)
+        .bind(&session.summary_engine)
+        .bind(&session.summary_model)
+        .bind(&session.transcription_model)
+        .bind(&session.language)
+        .bind(&session.chapters)
+        .bind(session.is_favorite)
+        .bind(&session.notes)
+        .bind(session.translated)
+        .bind(&session.deleted_at)
+        .bind(&session.created_at)
+        .bind(&session.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn update_session_transcript(&self, session_id: &str, transcript: &str) -> Result<(), sqlx::Error> {
         sqlx::query(r#"
             UPDATE sessions SET transcript = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?
@@ -277,6 +1092,32 @@ impl Database {
         Ok(())
     }
 
+    /// Store a transcript the user edited. The timed segments no longer match it and are
+    /// dropped, and `raw` replaces the unredacted copy. Returns false if there's no such session.
+    pub async fn replace_session_transcript(&self, session_id: &str, transcript: &str, raw: Option<&str>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(r#"
+            UPDATE sessions SET transcript = ?, segments = NULL, raw_transcript = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?
+        "#)
+        .bind(transcript)
+        .bind(raw)
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn update_session_segments(&self, session_id: &str, segments: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(r#"
+            UPDATE sessions SET segments = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?
+        "#)
+        .bind(segments)
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_session_summary(&self, session_id: &str, summary: &str) -> Result<(), sqlx::Error> {
         sqlx::query(r#"
             UPDATE sessions SET summary = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?
@@ -289,6 +1130,116 @@ impl Database {
         Ok(())
     }
 
+    /// Set or clear (`None`) the user's notes. Returns false when no session has that id.
+    pub async fn update_session_notes(&self, session_id: &str, notes: Option<&str>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE sessions SET notes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(notes)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the session's artifacts, clearing the column for `None`. Returns false when no
+    /// session has that id.
+    pub async fn update_session_artifacts(&self, session_id: &str, artifacts: Option<&Artifacts>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE sessions SET artifacts = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(artifacts.map(Artifacts::to_json))
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn update_session_translated(&self, session_id: &str, translated: bool) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET translated = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(translated)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_track2_audio(&self, session_id: &str, path: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET track2_audio_path = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(path)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_debug_audio(&self, session_id: &str, raw: Option<&str>, whisper: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET debug_raw_audio_path = ?, debug_whisper_audio_path = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(raw)
+            .bind(whisper)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record which engine/model produced the current summary.
+    pub async fn update_session_summary_engine(&self, session_id: &str, engine: &str, model: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET summary_engine = ?, summary_model = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(engine)
+            .bind(model)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_transcription_model(&self, session_id: &str, model: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET transcription_model = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(model)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_chapters(&self, session_id: &str, chapters: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET chapters = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(chapters)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record the dominant language detected in the session's transcript.
+    /// Set or clear (`None`) the unredacted copy of a session's transcript.
+    pub async fn update_session_raw_transcript(&self, session_id: &str, raw_transcript: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET raw_transcript = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(raw_transcript)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_language(&self, session_id: &str, language: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET language = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(language)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_title(&self, session_id: &str, title: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(r#"
+            UPDATE sessions SET title = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?
+        "#)
+        .bind(title)
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, sqlx::Error> {
         let row = sqlx::query("SELECT * FROM sessions WHERE id = ?")
             .bind(session_id)
@@ -296,47 +1247,256 @@ impl Database {
             .await?;
 
         match row {
-            Some(row) => Ok(Some(SessionRecord {
-                id: row.get("id"),
-                title: row.get("title"),
-                date: row.get("date"),
-                duration: row.get("duration"),
-                transcript: row.get("transcript"),
-                summary: row.get("summary"),
-                artifacts: row.get("artifacts"),
-                folder_id: row.try_get("folder_id").ok(),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            })),
+            Some(row) => Ok(Some(session_from_row(&row))),
             None => Ok(None),
         }
     }
 
     pub async fn list_sessions(&self, limit: Option<i32>) -> Result<Vec<SessionRecord>, sqlx::Error> {
-        let limit_value = limit.unwrap_or(50);
-        let rows = sqlx::query("SELECT * FROM sessions ORDER BY created_at DESC LIMIT ?")
-            .bind(limit_value)
-            .fetch_all(&self.pool)
-            .await?;
+        self.query_sessions(&SessionFilter { limit, ..SessionFilter::default() }).await
+    }
+
+    pub async fn query_sessions(&self, filter: &SessionFilter) -> Result<Vec<SessionRecord>, sqlx::Error> {
+        let limit_value = filter.limit.unwrap_or(50);
+        let mut sql = String::from("SELECT * FROM sessions WHERE deleted_at IS NULL AND is_draft = 0");
+        if filter.favorites_only {
+            sql.push_str(" AND is_favorite = 1");
+        }
+        // Bounds are already in SQLite's "YYYY-MM-DD HH:MM:SS" UTC form (see
+        // `parse_timestamp`); datetime() brings created_at to the same form
+        if filter.from.is_some() {
+            sql.push_str(" AND datetime(created_at) >= ?");
+        }
+        if filter.to.is_some() {
+            sql.push_str(" AND datetime(created_at) <= ?");
+        }
+        sql.push_str(if filter.favorites_first {
+            " ORDER BY is_favorite DESC, created_at DESC LIMIT ?"
+        } else {
+            " ORDER BY created_at DESC LIMIT ?"
+        });
+        let mut query = sqlx::query(&sql);
+        for bound in [&filter.from, &filter.to].into_iter().flatten() {
+            query = query.bind(bound);
+        }
+        let rows = query.bind(limit_value).fetch_all(&self.pool).await?;
 
         let sessions = rows
             .into_iter()
-            .map(|row| SessionRecord {
-                id: row.get("id"),
-                title: row.get("title"),
-                date: row.get("date"),
-                duration: row.get("duration"),
-                transcript: row.get("transcript"),
-                summary: row.get("summary"),
-                artifacts: row.get("artifacts"),
-                folder_id: row.try_get("folder_id").ok(),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            })
+            .map(|row| session_from_row(&row))
             .collect();
 
         Ok(sessions)
     }
+
+    /// Copy the whole database into a new file at `path`, encrypted with `key` or
+    /// plaintext when `key` is None. Used to turn encryption on or off.
+    pub async fn export_to(&self, path: &Path, key: Option<&str>) -> Result<(), sqlx::Error> {
+        // ATTACH is per-connection, so keep everything on one
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("ATTACH DATABASE ? AS migrated KEY ?")
+            .bind(path.to_string_lossy().to_string())
+            .bind(key.unwrap_or(""))
+            .execute(&mut *conn)
+            .await?;
+        let exported = sqlx::query("SELECT sqlcipher_export('migrated')")
+            .execute(&mut *conn)
+            .await;
+        sqlx::query("DETACH DATABASE migrated").execute(&mut *conn).await?;
+        exported.map(|_| ())
+    }
+
+    /// Close every pooled connection so the file can be replaced.
+    pub async fn close(self) {
+        self.pool.close().await;
+    }
+
+    /// Every session row, including drafts and the trash.
+    pub async fn count_sessions(&self) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query("SELECT COUNT(*) AS count FROM sessions")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.get("count"))
+    }
+
+    /// An ISO 8601 timestamp (or plain date) as a UTC "YYYY-MM-DD HH:MM:SS" string, or
+    /// None if SQLite can't parse it. With `end_of_day`, a plain date is its last second
+    /// rather than midnight.
+    pub async fn parse_timestamp(&self, value: &str, end_of_day: bool) -> Result<Option<String>, sqlx::Error> {
+        let sql = if end_of_day {
+            "SELECT datetime(?, '+1 day', '-1 second') AS ts"
+        } else {
+            "SELECT datetime(?) AS ts"
+        };
+        let row = sqlx::query(sql)
+            .bind(value)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.try_get("ts").ok().flatten())
+    }
+
+    /// Returns false when no live session has that id.
+    pub async fn set_session_favorite(&self, session_id: &str, is_favorite: bool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE sessions SET is_favorite = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL")
+            .bind(is_favorite)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn set_session_draft(&self, session_id: &str, is_draft: bool) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET is_draft = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(is_draft)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_session_duration(&self, session_id: &str, duration: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sessions SET duration = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(duration)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Drafts left behind by recordings that were never saved.
+    pub async fn list_draft_sessions(&self) -> Result<Vec<SessionRecord>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM sessions WHERE is_draft = 1 AND deleted_at IS NULL ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(session_from_row).collect())
+    }
+
+    /// Delete a session row outright, skipping the trash (used for drafts).
+    pub async fn delete_session(&self, session_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM session_embeddings WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM participants WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Fold `secondary_id` into `primary_id` in one transaction: the primary takes the
+    /// combined content in `merged` and the secondary's notes and token usage, and the
+    /// secondary is deleted. Returns false, changing nothing, if either is missing or trashed.
+    pub async fn merge_sessions(&self, primary_id: &str, secondary_id: &str, merged: &SessionMerge) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let updated = sqlx::query(r#"
+            UPDATE sessions SET transcript = ?, segments = ?, chapters = ?, raw_transcript = ?, duration = ?,
+                audio_path = COALESCE(?, audio_path), track2_audio_path = COALESCE(?, track2_audio_path), updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND deleted_at IS NULL
+        "#)
+        .bind(&merged.transcript)
+        .bind(&merged.segments)
+        .bind(&merged.chapters)
+        .bind(&merged.raw_transcript)
+        .bind(merged.duration)
+        .bind(&merged.audio_path)
+        .bind(&merged.track2_audio_path)
+        .bind(primary_id)
+        .execute(&mut *tx)
+        .await?;
+        let deleted = sqlx::query("DELETE FROM sessions WHERE id = ? AND deleted_at IS NULL")
+            .bind(secondary_id)
+            .execute(&mut *tx)
+            .await?;
+        if updated.rows_affected() == 0 || deleted.rows_affected() == 0 {
+            // Dropping the transaction rolls it back
+            return Ok(false);
+        }
+        for table in ["notes", "token_usage"] {
+            sqlx::query(&format!("UPDATE {} SET session_id = ? WHERE session_id = ?", table))
+                .bind(primary_id)
+                .bind(secondary_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        // Participants both sessions share stay on the primary once
+        sqlx::query("UPDATE OR IGNORE participants SET session_id = ? WHERE session_id = ?")
+            .bind(primary_id)
+            .bind(secondary_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM participants WHERE session_id = ?")
+            .bind(secondary_id)
+            .execute(&mut *tx)
+            .await?;
+        // The primary's embeddings no longer cover its transcript; the caller reindexes
+        sqlx::query("DELETE FROM session_embeddings WHERE session_id IN (?, ?)")
+            .bind(primary_id)
+            .bind(secondary_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Move a session to the trash. Returns false if it doesn't exist or is already trashed.
+    pub async fn trash_session(&self, session_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE sessions SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a session back out of the trash. Returns false if it isn't in the trash.
+    pub async fn restore_session(&self, session_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE sessions SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_trashed_sessions(&self) -> Result<Vec<SessionRecord>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM sessions WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(session_from_row).collect())
+    }
+
+    /// Permanently delete trashed sessions, either all of them (`older_than_days` = None)
+    /// or only those trashed more than that many days ago. Returns the deleted records so
+    /// the caller can clean up their audio files.
+    pub async fn purge_trashed_sessions(&self, older_than_days: Option<i64>) -> Result<Vec<SessionRecord>, sqlx::Error> {
+        let cutoff = format!("-{} days", older_than_days.unwrap_or(0).max(0));
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query("SELECT * FROM sessions WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?)")
+            .bind(&cutoff)
+            .fetch_all(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM notes WHERE session_id IN (SELECT id FROM sessions WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?))")
+            .bind(&cutoff)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM session_embeddings WHERE session_id IN (SELECT id FROM sessions WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?))")
+            .bind(&cutoff)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM participants WHERE session_id IN (SELECT id FROM sessions WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?))")
+            .bind(&cutoff)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM sessions WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?)")
+            .bind(&cutoff)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(rows.iter().map(session_from_row).collect())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -347,12 +1507,98 @@ pub struct SessionRecord {
     pub duration: i32,
     pub transcript: Option<String>,
     pub summary: Option<String>,
-    pub artifacts: Option<String>,
+    pub artifacts: Option<Artifacts>,
     pub folder_id: Option<String>,
+    pub segments: Option<String>,
+    pub audio_path: Option<String>,
+    pub summary_engine: Option<String>,
+    pub summary_model: Option<String>,
+    pub transcription_model: Option<String>,
+    pub language: Option<String>,
+    /// JSON array of `llm::Chapter`, set by `generate_chapters`
+    pub chapters: Option<String>,
+    /// Unredacted transcript, kept only when `keep_raw_transcript` was on at redaction time
+    pub raw_transcript: Option<String>,
+    pub is_favorite: bool,
+    /// Free-form notes the user wrote about the session
+    pub notes: Option<String>,
+    /// The mic as captured, before gain, processing and mixing (`debug_save_audio`)
+    pub debug_raw_audio_path: Option<String>,
+    /// The 16 kHz mono audio passed to Whisper, chunk after chunk (`debug_save_audio`)
+    pub debug_whisper_audio_path: Option<String>,
+    /// Track 2 of a two-track recording (`audio_path` is track 1)
+    pub track2_audio_path: Option<String>,
+    /// The transcript is an English translation (`translate_to_english` was on)
+    pub translated: bool,
+    pub deleted_at: Option<String>,
+    pub is_draft: bool,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Combined content written to the primary session by `Database::merge_sessions`.
+#[derive(Debug, Default)]
+pub struct SessionMerge {
+    pub transcript: Option<String>,
+    pub segments: Option<String>,
+    pub chapters: Option<String>,
+    pub raw_transcript: Option<String>,
+    pub duration: i32,
+    /// Replaces the primary's recording when set
+    pub audio_path: Option<String>,
+    /// Replaces the primary's track 2 recording when set
+    pub track2_audio_path: Option<String>,
+}
+
+/// Options for `Database::query_sessions`. Drafts and trashed sessions are never included.
+#[derive(Debug, Default, Clone)]
+pub struct SessionFilter {
+    /// Defaults to 50; negative means no limit
+    pub limit: Option<i32>,
+    pub favorites_only: bool,
+    /// Favorites ahead of everything else, each group newest first
+    pub favorites_first: bool,
+    /// Inclusive created_at bounds as returned by `Database::parse_timestamp`
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+fn session_from_row(row: &SqliteRow) -> SessionRecord {
+    SessionRecord {
+        id: row.get("id"),
+        title: row.get("title"),
+        date: row.get("date"),
+        duration: row.get("duration"),
+        transcript: row.get("transcript"),
+        summary: row.get("summary"),
+        artifacts: row
+            .try_get::<Option<String>, _>("artifacts")
+            .ok()
+            .flatten()
+            .as_deref()
+            .and_then(Artifacts::from_json),
+        folder_id: row.try_get("folder_id").ok(),
+        segments: row.try_get("segments").ok().flatten(),
+        audio_path: row.try_get("audio_path").ok().flatten(),
+        summary_engine: row.try_get("summary_engine").ok().flatten(),
+        summary_model: row.try_get("summary_model").ok().flatten(),
+        transcription_model: row.try_get("transcription_model").ok().flatten(),
+        language: row.try_get("language").ok().flatten(),
+        chapters: row.try_get("chapters").ok().flatten(),
+        raw_transcript: row.try_get("raw_transcript").ok().flatten(),
+        is_favorite: row.try_get("is_favorite").unwrap_or(false),
+        notes: row.try_get("notes").ok().flatten(),
+        debug_raw_audio_path: row.try_get("debug_raw_audio_path").ok().flatten(),
+        debug_whisper_audio_path: row.try_get("debug_whisper_audio_path").ok().flatten(),
+        track2_audio_path: row.try_get("track2_audio_path").ok().flatten(),
+        translated: row.try_get("translated").unwrap_or(false),
+        deleted_at: row.try_get("deleted_at").ok().flatten(),
+        is_draft: row.try_get("is_draft").unwrap_or(false),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FolderRecord {
     pub id: String,
@@ -384,6 +1630,31 @@ impl Database {
         }).collect())
     }
 
+    /// Insert a folder keeping its id; false if the id or name is already taken.
+    pub async fn insert_folder_record(&self, folder: &FolderRecord) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("INSERT OR IGNORE INTO folders (id, name, created_at, updated_at) VALUES (?, ?, ?, ?)")
+            .bind(&folder.id)
+            .bind(&folder.name)
+            .bind(&folder.created_at)
+            .bind(&folder.updated_at)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_folder(&self, folder_id: &str) -> Result<Option<FolderRecord>, sqlx::Error> {
+        let row = sqlx::query("SELECT * FROM folders WHERE id = ?")
+            .bind(folder_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|row| FolderRecord {
+            id: row.get("id"),
+            name: row.get("name"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }))
+    }
+
     pub async fn assign_session_folder(&self, session_id: &str, folder_id: Option<&str>) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE sessions SET folder_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
             .bind(folder_id)
@@ -392,4 +1663,291 @@ impl Database {
             .await?;
         Ok(())
     }
+
+    /// Move many sessions into `folder_id` (or out of any folder) in one transaction.
+    /// Unknown ids are skipped; returns how many sessions were updated.
+    pub async fn assign_sessions_folder(&self, session_ids: &[String], folder_id: Option<&str>) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut updated = 0;
+        for session_id in session_ids {
+            let result = sqlx::query("UPDATE sessions SET folder_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+                .bind(folder_id)
+                .bind(session_id)
+                .execute(&mut *tx)
+                .await?;
+            updated += result.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(updated)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoteRecord {
+    pub id: String,
+    pub session_id: Option<String>,
+    pub text: String,
+    pub created_at: String,
+}
+
+fn note_from_row(row: &SqliteRow) -> NoteRecord {
+    NoteRecord {
+        id: row.get("id"),
+        session_id: row.try_get("session_id").ok().flatten(),
+        text: row.get("text"),
+        created_at: row.get("created_at"),
+    }
+}
+
+impl Database {
+    pub async fn create_note(&self, text: &str, session_id: Option<&str>) -> Result<NoteRecord, sqlx::Error> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO notes (id, session_id, text) VALUES (?, ?, ?)")
+            .bind(&id)
+            .bind(session_id)
+            .bind(text)
+            .execute(&self.pool)
+            .await?;
+        let row = sqlx::query("SELECT * FROM notes WHERE id = ?")
+            .bind(&id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(note_from_row(&row))
+    }
+
+    /// Notes for one session, or every note when `session_id` is None, oldest first.
+    pub async fn list_notes(&self, session_id: Option<&str>) -> Result<Vec<NoteRecord>, sqlx::Error> {
+        let rows = match session_id {
+            Some(session_id) => {
+                sqlx::query("SELECT * FROM notes WHERE session_id = ? ORDER BY created_at ASC")
+                    .bind(session_id)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+            None => {
+                sqlx::query("SELECT * FROM notes ORDER BY created_at ASC")
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+        };
+        Ok(rows.iter().map(note_from_row).collect())
+    }
+
+    /// Attach notes taken during a recording to the session it was saved as.
+    pub async fn attach_notes(&self, note_ids: &[String], session_id: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for note_id in note_ids {
+            sqlx::query("UPDATE notes SET session_id = ? WHERE id = ? AND session_id IS NULL")
+                .bind(session_id)
+                .bind(note_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn record_token_usage(&self, session_id: Option<&str>, engine: &str, model: &str, usage: &TokenUsage) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO token_usage (session_id, engine, model, input_tokens, output_tokens, estimated) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(session_id)
+            .bind(engine)
+            .bind(model)
+            .bind(usage.input_tokens as i64)
+            .bind(usage.output_tokens as i64)
+            .bind(usage.estimated)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Token totals per engine/model, across everything or for one session.
+    pub async fn token_usage_totals(&self, session_id: Option<&str>) -> Result<Vec<UsageTotal>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT engine, model, SUM(input_tokens) AS input_tokens, SUM(output_tokens) AS output_tokens, \
+             COUNT(*) AS requests, MAX(estimated) AS estimated \
+             FROM token_usage WHERE (?1 IS NULL OR session_id = ?1) GROUP BY engine, model ORDER BY engine, model",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| UsageTotal {
+                engine: row.get("engine"),
+                model: row.get("model"),
+                input_tokens: row.get("input_tokens"),
+                output_tokens: row.get("output_tokens"),
+                requests: row.get("requests"),
+                estimated: row.try_get("estimated").unwrap_or(false),
+            })
+            .collect())
+    }
+}
+
+impl Database {
+    /// Replace a session's embedding chunks (vectors already encoded as BLOBs).
+    pub async fn replace_session_embeddings(&self, session_id: &str, model: &str, vectors: &[Vec<u8>]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM session_embeddings WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+        for (index, vector) in vectors.iter().enumerate() {
+            sqlx::query("INSERT INTO session_embeddings (session_id, chunk_index, model, vector) VALUES (?, ?, ?, ?)")
+                .bind(session_id)
+                .bind(index as i64)
+                .bind(model)
+                .bind(vector)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Every chunk vector made with `model` for visible (not trashed, not draft) sessions.
+    pub async fn session_embeddings(&self, model: &str) -> Result<Vec<(String, Vec<u8>)>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT e.session_id, e.vector FROM session_embeddings e \
+             JOIN sessions s ON s.id = e.session_id \
+             WHERE e.model = ? AND s.deleted_at IS NULL AND s.is_draft = 0",
+        )
+        .bind(model)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(|row| (row.get("session_id"), row.get("vector"))).collect())
+    }
+
+    /// Sessions with a transcript but no vectors for `model` yet.
+    pub async fn sessions_missing_embeddings(&self, model: &str) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id FROM sessions WHERE deleted_at IS NULL AND is_draft = 0 \
+             AND transcript IS NOT NULL AND transcript != '' \
+             AND id NOT IN (SELECT session_id FROM session_embeddings WHERE model = ?)",
+        )
+        .bind(model)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+}
+
+impl Database {
+    /// Replace the session's participants from one `source` ('label' or 'llm'). A name
+    /// already recorded from the other source is kept as it is.
+    pub async fn replace_session_participants(&self, session_id: &str, source: &str, names: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM participants WHERE session_id = ? AND source = ?")
+            .bind(session_id)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+        for name in names {
+            sqlx::query("INSERT OR IGNORE INTO participants (session_id, name, source) VALUES (?, ?, ?)")
+                .bind(session_id)
+                .bind(name)
+                .bind(source)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Everyone seen across visible sessions, most frequent first. Names are matched
+    /// case-insensitively.
+    pub async fn list_participants(&self) -> Result<Vec<ParticipantSummary>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT p.name AS name, COUNT(DISTINCT p.session_id) AS sessions, MAX(s.created_at) AS last_seen, \
+             MAX(p.source = 'llm') AS named, GROUP_CONCAT(DISTINCT p.session_id) AS session_ids \
+             FROM participants p JOIN sessions s ON s.id = p.session_id \
+             WHERE s.deleted_at IS NULL AND s.is_draft = 0 \
+             GROUP BY p.name COLLATE NOCASE ORDER BY sessions DESC, p.name COLLATE NOCASE",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| ParticipantSummary {
+                name: row.get("name"),
+                sessions: row.get("sessions"),
+                last_seen: row.try_get("last_seen").ok().flatten(),
+                named: row.try_get("named").unwrap_or(false),
+                session_ids: row
+                    .try_get::<Option<String>, _>("session_ids")
+                    .ok()
+                    .flatten()
+                    .map(|ids| ids.split(',').map(str::to_string).collect())
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+/// A person across sessions, as returned by `Database::list_participants`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticipantSummary {
+    pub name: String,
+    pub sessions: i64,
+    pub last_seen: Option<String>,
+    /// True when the name came from the transcript rather than a speaker label
+    pub named: bool,
+    pub session_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageTotal {
+    pub engine: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub requests: i64,
+    /// True if any of the counts were estimated rather than reported by the API
+    pub estimated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disjoint_partial_updates_both_survive() {
+        let stored = Settings::default();
+        let first = stored.merged(&serde_json::json!({ "chunk_seconds": 4.0 })).unwrap();
+        let second = first.merged(&serde_json::json!({ "language": "es" })).unwrap();
+        assert_eq!(second.chunk_seconds, 4.0);
+        assert_eq!(second.language, "es");
+        assert_eq!(second.summary_engine, stored.summary_engine);
+    }
+
+    #[test]
+    fn merged_rejects_unknown_keys() {
+        let err = Settings::default().merged(&serde_json::json!({ "no_such_setting": true })).unwrap_err();
+        assert!(err.contains("no_such_setting"), "{}", err);
+    }
+
+    #[test]
+    fn merged_rejects_wrong_types() {
+        assert!(Settings::default().merged(&serde_json::json!({ "chunk_seconds": "long" })).is_err());
+        assert!(Settings::default().merged(&serde_json::json!(["chunk_seconds"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn edited_transcript_replaces_segments_in_export() {
+        let path = std::env::temp_dir().join(format!("oatmeal-test-{}.db", uuid::Uuid::new_v4()));
+        let database = Database::new(path.to_str().unwrap(), None).await.unwrap();
+        let segments = r#"[{"start_ms":0,"end_ms":2000,"text":"Thanks, Jon."}]"#;
+        let id = database.save_session("Standup", 2, "Thanks, Jon.", Some(segments), None, None).await.unwrap();
+
+        assert!(database.replace_session_transcript(&id, "Thanks, John.", None).await.unwrap());
+        let session = database.get_session(&id).await.unwrap().unwrap();
+        let markdown = crate::export::session_markdown(&session);
+        assert!(markdown.contains("Thanks, John."), "{}", markdown);
+        assert!(!markdown.contains("Jon."), "{}", markdown);
+        assert!(session.raw_transcript.is_none());
+
+        assert!(!database.replace_session_transcript("missing", "text", None).await.unwrap());
+        drop(database);
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,178 @@
+//! Lightweight speaker diarization for in-room meetings.
+//!
+//! Each transcribed chunk is reduced to a spectral-envelope embedding (mean and spread
+//! of log band energies over voiced frames) and greedily clustered against running
+//! speaker centroids. This is a heuristic, not a neural speaker model: it separates
+//! voices with clearly different timbre well, and confuses similar ones.
+//!
+//! Cost: a naive DFT over 16 ms frames, roughly 10 ms of CPU per 2.5 s chunk, paid on
+//! the transcription path while the transcriber lock is released. Off unless
+//! `enable_diarization` is set.
+
+use crate::audio::resample::StreamResampler;
+
+pub const DEFAULT_MAX_SPEAKERS: usize = 2;
+
+const RATE: u32 = 16_000;
+const FRAME: usize = 256; // 16 ms at 16 kHz
+const BANDS: usize = 20;
+// Below this cosine similarity to every centroid, a new speaker is opened (if allowed)
+const NEW_SPEAKER_THRESHOLD: f32 = 0.80;
+// Frames quieter than this (RMS) are ignored when building the embedding
+const VOICED_RMS: f32 = 0.01;
+
+/// Mel-spaced band edges over DFT bins 1..FRAME/2.
+fn band_edges() -> [usize; BANDS + 1] {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+    let lo = hz_to_mel(80.0);
+    let hi = hz_to_mel(7600.0);
+    let bin_hz = RATE as f32 / FRAME as f32;
+    let mut edges = [0usize; BANDS + 1];
+    for (i, edge) in edges.iter_mut().enumerate() {
+        let hz = mel_to_hz(lo + (hi - lo) * i as f32 / BANDS as f32);
+        *edge = ((hz / bin_hz).round() as usize).clamp(1, FRAME / 2);
+    }
+    // Keep every band at least one bin wide
+    for i in 1..edges.len() {
+        if edges[i] <= edges[i - 1] {
+            edges[i] = (edges[i - 1] + 1).min(FRAME / 2);
+        }
+    }
+    edges
+}
+
+/// Spectral embedding of a chunk, or None if it has too little voiced audio.
+pub fn embed(samples: &[f32], sample_rate: u32) -> Option<Vec<f32>> {
+    let audio = StreamResampler::new(sample_rate, RATE).process(samples);
+    let edges = band_edges();
+
+    // Precompute DFT twiddles for the bins we use
+    let max_bin = edges[BANDS];
+    let window: Vec<f32> = (0..FRAME)
+        .map(|n| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / FRAME as f32).cos())
+        .collect();
+    let mut cos_t = vec![0f32; (max_bin + 1) * FRAME];
+    let mut sin_t = vec![0f32; (max_bin + 1) * FRAME];
+    for k in 1..=max_bin {
+        for n in 0..FRAME {
+            let phase = 2.0 * std::f32::consts::PI * (k * n) as f32 / FRAME as f32;
+            cos_t[k * FRAME + n] = phase.cos();
+            sin_t[k * FRAME + n] = phase.sin();
+        }
+    }
+
+    let mut frames: Vec<[f32; BANDS]> = Vec::new();
+    for chunk in audio.chunks_exact(FRAME) {
+        let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / FRAME as f32).sqrt();
+        if rms < VOICED_RMS {
+            continue;
+        }
+        let windowed: Vec<f32> = chunk.iter().zip(&window).map(|(s, w)| s * w).collect();
+        let mut power = vec![0f32; max_bin + 1];
+        for k in 1..=max_bin {
+            let (mut re, mut im) = (0f32, 0f32);
+            let row = k * FRAME;
+            for n in 0..FRAME {
+                re += windowed[n] * cos_t[row + n];
+                im -= windowed[n] * sin_t[row + n];
+            }
+            power[k] = re * re + im * im;
+        }
+        let mut bands = [0f32; BANDS];
+        for b in 0..BANDS {
+            let energy: f32 = power[edges[b]..edges[b + 1].max(edges[b] + 1).min(max_bin + 1)].iter().sum();
+            bands[b] = (energy + 1e-9).ln();
+        }
+        // Remove overall loudness so distance to the mic doesn't dominate
+        let mean = bands.iter().sum::<f32>() / BANDS as f32;
+        for v in bands.iter_mut() {
+            *v -= mean;
+        }
+        frames.push(bands);
+    }
+
+    // Need ~0.3 s of speech for a stable estimate
+    if frames.len() < 20 {
+        return None;
+    }
+
+    let n = frames.len() as f32;
+    let mut embedding = vec![0f32; BANDS * 2];
+    for f in &frames {
+        for b in 0..BANDS {
+            embedding[b] += f[b] / n;
+        }
+    }
+    for f in &frames {
+        for b in 0..BANDS {
+            embedding[BANDS + b] += (f[b] - embedding[b]).powi(2) / n;
+        }
+    }
+    for v in embedding[BANDS..].iter_mut() {
+        *v = v.sqrt();
+    }
+    normalize(&mut embedding);
+    Some(embedding)
+}
+
+fn normalize(v: &mut [f32]) {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for x in v.iter_mut() {
+            *x /= norm;
+        }
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+struct Speaker {
+    centroid: Vec<f32>,
+    count: usize,
+}
+
+/// Greedy online clustering of chunk embeddings into at most `max_speakers` labels.
+pub struct Diarizer {
+    speakers: Vec<Speaker>,
+    max_speakers: usize,
+}
+
+impl Diarizer {
+    pub fn new(max_speakers: usize) -> Self {
+        Self { speakers: Vec::new(), max_speakers: max_speakers.max(1) }
+    }
+
+    /// Label a chunk "S1", "S2", ... or None if it doesn't contain enough speech.
+    pub fn assign(&mut self, samples: &[f32], sample_rate: u32) -> Option<String> {
+        let embedding = embed(samples, sample_rate)?;
+
+        let best = self
+            .speakers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, cosine(&s.centroid, &embedding)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let index = match best {
+            Some((i, similarity)) if similarity >= NEW_SPEAKER_THRESHOLD || self.speakers.len() >= self.max_speakers => {
+                // Running mean keeps the centroid stable as the speaker talks more
+                let speaker = &mut self.speakers[i];
+                speaker.count += 1;
+                let w = 1.0 / speaker.count as f32;
+                for (c, e) in speaker.centroid.iter_mut().zip(&embedding) {
+                    *c = *c * (1.0 - w) + e * w;
+                }
+                normalize(&mut speaker.centroid);
+                i
+            }
+            _ => {
+                self.speakers.push(Speaker { centroid: embedding, count: 1 });
+                self.speakers.len() - 1
+            }
+        };
+        Some(format!("S{}", index + 1))
+    }
+}
@@ -0,0 +1,141 @@
+//! Optional semantic index over transcripts. Each saved transcript is split into chunks
+//! that are embedded with Ollama or OpenAI and stored as little-endian f32 BLOBs; a query
+//! is embedded the same way and sessions are ranked by their best-matching chunk. At a
+//! few thousand sessions a brute-force cosine in Rust is fast enough that a vector
+//! database isn't worth the dependency.
+
+use std::time::Duration;
+
+use reqwest::Client;
+
+use crate::database::Settings;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+// Small enough to fit every common embedding model's context window
+const CHUNK_WORDS: usize = 400;
+const CHUNK_OVERLAP_WORDS: usize = 50;
+// Very long meetings are indexed by their first chunks only
+const MAX_CHUNKS: usize = 32;
+
+const DEFAULT_OLLAMA_MODEL: &str = "nomic-embed-text";
+const DEFAULT_OPENAI_MODEL: &str = "text-embedding-3-small";
+
+/// Whether `embedding_engine` is set to something other than "none".
+pub fn enabled(settings: &Settings) -> bool {
+    matches!(settings.embedding_engine.as_str(), "ollama" | "openai")
+}
+
+/// Model name stored next to each vector, so switching models doesn't compare
+/// incompatible embeddings.
+pub fn model_id(settings: &Settings) -> String {
+    let model = settings.embedding_model.trim();
+    let model = match (settings.embedding_engine.as_str(), model.is_empty()) {
+        ("openai", true) => DEFAULT_OPENAI_MODEL,
+        (_, true) => DEFAULT_OLLAMA_MODEL,
+        (_, false) => model,
+    };
+    format!("{}:{}", settings.embedding_engine, model)
+}
+
+fn model_name(settings: &Settings) -> String {
+    let id = model_id(settings);
+    id.split_once(':').map(|(_, m)| m.to_string()).unwrap_or(id)
+}
+
+/// Overlapping word windows of a transcript, at most `MAX_CHUNKS`.
+pub fn chunks(transcript: &str) -> Vec<String> {
+    let words: Vec<&str> = transcript.split_whitespace().collect();
+    let step = CHUNK_WORDS - CHUNK_OVERLAP_WORDS;
+    let mut out = Vec::new();
+    let mut start = 0;
+    while start < words.len() && out.len() < MAX_CHUNKS {
+        let end = (start + CHUNK_WORDS).min(words.len());
+        out.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+        start += step;
+    }
+    out
+}
+
+/// Embed `text` with the configured engine.
+pub async fn embed(settings: &Settings, text: &str) -> Result<Vec<f32>, String> {
+    let client = Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let model = model_name(settings);
+    let json: serde_json::Value = match settings.embedding_engine.as_str() {
+        "ollama" => {
+            let url = format!("{}/api/embeddings", settings.ollama_host.trim_end_matches('/'));
+            let resp = client
+                .post(&url)
+                .json(&serde_json::json!({ "model": model, "prompt": text }))
+                .send()
+                .await
+                .map_err(|e| format!("Ollama embeddings request failed: {}", e))?;
+            if !resp.status().is_success() {
+                return Err(format!("Ollama embeddings error ({}): {}", resp.status(), resp.text().await.unwrap_or_default()));
+            }
+            resp.json().await.map_err(|e| format!("Invalid Ollama embeddings response: {}", e))?
+        }
+        "openai" => {
+            let key = std::env::var("OPENAI_API_KEY")
+                .ok()
+                .filter(|k| !k.trim().is_empty())
+                .ok_or("OPENAI_API_KEY not set")?;
+            let resp = client
+                .post(format!("{}/embeddings", settings.openai_base_url.trim_end_matches('/')))
+                .bearer_auth(key.trim())
+                .json(&serde_json::json!({ "model": model, "input": text }))
+                .send()
+                .await
+                .map_err(|e| format!("OpenAI embeddings request failed: {}", e))?;
+            if !resp.status().is_success() {
+                return Err(format!("OpenAI embeddings error ({}): {}", resp.status(), resp.text().await.unwrap_or_default()));
+            }
+            resp.json().await.map_err(|e| format!("Invalid OpenAI embeddings response: {}", e))?
+        }
+        other => return Err(format!("Semantic search is off (embedding engine '{}')", other)),
+    };
+
+    let values = json
+        .get("embedding")
+        .or_else(|| json.pointer("/data/0/embedding"))
+        .and_then(|v| v.as_array())
+        .ok_or("Embeddings response had no vector")?;
+    let vector: Vec<f32> = values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+    if vector.is_empty() {
+        return Err("Embeddings response had an empty vector".to_string());
+    }
+    Ok(vector)
+}
+
+pub fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Cosine similarity; 0.0 for mismatched lengths or zero vectors.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
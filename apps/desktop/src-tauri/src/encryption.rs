@@ -0,0 +1,49 @@
+//! Optional at-rest encryption for the session database (SQLCipher).
+//!
+//! A random passphrase is generated the first time `encrypt_database` is turned on and
+//! stored in the OS keychain; SQLCipher derives the page key from it (PBKDF2) via
+//! `PRAGMA key`. There is no recovery path: if the keychain item is deleted, or the
+//! database is moved to a machine without it, the encrypted database can't be opened and
+//! every session in it is lost.
+
+use std::io::Read;
+use std::path::Path;
+
+const KEYCHAIN_SERVICE: &str = "com.oatmeal.desktop";
+const KEYCHAIN_ACCOUNT: &str = "database-passphrase";
+/// Every plaintext SQLite file starts with this; a SQLCipher file is indistinguishable from noise.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Whether the file at `db_path` is encrypted. Missing or empty files count as plaintext,
+/// since that's how a new database is created.
+pub fn is_encrypted(db_path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    match std::fs::File::open(db_path).and_then(|mut f| f.read_exact(&mut header)) {
+        Ok(()) => &header != SQLITE_HEADER,
+        Err(_) => false,
+    }
+}
+
+fn keychain_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT).map_err(|e| format!("Keychain unavailable: {}", e))
+}
+
+pub fn stored_passphrase() -> Result<Option<String>, String> {
+    match keychain_entry()?.get_password() {
+        Ok(passphrase) => Ok(Some(passphrase)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read database key from keychain: {}", e)),
+    }
+}
+
+pub fn get_or_create_passphrase() -> Result<String, String> {
+    if let Some(passphrase) = stored_passphrase()? {
+        return Ok(passphrase);
+    }
+    // Two v4 UUIDs carry 244 bits from the OS RNG, hex-only so it's safe to inline in PRAGMA key
+    let passphrase = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    keychain_entry()?
+        .set_password(&passphrase)
+        .map_err(|e| format!("Failed to store database key in keychain: {}", e))?;
+    Ok(passphrase)
+}
@@ -0,0 +1,98 @@
+//! Errors returned by Tauri commands. Each one reaches the frontend as
+//! `{ code, message }`, so the UI can branch on `code` (e.g. show the permissions prompt
+//! for `permission`) and only display `message`.
+
+use serde::ser::SerializeStruct;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// The database couldn't be opened, read or written
+    Database(String),
+    /// An input device or the system-audio feed failed
+    Audio(String),
+    /// The OS denied microphone or screen-recording access
+    Permission(String),
+    /// Whisper or a cloud transcription engine failed
+    Transcription(String),
+    /// An LLM, CRM, email or other remote service couldn't be reached or refused the request
+    Network(String),
+    /// The session, folder or other record doesn't exist
+    NotFound(String),
+    /// The arguments, or the current state, don't allow the request
+    InvalidInput(String),
+    /// The user stopped the operation before it finished
+    Cancelled(String),
+    /// Anything not classified above
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "database",
+            AppError::Audio(_) => "audio",
+            AppError::Permission(_) => "permission",
+            AppError::Transcription(_) => "transcription",
+            AppError::Network(_) => "network",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Cancelled(_) => "cancelled",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Database(m)
+            | AppError::Audio(m)
+            | AppError::Permission(m)
+            | AppError::Transcription(m)
+            | AppError::Network(m)
+            | AppError::NotFound(m)
+            | AppError::InvalidInput(m)
+            | AppError::Cancelled(m)
+            | AppError::Internal(m) => m,
+        }
+    }
+
+    pub fn no_database() -> Self {
+        AppError::Database("Database not initialized".to_string())
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl serde::Serialize for AppError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", self.message())?;
+        error.end()
+    }
+}
+
+// Helpers still return `Result<_, String>`; their errors surface as `internal` unless the
+// command classifies them with `map_err`
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message().to_string()
+    }
+}
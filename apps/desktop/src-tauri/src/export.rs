@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::{FolderRecord, SessionRecord};
+use crate::llm::Chapter;
+use crate::transcribe::TranscriptSegment;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionItem {
+    pub text: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub due: Option<String>,
+}
+
+/// Structured outputs of a meeting, stored as JSON in `sessions.artifacts`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Artifacts {
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    #[serde(default)]
+    pub questions: Vec<String>,
+}
+
+impl Artifacts {
+    /// Read the `artifacts` column. Older rows may hold action items as plain strings or
+    /// under the pipeline's `todos` key; anything that isn't a JSON object gives `None`.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(json).ok()?;
+        let object = value.as_object()?;
+        let strings = |key: &str| -> Vec<String> {
+            match object.get(key) {
+                Some(serde_json::Value::Array(values)) => values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+                _ => Vec::new(),
+            }
+        };
+        let action_items = match object.get("action_items").or_else(|| object.get("todos")) {
+            Some(serde_json::Value::Array(values)) => values.iter().filter_map(action_item_from_value).collect(),
+            _ => Vec::new(),
+        };
+        Some(Artifacts { action_items, decisions: strings("decisions"), questions: strings("questions") })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.action_items.is_empty() && self.decisions.is_empty() && self.questions.is_empty()
+    }
+
+    /// Fill each list from the matching section of a Markdown summary ("Action Items",
+    /// "Decisions", "Open Questions"). Lists the summary has no section for are kept, and
+    /// action items the summary repeats keep the owner and due date they already had.
+    pub fn merge_summary(&mut self, summary: &str) {
+        let action_items = summary_action_items(summary);
+        if !action_items.is_empty() {
+            let existing = std::mem::take(&mut self.action_items);
+            self.action_items = action_items
+                .into_iter()
+                .map(|text| {
+                    let key = text.trim().to_lowercase();
+                    match existing.iter().find(|item| item.text.trim().to_lowercase() == key) {
+                        Some(item) => ActionItem { text, owner: item.owner.clone(), due: item.due.clone() },
+                        None => ActionItem { text, owner: None, due: None },
+                    }
+                })
+                .collect();
+        }
+        let decisions = summary_section_items(summary, &["decision"]);
+        if !decisions.is_empty() {
+            self.decisions = decisions;
+        }
+        let questions = summary_section_items(summary, &["open question", "question"]);
+        if !questions.is_empty() {
+            self.questions = questions;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportFolder {
+    pub id: String,
+    pub name: String,
+}
+
+/// Canonical machine-readable form of a session, used for JSON export and backups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    pub id: String,
+    pub title: String,
+    pub date: String,
+    pub duration: i32,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub transcript: Option<String>,
+    /// `transcript` was translated to English from `language`
+    #[serde(default)]
+    pub translated: bool,
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    #[serde(default)]
+    pub questions: Vec<String>,
+    #[serde(default)]
+    pub folder: Option<ExportFolder>,
+    #[serde(default)]
+    pub summary_engine: Option<String>,
+    #[serde(default)]
+    pub summary_model: Option<String>,
+    #[serde(default)]
+    pub transcription_model: Option<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn format_timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn format_duration(seconds: i32) -> String {
+    let seconds = seconds.max(0);
+    if seconds >= 60 {
+        format!("{} min", (seconds + 30) / 60)
+    } else {
+        format!("{} s", seconds)
+    }
+}
+
+/// Parse the stored `segments` JSON; bad or missing data yields an empty list.
+pub fn parse_segments(session: &SessionRecord) -> Vec<TranscriptSegment> {
+    session
+        .segments
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Render a session as Markdown. Segments are listed with timestamps and speaker
+/// labels when available; otherwise the flat transcript is used.
+pub fn parse_chapters(session: &SessionRecord) -> Vec<Chapter> {
+    session
+        .chapters
+        .as_deref()
+        .and_then(|c| serde_json::from_str(c).ok())
+        .unwrap_or_default()
+}
+
+pub fn session_markdown(session: &SessionRecord) -> String {
+    let mut out = format!("# {}\n\n_{} · {}_\n", session.title, session.date, format_duration(session.duration));
+
+    if let Some(summary) = session.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        out.push_str("\n## Summary\n\n");
+        out.push_str(summary.trim());
+        out.push('\n');
+    }
+
+    if let Some(notes) = session.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        out.push_str("\n## Notes\n\n");
+        out.push_str(notes.trim());
+        out.push('\n');
+    }
+
+    let chapters = parse_chapters(session);
+    if !chapters.is_empty() {
+        out.push_str("\n## Chapters\n\n");
+        for chapter in &chapters {
+            out.push_str(&format!("- [{}] {}\n", format_timestamp(chapter.start_ms), chapter.title));
+        }
+    }
+
+    out.push_str("\n## Transcript\n\n");
+    let segments = parse_segments(session);
+    if segments.is_empty() {
+        out.push_str(session.transcript.as_deref().unwrap_or("").trim());
+        out.push('\n');
+    } else {
+        for segment in &segments {
+            let stamp = format_timestamp(segment.start_ms);
+            match segment.speaker.as_deref() {
+                Some(speaker) => out.push_str(&format!("**[{}] {}:** {}\n\n", stamp, speaker, segment.text.trim())),
+                None => out.push_str(&format!("**[{}]** {}\n\n", stamp, segment.text.trim())),
+            }
+        }
+    }
+    out
+}
+
+/// Lines under a Markdown summary's "Action Items" heading, if it has one.
+pub fn summary_action_items(summary: &str) -> Vec<String> {
+    summary_section_items(summary, &["action item"])
+}
+
+/// Lines under any heading that starts with one of `headings` (lowercase).
+fn summary_section_items(summary: &str, headings: &[&str]) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut in_section = false;
+    for line in summary.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#').trim().to_lowercase();
+            in_section = headings.iter().any(|h| heading.starts_with(h));
+            continue;
+        }
+        if in_section {
+            let item = trimmed.trim_start_matches(|c: char| c == '-' || c == '*' || c == ' ');
+            if !item.is_empty() {
+                items.push(item.to_string());
+            }
+        }
+    }
+    items
+}
+
+fn action_item_from_value(value: &serde_json::Value) -> Option<ActionItem> {
+    match value {
+        serde_json::Value::String(text) => Some(ActionItem { text: text.clone(), owner: None, due: None }),
+        serde_json::Value::Object(_) => serde_json::from_value(value.clone()).ok(),
+        _ => None,
+    }
+}
+
+/// Action items from the session's artifacts, falling back to the summary's
+/// "Action Items" section.
+pub fn parse_action_items(session: &SessionRecord) -> Vec<ActionItem> {
+    if let Some(artifacts) = session.artifacts.as_ref().filter(|a| !a.action_items.is_empty()) {
+        return artifacts.action_items.clone();
+    }
+    session
+        .summary
+        .as_deref()
+        .map(summary_action_items)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|text| ActionItem { text, owner: None, due: None })
+        .collect()
+}
+
+pub fn session_export(session: &SessionRecord, folder: Option<&FolderRecord>) -> SessionExport {
+    SessionExport {
+        id: session.id.clone(),
+        title: session.title.clone(),
+        date: session.date.clone(),
+        duration: session.duration,
+        language: session.language.clone(),
+        transcript: session.transcript.clone(),
+        translated: session.translated,
+        segments: parse_segments(session),
+        chapters: parse_chapters(session),
+        summary: session.summary.clone(),
+        action_items: parse_action_items(session),
+        decisions: session.artifacts.as_ref().map(|a| a.decisions.clone()).unwrap_or_default(),
+        questions: session.artifacts.as_ref().map(|a| a.questions.clone()).unwrap_or_default(),
+        folder: folder.map(|f| ExportFolder { id: f.id.clone(), name: f.name.clone() }),
+        summary_engine: session.summary_engine.clone(),
+        summary_model: session.summary_model.clone(),
+        transcription_model: session.transcription_model.clone(),
+        is_favorite: session.is_favorite,
+        notes: session.notes.clone(),
+        created_at: session.created_at.clone(),
+        updated_at: session.updated_at.clone(),
+    }
+}
+
+/// Layouts for `shareable_summary`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareFormat {
+    #[serde(alias = "plain")]
+    Text,
+    Markdown,
+}
+
+/// The summary minus its "Action Items" section; `shareable_summary` lists those itself.
+fn summary_without_action_items(summary: &str) -> String {
+    let mut kept = Vec::new();
+    let mut in_section = false;
+    for line in summary.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            in_section = trimmed.trim_start_matches('#').trim().to_lowercase().starts_with("action item");
+        }
+        if !in_section {
+            kept.push(line);
+        }
+    }
+    kept.join("\n").trim().to_string()
+}
+
+/// Markdown summary as plain text: headings lose their `#`, bullets become `•`, and
+/// bold/italic markers are dropped.
+fn markdown_to_text(markdown: &str) -> String {
+    markdown
+        .lines()
+        .map(|line| {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let trimmed = line.trim_start();
+            let body = if trimmed.starts_with('#') {
+                trimmed.trim_start_matches('#').trim_start().to_string()
+            } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+                format!("{}• {}", indent, item)
+            } else {
+                line.to_string()
+            };
+            body.replace("**", "").replace("__", "")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn action_item_line(item: &ActionItem) -> String {
+    let details: Vec<String> = item
+        .owner
+        .iter()
+        .cloned()
+        .chain(item.due.iter().map(|due| format!("due {}", due)))
+        .collect();
+    if details.is_empty() {
+        item.text.clone()
+    } else {
+        format!("{} ({})", item.text, details.join(", "))
+    }
+}
+
+/// Title, date, summary and action items laid out for pasting into chat or email.
+/// None when the session has no summary yet.
+pub fn shareable_summary(session: &SessionRecord, format: ShareFormat) -> Option<String> {
+    let summary = session.summary.as_deref().filter(|s| !s.trim().is_empty())?;
+    let body = summary_without_action_items(summary);
+    let items = parse_action_items(session);
+    let meta = format!("{} · {}", session.date, format_duration(session.duration));
+
+    let mut out = match format {
+        ShareFormat::Markdown => format!("# {}\n_{}_\n", session.title, meta),
+        ShareFormat::Text => format!("{}\n{}\n", session.title, meta),
+    };
+    if !body.is_empty() {
+        match format {
+            ShareFormat::Markdown => out.push_str(&format!("\n{}\n", body)),
+            ShareFormat::Text => out.push_str(&format!("\n{}\n", markdown_to_text(&body))),
+        }
+    }
+    if !items.is_empty() {
+        out.push_str(match format {
+            ShareFormat::Markdown => "\n## Action Items\n",
+            ShareFormat::Text => "\nAction items\n",
+        });
+        for item in &items {
+            let bullet = match format {
+                ShareFormat::Markdown => "- [ ]",
+                ShareFormat::Text => "•",
+            };
+            out.push_str(&format!("{} {}\n", bullet, action_item_line(item)));
+        }
+    }
+    Some(out.trim_end().to_string())
+}
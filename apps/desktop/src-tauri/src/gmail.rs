@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use base64::Engine;
+use reqwest::Client;
+
+const DRAFTS_URL: &str = "https://gmail.googleapis.com/gmail/v1/users/me/drafts";
+
+/// OAuth access token with the `gmail.compose` scope, read from `.env` like the LLM keys.
+fn access_token() -> Result<String, String> {
+    match std::env::var("GMAIL_ACCESS_TOKEN") {
+        Ok(v) if !v.trim().is_empty() => Ok(v.trim().to_string()),
+        _ => Err("GMAIL_ACCESS_TOKEN not set".to_string()),
+    }
+}
+
+fn rfc822(to: &str, subject: &str, body: &str) -> String {
+    // Header values must stay on one line
+    let to = to.replace(['\r', '\n'], " ");
+    let subject = subject.replace(['\r', '\n'], " ");
+    format!(
+        "To: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=\"UTF-8\"\r\n\r\n{}",
+        to.trim(),
+        subject.trim(),
+        body.replace("\r\n", "\n").replace('\n', "\r\n")
+    )
+}
+
+/// Create (never send) a Gmail draft and return its draft id.
+pub async fn create_draft(to: &str, subject: &str, body: &str) -> Result<String, String> {
+    if to.trim().is_empty() {
+        return Err("Recipient is empty".to_string());
+    }
+    let token = access_token()?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let raw = base64::engine::general_purpose::URL_SAFE.encode(rfc822(to, subject, body));
+    let resp = client
+        .post(DRAFTS_URL)
+        .bearer_auth(&token)
+        .json(&serde_json::json!({ "message": { "raw": raw } }))
+        .send()
+        .await
+        .map_err(|e| format!("Gmail request failed: {}", e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Gmail returned {}: {}", status, body));
+    }
+    let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid Gmail response: {}", e))?;
+    json["id"].as_str().map(|id| id.to_string()).ok_or_else(|| "Gmail response missing draft id".to_string())
+}
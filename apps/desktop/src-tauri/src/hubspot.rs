@@ -0,0 +1,104 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::Client;
+
+use crate::database::SessionRecord;
+
+const API_BASE: &str = "https://api.hubapi.com";
+// HubSpot-defined association type for note -> contact
+const NOTE_TO_CONTACT: u32 = 202;
+
+/// Private-app token from `.env`, read the same way as the LLM keys.
+fn access_token() -> Result<String, String> {
+    match std::env::var("HUBSPOT_PRIVATE_APP_TOKEN") {
+        Ok(v) if !v.trim().is_empty() => Ok(v.trim().to_string()),
+        _ => Err("HUBSPOT_PRIVATE_APP_TOKEN not set".to_string()),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn note_body(session: &SessionRecord, summary: &str) -> String {
+    let mut html = format!("<p><strong>{}</strong> ({})</p>", escape_html(&session.title), escape_html(&session.date));
+    html.push_str(&format!("<p>{}</p>", escape_html(summary).replace('\n', "<br>")));
+    let items = crate::export::summary_action_items(summary);
+    if !items.is_empty() {
+        html.push_str("<p><strong>Action items</strong></p><ul>");
+        for item in items {
+            html.push_str(&format!("<li>{}</li>", escape_html(&item)));
+        }
+        html.push_str("</ul>");
+    }
+    html
+}
+
+async fn error_body(resp: reqwest::Response) -> String {
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    format!("HubSpot returned {}: {}", status, body)
+}
+
+async fn find_contact_id(client: &Client, token: &str, email: &str) -> Result<String, String> {
+    let body = serde_json::json!({
+        "filterGroups": [{ "filters": [{ "propertyName": "email", "operator": "EQ", "value": email }] }],
+        "properties": ["email"],
+        "limit": 1,
+    });
+    let resp = client
+        .post(format!("{}/crm/v3/objects/contacts/search", API_BASE))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("HubSpot request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(error_body(resp).await);
+    }
+    let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid HubSpot response: {}", e))?;
+    json["results"][0]["id"]
+        .as_str()
+        .map(|id| id.to_string())
+        .ok_or_else(|| format!("No HubSpot contact found for {}", email))
+}
+
+/// Log the session summary as a note on the HubSpot contact with `contact_email`.
+/// Returns the new note id.
+pub async fn create_note(session: &SessionRecord, contact_email: &str) -> Result<String, String> {
+    let summary = session
+        .summary
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or("Session has no summary yet")?;
+    let token = access_token()?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let contact_id = find_contact_id(&client, &token, contact_email.trim()).await?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis().to_string();
+    let body = serde_json::json!({
+        "properties": {
+            "hs_timestamp": timestamp,
+            "hs_note_body": note_body(session, summary),
+        },
+        "associations": [{
+            "to": { "id": contact_id },
+            "types": [{ "associationCategory": "HUBSPOT_DEFINED", "associationTypeId": NOTE_TO_CONTACT }],
+        }],
+    });
+    let resp = client
+        .post(format!("{}/crm/v3/objects/notes", API_BASE))
+        .bearer_auth(&token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("HubSpot request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(error_body(resp).await);
+    }
+    let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid HubSpot response: {}", e))?;
+    json["id"].as_str().map(|id| id.to_string()).ok_or_else(|| "HubSpot response missing note id".to_string())
+}
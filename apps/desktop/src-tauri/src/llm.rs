@@ -0,0 +1,1013 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::database::Settings;
+use crate::transcribe::TranscriptSegment;
+use log::warn;
+
+// Only the first few hundred words are needed to name a meeting; keeps title requests cheap
+const TITLE_EXCERPT_WORDS: usize = 300;
+const TITLE_TIMEOUT: Duration = Duration::from_secs(20);
+// Long transcripts on local models can take minutes; individual requests get a generous ceiling
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+const TITLE_SYSTEM_PROMPT: &str = "You name meetings. Reply with a 3 to 6 word title only: no quotes, no trailing punctuation, no explanation.";
+
+pub const DEFAULT_SUMMARY_PROMPT: &str = "You are a helpful assistant that produces a concise, well-structured Markdown summary of a meeting transcript. Use headings you infer from the content (e.g. Overview, Key Points, Decisions, Action Items, Risks). Do not include code fences.";
+
+// Used with `summary_prompt_template`, where the instructions travel in the user message
+const TEMPLATE_SYSTEM_PROMPT: &str = "You summarize meetings, following the user's instructions for format and content. Reply in Markdown without code fences.";
+
+/// Built-in values for `summary_prompt_template`, selectable by name.
+pub const SUMMARY_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "bullets",
+        "Summarize the meeting \"{{title}}\" ({{duration}}) as a flat list of short bullet points covering what was discussed, decided and assigned.\n\nTranscript:\n{{transcript}}",
+    ),
+    (
+        "executive",
+        "Write an executive summary of the meeting \"{{title}}\" ({{duration}}) for someone who wasn't there: one short paragraph on the outcome, then Decisions, Risks and Next Steps sections.\n\nTranscript:\n{{transcript}}",
+    ),
+    (
+        "bant",
+        "This is a sales call: \"{{title}}\" ({{duration}}). Summarize it using BANT with a section each for Budget, Authority, Need and Timeline, noting \"Not discussed\" where the call didn't cover one. Finish with Next Steps.\n\nTranscript:\n{{transcript}}",
+    ),
+];
+
+const DEFAULT_ANTHROPIC_MODEL: &str = "claude-3-5-sonnet-20241022";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
+
+/// Callback receiving streamed text chunks.
+pub type TokenSink = dyn Fn(&str) + Send + Sync;
+
+/// Tokens used by one request, as reported by the API or estimated when it isn't.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub estimated: bool,
+}
+
+impl TokenUsage {
+    /// Rough count for responses without usage figures: about 4 characters per token.
+    pub fn estimate(input: &str, output: &str) -> Self {
+        let tokens = |s: &str| (s.chars().count() as u64).div_ceil(4);
+        Self { input_tokens: tokens(input), output_tokens: tokens(output), estimated: true }
+    }
+
+    /// What `engine` reported for its last request, or an estimate from the text sent and received.
+    pub fn of(engine: &dyn SummarizerEngine, input: &[&str], output: &str) -> Self {
+        engine.last_usage().unwrap_or_else(|| Self::estimate(&input.concat(), output))
+    }
+}
+
+/// Read usage figures at `input`/`output` (JSON pointers) from an API response.
+fn usage_at(json: &serde_json::Value, input: &str, output: &str) -> Option<TokenUsage> {
+    Some(TokenUsage {
+        input_tokens: json.pointer(input)?.as_u64()?,
+        output_tokens: json.pointer(output)?.as_u64()?,
+        estimated: false,
+    })
+}
+
+/// A backend capable of turning a transcript into text according to an instruction prompt.
+#[async_trait]
+pub trait SummarizerEngine: Send + Sync {
+    /// Engine identifier as stored in `Settings::summary_engine`.
+    fn name(&self) -> &'static str;
+    fn model(&self) -> &str;
+    /// Usage reported by the most recent request, if the API returned it.
+    fn last_usage(&self) -> Option<TokenUsage>;
+    async fn summarize(&self, transcript: &str, prompt: &str) -> Result<String, EngineError>;
+
+    /// Like `summarize`, but calls `on_token` with each chunk as it arrives and returns the
+    /// full text at the end. Engines without streaming support emit the whole result once.
+    async fn summarize_stream(&self, transcript: &str, prompt: &str, on_token: &TokenSink) -> Result<String, EngineError> {
+        let text = self.summarize(transcript, prompt).await?;
+        on_token(&text);
+        Ok(text)
+    }
+}
+
+pub struct OllamaEngine {
+    client: Client,
+    host: String,
+    model: String,
+    usage: Mutex<Option<TokenUsage>>,
+}
+
+pub struct AnthropicEngine {
+    client: Client,
+    /// `anthropic_base_url`, e.g. "https://api.anthropic.com/v1"
+    base_url: String,
+    api_key: String,
+    model: String,
+    usage: Mutex<Option<TokenUsage>>,
+}
+
+pub struct OpenAiEngine {
+    client: Client,
+    /// `openai_base_url`, e.g. "https://api.openai.com/v1" or a gateway
+    base_url: String,
+    api_key: String,
+    model: String,
+    usage: Mutex<Option<TokenUsage>>,
+}
+
+fn http_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Read an API key from the environment (.env is loaded at startup), same as `get_env_var`.
+fn api_key(name: &str) -> Result<String, String> {
+    match std::env::var(name) {
+        Ok(v) if !v.trim().is_empty() => Ok(v.trim().to_string()),
+        _ => Err(format!("{} not set", name)),
+    }
+}
+
+/// Feed a streaming response to `on_line` one complete line at a time.
+/// Bytes are buffered until a newline so multi-byte characters split across chunks survive.
+async fn for_each_line(mut resp: reqwest::Response, engine: &str, mut on_line: impl FnMut(&str) -> bool + Send) -> Result<(), String> {
+    let mut pending: Vec<u8> = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| format!("{} stream failed: {}", engine, e))? {
+        pending.extend_from_slice(&chunk);
+        while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if !line.is_empty() && !on_line(line) {
+                return Ok(());
+            }
+        }
+    }
+    let rest = String::from_utf8_lossy(&pending);
+    if !rest.trim().is_empty() {
+        on_line(rest.trim());
+    }
+    Ok(())
+}
+
+/// Why a `SummarizerEngine` request failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    /// No response: the server is down, the network failed or the request timed out.
+    /// Trying another engine could help.
+    Unreachable(String),
+    /// The API refused the request (bad key, rejected input) or its answer couldn't be
+    /// read; a retry elsewhere won't fix that
+    Failed(String),
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineError::Unreachable(m) | EngineError::Failed(m) => f.write_str(m),
+        }
+    }
+}
+
+impl From<String> for EngineError {
+    fn from(message: String) -> Self {
+        EngineError::Failed(message)
+    }
+}
+
+// Callers outside the engines only need the message
+impl From<EngineError> for String {
+    fn from(error: EngineError) -> Self {
+        error.to_string()
+    }
+}
+
+/// A request that never got a response: connection failures and timeouts are
+/// `Unreachable`, anything else `Failed`.
+fn request_failed(engine: &str, e: reqwest::Error) -> EngineError {
+    if e.is_connect() || e.is_timeout() {
+        EngineError::Unreachable(format!("{} unreachable: {}", engine, e))
+    } else {
+        EngineError::Failed(format!("{} request failed: {}", engine, e))
+    }
+}
+
+async fn error_body(resp: reqwest::Response, engine: &str) -> String {
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    format!("{} error ({}): {}", engine, status, body)
+}
+
+#[async_trait]
+impl SummarizerEngine for OllamaEngine {
+    fn name(&self) -> &'static str { "ollama" }
+    fn model(&self) -> &str { &self.model }
+    fn last_usage(&self) -> Option<TokenUsage> { *self.usage.lock().unwrap() }
+
+    async fn summarize(&self, transcript: &str, prompt: &str) -> Result<String, EngineError> {
+        let url = format!("{}/api/chat", self.host.trim_end_matches('/'));
+        let resp = self.client
+            .post(&url)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "messages": [
+                    { "role": "system", "content": prompt },
+                    { "role": "user", "content": transcript }
+                ],
+                "options": { "temperature": 0.2 },
+                "stream": false
+            }))
+            .send()
+            .await
+            .map_err(|e| request_failed("Ollama", e))?;
+
+        if !resp.status().is_success() {
+            return Err(error_body(resp, "Ollama").await.into());
+        }
+
+        let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid Ollama response: {}", e))?;
+        *self.usage.lock().unwrap() = usage_at(&json, "/prompt_eval_count", "/eval_count");
+        Ok(json.pointer("/message/content").and_then(|v| v.as_str()).unwrap_or("").to_string())
+    }
+
+    async fn summarize_stream(&self, transcript: &str, prompt: &str, on_token: &TokenSink) -> Result<String, EngineError> {
+        let url = format!("{}/api/chat", self.host.trim_end_matches('/'));
+        let resp = self.client
+            .post(&url)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "messages": [
+                    { "role": "system", "content": prompt },
+                    { "role": "user", "content": transcript }
+                ],
+                "options": { "temperature": 0.2 },
+                "stream": true
+            }))
+            .send()
+            .await
+            .map_err(|e| request_failed("Ollama", e))?;
+
+        if !resp.status().is_success() {
+            return Err(error_body(resp, "Ollama").await.into());
+        }
+
+        // Ollama streams newline-delimited JSON objects, the last one has "done": true
+        let mut full = String::new();
+        let mut stream_error: Option<String> = None;
+        for_each_line(resp, "Ollama", |line| {
+            let json: serde_json::Value = match serde_json::from_str(line) {
+                Ok(v) => v,
+                Err(_) => return true,
+            };
+            if let Some(err) = json.get("error").and_then(|v| v.as_str()) {
+                stream_error = Some(format!("Ollama error: {}", err));
+                return false;
+            }
+            if let Some(token) = json.pointer("/message/content").and_then(|v| v.as_str()) {
+                if !token.is_empty() {
+                    full.push_str(token);
+                    on_token(token);
+                }
+            }
+            let done = json.get("done").and_then(|v| v.as_bool()).unwrap_or(false);
+            if done {
+                *self.usage.lock().unwrap() = usage_at(&json, "/prompt_eval_count", "/eval_count");
+            }
+            !done
+        }).await?;
+
+        match stream_error {
+            Some(e) => Err(e.into()),
+            None => Ok(full),
+        }
+    }
+}
+
+#[async_trait]
+impl SummarizerEngine for AnthropicEngine {
+    fn name(&self) -> &'static str { "anthropic" }
+    fn model(&self) -> &str { &self.model }
+    fn last_usage(&self) -> Option<TokenUsage> { *self.usage.lock().unwrap() }
+
+    async fn summarize(&self, transcript: &str, prompt: &str) -> Result<String, EngineError> {
+        let resp = self.client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&serde_json::json!({
+                "model": self.model,
+                "max_tokens": 4096,
+                "temperature": 0.2,
+                "system": prompt,
+                "messages": [
+                    { "role": "user", "content": transcript }
+                ]
+            }))
+            .send()
+            .await
+            .map_err(|e| request_failed("Anthropic", e))?;
+
+        if !resp.status().is_success() {
+            return Err(error_body(resp, "Anthropic").await.into());
+        }
+
+        let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid Anthropic response: {}", e))?;
+        *self.usage.lock().unwrap() = usage_at(&json, "/usage/input_tokens", "/usage/output_tokens");
+        // Content is a list of blocks; concatenate the text ones
+        let text = json
+            .get("content")
+            .and_then(|v| v.as_array())
+            .map(|blocks| blocks
+                .iter()
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join(""))
+            .unwrap_or_default();
+        Ok(text)
+    }
+}
+
+#[async_trait]
+impl SummarizerEngine for OpenAiEngine {
+    fn name(&self) -> &'static str { "openai" }
+    fn model(&self) -> &str { &self.model }
+    fn last_usage(&self) -> Option<TokenUsage> { *self.usage.lock().unwrap() }
+
+    async fn summarize(&self, transcript: &str, prompt: &str) -> Result<String, EngineError> {
+        let resp = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "temperature": 0.2,
+                "messages": [
+                    { "role": "system", "content": prompt },
+                    { "role": "user", "content": transcript }
+                ]
+            }))
+            .send()
+            .await
+            .map_err(|e| request_failed("OpenAI", e))?;
+
+        if !resp.status().is_success() {
+            return Err(error_body(resp, "OpenAI").await.into());
+        }
+
+        let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid OpenAI response: {}", e))?;
+        *self.usage.lock().unwrap() = usage_at(&json, "/usage/prompt_tokens", "/usage/completion_tokens");
+        Ok(json.pointer("/choices/0/message/content").and_then(|v| v.as_str()).unwrap_or("").to_string())
+    }
+
+    async fn summarize_stream(&self, transcript: &str, prompt: &str, on_token: &TokenSink) -> Result<String, EngineError> {
+        let resp = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "temperature": 0.2,
+                "stream": true,
+                // Adds a final chunk with token counts
+                "stream_options": { "include_usage": true },
+                "messages": [
+                    { "role": "system", "content": prompt },
+                    { "role": "user", "content": transcript }
+                ]
+            }))
+            .send()
+            .await
+            .map_err(|e| request_failed("OpenAI", e))?;
+
+        if !resp.status().is_success() {
+            return Err(error_body(resp, "OpenAI").await.into());
+        }
+
+        // Server-sent events: "data: {json}" lines terminated by "data: [DONE]"
+        let mut full = String::new();
+        for_each_line(resp, "OpenAI", |line| {
+            let data = match line.strip_prefix("data:") {
+                Some(d) => d.trim(),
+                None => return true,
+            };
+            if data == "[DONE]" {
+                return false;
+            }
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+                if let Some(usage) = usage_at(&json, "/usage/prompt_tokens", "/usage/completion_tokens") {
+                    *self.usage.lock().unwrap() = Some(usage);
+                }
+                if let Some(token) = json.pointer("/choices/0/delta/content").and_then(|v| v.as_str()) {
+                    if !token.is_empty() {
+                        full.push_str(token);
+                        on_token(token);
+                    }
+                }
+            }
+            true
+        }).await?;
+
+        Ok(full)
+    }
+}
+
+fn anthropic_model(settings: &Settings) -> String {
+    match settings.model.as_str() {
+        // The settings default is a family name; the API needs a dated id
+        "claude-3-5-sonnet" => DEFAULT_ANTHROPIC_MODEL.to_string(),
+        m if m.starts_with("claude") => m.to_string(),
+        _ => DEFAULT_ANTHROPIC_MODEL.to_string(),
+    }
+}
+
+fn openai_model(settings: &Settings) -> String {
+    let m = settings.model.trim();
+    if m.is_empty() || m.starts_with("claude") {
+        DEFAULT_OPENAI_MODEL.to_string()
+    } else {
+        m.to_string()
+    }
+}
+
+/// Build the engine selected in settings. Returns `Ok(None)` when summaries are disabled ("none").
+pub fn engine_from_settings(settings: &Settings) -> Result<Option<Box<dyn SummarizerEngine>>, String> {
+    engine_named(&settings.summary_engine, settings)
+}
+
+/// The `summary_fallback_engine`, if one is set, differs from the primary and is usable
+/// (e.g. has its API key).
+pub fn fallback_engine_from_settings(settings: &Settings) -> Option<Box<dyn SummarizerEngine>> {
+    let name = settings.summary_fallback_engine.trim();
+    if name.is_empty() || name == settings.summary_engine {
+        return None;
+    }
+    match engine_named(name, settings) {
+        Ok(engine) => engine,
+        Err(e) => {
+            warn!("Summary fallback '{}' unavailable: {}", name, e);
+            None
+        }
+    }
+}
+
+fn engine_named(name: &str, settings: &Settings) -> Result<Option<Box<dyn SummarizerEngine>>, String> {
+    let engine: Box<dyn SummarizerEngine> = match name {
+        "none" => return Ok(None),
+        "ollama" => Box::new(OllamaEngine {
+            client: http_client()?,
+            host: settings.ollama_host.clone(),
+            model: settings.ollama_model.clone(),
+            usage: Mutex::new(None),
+        }),
+        "anthropic" => Box::new(AnthropicEngine {
+            client: http_client()?,
+            base_url: settings.anthropic_base_url.trim_end_matches('/').to_string(),
+            api_key: api_key("ANTHROPIC_API_KEY")?,
+            model: anthropic_model(settings),
+            usage: Mutex::new(None),
+        }),
+        "openai" => Box::new(OpenAiEngine {
+            client: http_client()?,
+            base_url: settings.openai_base_url.trim_end_matches('/').to_string(),
+            api_key: api_key("OPENAI_API_KEY")?,
+            model: openai_model(settings),
+            usage: Mutex::new(None),
+        }),
+        other => return Err(format!("Unknown summary engine '{}'", other)),
+    };
+    Ok(Some(engine))
+}
+
+/// Summarize with `primary`, switching to `fallback` only when the primary can't be
+/// reached. `on_fallback` gets the primary's error before the fallback is tried.
+/// Returns the text and the engine that produced it.
+pub async fn summarize_with_fallback<'a>(
+    primary: &'a dyn SummarizerEngine,
+    fallback: Option<&'a dyn SummarizerEngine>,
+    content: &str,
+    prompt: &str,
+    on_token: &TokenSink,
+    on_fallback: impl FnOnce(&str) + Send,
+) -> Result<(String, &'a dyn SummarizerEngine), String> {
+    match primary.summarize_stream(content, prompt, on_token).await {
+        Ok(text) => Ok((text, primary)),
+        Err(EngineError::Unreachable(e)) => match fallback {
+            Some(fallback) => {
+                warn!("{} failed ({}); falling back to {}", primary.name(), e, fallback.name());
+                on_fallback(&e);
+                let text = fallback.summarize_stream(content, prompt, on_token).await?;
+                Ok((text, fallback))
+            }
+            None => Err(e),
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Strip code fences some models wrap Markdown in.
+pub fn clean_markdown(raw: &str) -> String {
+    raw.lines()
+        .filter(|l| !l.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct EngineTestResult {
+    pub engine: String,
+    pub model: String,
+    pub latency_ms: u64,
+    pub reply: String,
+}
+
+/// Tiny round-trip to confirm the configured engine is reachable and credentials work.
+pub async fn test_engine(settings: &Settings) -> Result<EngineTestResult, String> {
+    let engine = engine_from_settings(settings)?
+        .ok_or_else(|| "Summary engine is set to 'none'".to_string())?;
+    let started = Instant::now();
+    let reply = engine
+        .summarize("Alice: Let's ship on Friday.\nBob: Agreed.", "Reply with the single word OK.")
+        .await?;
+    Ok(EngineTestResult {
+        engine: engine.name().to_string(),
+        model: engine.model().to_string(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        reply: reply.trim().to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct OllamaConnectionResult {
+    pub reachable: bool,
+    pub model_available: bool,
+    pub available_models: Vec<String>,
+    pub error: Option<String>,
+}
+
+// Short so a wrong host fails fast in the settings screen
+const OLLAMA_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Check that `ollama_host` answers and has `ollama_model` pulled.
+/// A bare model name matches its `:latest` tag, as in `ollama run`.
+pub async fn test_ollama_connection(settings: &Settings) -> OllamaConnectionResult {
+    let unreachable = |error: String| OllamaConnectionResult {
+        reachable: false,
+        model_available: false,
+        available_models: Vec::new(),
+        error: Some(error),
+    };
+    let client = match Client::builder().timeout(OLLAMA_PROBE_TIMEOUT).build() {
+        Ok(c) => c,
+        Err(e) => return unreachable(format!("Failed to build HTTP client: {}", e)),
+    };
+    let url = format!("{}/api/tags", settings.ollama_host.trim().trim_end_matches('/'));
+    let resp = match client.get(&url).send().await {
+        Ok(r) if r.status().is_success() => r,
+        Ok(r) => return unreachable(error_body(r, "Ollama").await),
+        Err(e) => return unreachable(format!("Could not reach Ollama at {}: {}", settings.ollama_host, e)),
+    };
+    let json: serde_json::Value = match resp.json().await {
+        Ok(j) => j,
+        Err(e) => return unreachable(format!("Invalid Ollama response: {}", e)),
+    };
+
+    let available_models: Vec<String> = json["models"]
+        .as_array()
+        .map(|models| models.iter().filter_map(|m| m["name"].as_str().map(|n| n.to_string())).collect())
+        .unwrap_or_default();
+    let wanted = settings.ollama_model.trim();
+    let model_available = available_models
+        .iter()
+        .any(|name| name == wanted || (!wanted.contains(':') && name == &format!("{}:latest", wanted)));
+    let error = if model_available {
+        None
+    } else {
+        Some(format!("Model '{}' is not pulled; run `ollama pull {}`", wanted, wanted))
+    };
+    OllamaConnectionResult { reachable: true, model_available, available_models, error }
+}
+
+fn template_text(template: &str) -> &str {
+    let name = template.trim();
+    SUMMARY_TEMPLATES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, text)| *text)
+        .unwrap_or(template)
+}
+
+/// Reject a `summary_prompt_template` that would summarize nothing: custom text has to
+/// include `{{transcript}}`. Empty means the default prompt.
+pub fn validate_summary_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() || template_text(template).contains("{{transcript}}") {
+        Ok(())
+    } else {
+        Err("Summary template must include {{transcript}}".to_string())
+    }
+}
+
+/// Clean up a user-entered base URL such as `ollama_host`: add `http://` when no scheme
+/// is given and drop trailing slashes, so paths can be appended with `format!`. `field`
+/// names the setting in the error for anything that still isn't an http(s) URL.
+pub fn normalize_base_url(field: &str, value: &str) -> Result<String, String> {
+    let trimmed = value.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return Err(format!("{} can't be empty", field));
+    }
+    let normalized = if value.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("http://{}", trimmed)
+    };
+    let url = reqwest::Url::parse(&normalized).map_err(|e| format!("{} '{}' is not a valid URL: {}", field, value.trim(), e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("{} must be an http:// or https:// URL", field));
+    }
+    if url.host_str().map_or(true, str::is_empty) {
+        return Err(format!("{} '{}' has no host", field, value.trim()));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(format!("{} shouldn't include a query string or fragment", field));
+    }
+    Ok(normalized)
+}
+
+fn format_duration(seconds: i32) -> String {
+    let seconds = seconds.max(0);
+    if seconds >= 3600 {
+        format!("{}h {}m", seconds / 3600, seconds % 3600 / 60)
+    } else {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    }
+}
+
+/// The (system prompt, content) pair to summarize a session with: the default prompt
+/// over the raw transcript, or `summary_prompt_template` with `{{title}}`, `{{duration}}`
+/// and `{{transcript}}` filled in.
+pub fn summary_request(settings: &Settings, title: &str, duration_seconds: i32, transcript: &str) -> Result<(String, String), String> {
+    let template = settings.summary_prompt_template.as_str();
+    if template.trim().is_empty() {
+        return Ok((DEFAULT_SUMMARY_PROMPT.to_string(), transcript.to_string()));
+    }
+    validate_summary_template(template)?;
+    // Transcript last, so placeholder-looking text in what was said is left alone
+    let content = template_text(template)
+        .replace("{{title}}", title)
+        .replace("{{duration}}", &format_duration(duration_seconds))
+        .replace("{{transcript}}", transcript);
+    Ok((TEMPLATE_SYSTEM_PROMPT.to_string(), content))
+}
+
+/// A topical section of a meeting, for the clickable outline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Chapter {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub title: String,
+}
+
+const CHAPTERS_SYSTEM_PROMPT: &str = "You split meeting transcripts into topical chapters. Each transcript line starts with its time in seconds. Reply with only a JSON array, in order, like [{\"start_seconds\": 0, \"title\": \"Introductions\"}]. Use 3 to 12 chapters with titles of at most 6 words. No prose, no code fences.";
+// Consecutive segments are merged into lines of about this length to keep the prompt small
+const CHAPTER_LINE_MS: u64 = 30_000;
+
+#[derive(Deserialize)]
+struct RawChapter {
+    start_seconds: f64,
+    title: String,
+}
+
+/// Transcript lines prefixed with their start time in seconds, roughly `CHAPTER_LINE_MS` each.
+fn timestamped_lines(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::new();
+    let mut line_start: Option<u64> = None;
+    let mut line = String::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let start = *line_start.get_or_insert(segment.start_ms);
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(text);
+        if segment.end_ms.saturating_sub(start) >= CHAPTER_LINE_MS {
+            out.push_str(&format!("[{}] {}\n", start / 1000, line));
+            line.clear();
+            line_start = None;
+        }
+    }
+    if let Some(start) = line_start {
+        out.push_str(&format!("[{}] {}\n", start / 1000, line));
+    }
+    out
+}
+
+/// Turn whatever the model returned into sorted, non-overlapping chapters that cover
+/// `0..duration_ms`: starts are clamped to the session, duplicates dropped, and each
+/// chapter ends where the next begins.
+fn normalize_chapters(raw: Vec<RawChapter>, duration_ms: u64) -> Vec<Chapter> {
+    let mut starts: Vec<(u64, String)> = raw
+        .into_iter()
+        .filter(|c| c.start_seconds.is_finite())
+        .map(|c| (((c.start_seconds.max(0.0) * 1000.0) as u64).min(duration_ms), c.title.trim().to_string()))
+        .filter(|(_, title)| !title.is_empty())
+        .collect();
+    starts.sort_by_key(|(start, _)| *start);
+    starts.dedup_by_key(|(start, _)| *start);
+    // The outline should start at the beginning of the meeting
+    if let Some(first) = starts.first_mut() {
+        first.0 = 0;
+    }
+    let ends: Vec<u64> = starts.iter().skip(1).map(|(start, _)| *start).chain(std::iter::once(duration_ms)).collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .filter(|((start, _), end)| end > start)
+        .map(|((start_ms, title), end_ms)| Chapter { start_ms, end_ms, title })
+        .collect()
+}
+
+/// Ask `engine` to group timestamped segments into chapters, returning them with the
+/// tokens the request used.
+pub async fn generate_chapters(engine: &dyn SummarizerEngine, segments: &[TranscriptSegment], duration_ms: u64) -> Result<(Vec<Chapter>, TokenUsage), String> {
+    let lines = timestamped_lines(segments);
+    if lines.is_empty() {
+        return Err("Session has no timestamped segments".to_string());
+    }
+    let raw = engine.summarize(&lines, CHAPTERS_SYSTEM_PROMPT).await?;
+    let usage = TokenUsage::of(engine, &[CHAPTERS_SYSTEM_PROMPT, &lines], &raw);
+    // Models sometimes add prose or fences around the array
+    let json = match (raw.find('['), raw.rfind(']')) {
+        (Some(start), Some(end)) if end > start => &raw[start..=end],
+        _ => return Err("Model didn't return a chapter list".to_string()),
+    };
+    let parsed: Vec<RawChapter> = serde_json::from_str(json).map_err(|e| format!("Invalid chapter list from model: {}", e))?;
+    // Fall back to the last segment's end when the session has no recorded duration
+    let duration_ms = if duration_ms > 0 {
+        duration_ms
+    } else {
+        segments.iter().map(|s| s.end_ms).max().unwrap_or(0)
+    };
+    let chapters = normalize_chapters(parsed, duration_ms);
+    if chapters.is_empty() {
+        return Err("Model returned no usable chapters".to_string());
+    }
+    Ok((chapters, usage))
+}
+
+const PARTICIPANTS_SYSTEM_PROMPT: &str = "You list the people taking part in a meeting. From the transcript, find the names of people who speak or are addressed as attendees; ignore people who are only mentioned. Reply with only a JSON array of names, like [\"Jane Doe\", \"Sam\"]. Use [] if nobody is named. No prose, no code fences.";
+// Introductions happen early; the rest of a long meeting rarely adds names
+const PARTICIPANTS_TRANSCRIPT_WORDS: usize = 4000;
+
+/// Ask `engine` for the names of the meeting's participants, returning them with the
+/// tokens the request used.
+pub async fn extract_participants(engine: &dyn SummarizerEngine, transcript: &str) -> Result<(Vec<String>, TokenUsage), String> {
+    let excerpt = transcript.split_whitespace().take(PARTICIPANTS_TRANSCRIPT_WORDS).collect::<Vec<_>>().join(" ");
+    if excerpt.is_empty() {
+        return Err("Session has no transcript".to_string());
+    }
+    let raw = engine.summarize(&excerpt, PARTICIPANTS_SYSTEM_PROMPT).await?;
+    let usage = TokenUsage::of(engine, &[PARTICIPANTS_SYSTEM_PROMPT, &excerpt], &raw);
+    let json = match (raw.find('['), raw.rfind(']')) {
+        (Some(start), Some(end)) if end > start => &raw[start..=end],
+        _ => return Err("Model didn't return a list of names".to_string()),
+    };
+    let parsed: Vec<String> = serde_json::from_str(json).map_err(|e| format!("Invalid participant list from model: {}", e))?;
+    let mut names: Vec<String> = Vec::new();
+    for name in parsed {
+        let name = name.trim().to_string();
+        if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+            names.push(name);
+        }
+    }
+    Ok((names, usage))
+}
+
+/// Titles the frontend assigns when the user hasn't named the session.
+pub fn is_default_title(title: &str) -> bool {
+    let t = title.trim();
+    t.is_empty() || t.eq_ignore_ascii_case("untitled") || t.starts_with("Recording ")
+}
+
+/// Fallback title when no LLM is reachable, e.g. "Meeting 2024-05-01 14:30".
+pub fn date_title(date: &str) -> String {
+    let trimmed: String = date.trim().chars().take(16).collect();
+    if trimmed.is_empty() {
+        "Meeting".to_string()
+    } else {
+        format!("Meeting {}", trimmed)
+    }
+}
+
+fn title_excerpt(transcript: &str) -> String {
+    transcript
+        .split_whitespace()
+        .take(TITLE_EXCERPT_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn clean_title(raw: &str) -> Option<String> {
+    // Models sometimes wrap the answer in quotes or prefix it with "Title:"
+    let first_line = raw.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let without_label = first_line
+        .strip_prefix("Title:")
+        .or_else(|| first_line.strip_prefix("title:"))
+        .unwrap_or(first_line);
+    let cleaned = without_label
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '*' || c == '#')
+        .trim_end_matches(|c| c == '.' || c == '!' || c == ':')
+        .trim()
+        .to_string();
+    if cleaned.is_empty() {
+        return None;
+    }
+    // Keep the title short even if the model ignored the instructions
+    Some(cleaned.split_whitespace().take(8).collect::<Vec<_>>().join(" "))
+}
+
+/// Ask the configured summary engine for a short meeting title.
+pub async fn generate_title(settings: &Settings, transcript: &str) -> Result<String, String> {
+    let excerpt = title_excerpt(transcript);
+    if excerpt.is_empty() {
+        return Err("Transcript is empty".to_string());
+    }
+    let engine = engine_from_settings(settings)?
+        .ok_or_else(|| "Summary engine is set to 'none'".to_string())?;
+    let raw = tokio::time::timeout(TITLE_TIMEOUT, engine.summarize(&excerpt, TITLE_SYSTEM_PROMPT))
+        .await
+        .map_err(|_| "Title request timed out".to_string())??;
+    clean_title(&raw).ok_or_else(|| "Model returned an empty title".to_string())
+}
+
+// Transcripts longer than this are narrowed to the most relevant chunks before asking
+const QA_CONTEXT_WORDS: usize = 3000;
+const QA_CHUNK_WORDS: usize = 250;
+const QA_CHUNK_OVERLAP: usize = 50;
+
+const QA_SYSTEM_PROMPT: &str = "You answer questions about a meeting using only the transcript excerpts provided. Quote or paraphrase what was said. If the excerpts don't contain the answer, say so plainly.";
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "of", "to", "in", "on", "at", "for", "with", "about",
+    "is", "are", "was", "were", "be", "been", "it", "this", "that", "what", "which", "who",
+    "when", "where", "why", "how", "did", "do", "does", "they", "we", "you", "i", "he", "she",
+    "say", "said", "there", "their", "our", "my", "me", "us", "them", "any", "anything",
+];
+
+fn keywords(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() > 1 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Pick the transcript sections most relevant to `question` by simple keyword overlap,
+/// returned in their original order. Short transcripts are returned whole.
+pub fn relevant_context(transcript: &str, question: &str, max_words: usize) -> String {
+    let words: Vec<&str> = transcript.split_whitespace().collect();
+    if words.len() <= max_words {
+        return words.join(" ");
+    }
+
+    let query: std::collections::HashSet<String> = keywords(question).into_iter().collect();
+    let step = QA_CHUNK_WORDS - QA_CHUNK_OVERLAP;
+    let mut chunks: Vec<(usize, usize, usize)> = Vec::new(); // (start, end, score)
+    let mut start = 0;
+    while start < words.len() {
+        let end = (start + QA_CHUNK_WORDS).min(words.len());
+        let score = keywords(&words[start..end].join(" "))
+            .iter()
+            .filter(|w| query.contains(*w))
+            .count();
+        chunks.push((start, end, score));
+        if end == words.len() { break; }
+        start += step;
+    }
+
+    // Highest scoring first; earlier chunks win ties so context reads chronologically
+    let mut ranked = chunks.clone();
+    ranked.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+    // Whole chunks only, as many as fit in `max_words` (always at least one)
+    let mut picked: Vec<(usize, usize, usize)> = Vec::new();
+    let mut total = 0;
+    for chunk in ranked {
+        let len = chunk.1 - chunk.0;
+        if !picked.is_empty() && total + len > max_words {
+            continue;
+        }
+        total += len;
+        picked.push(chunk);
+    }
+    picked.sort_by_key(|c| c.0);
+
+    picked
+        .iter()
+        .map(|(s, e, _)| words[*s..*e].join(" "))
+        .collect::<Vec<_>>()
+        .join("\n...\n")
+}
+
+/// Answer a free-form question about a transcript with the given engine.
+pub async fn ask_transcript(engine: &dyn SummarizerEngine, transcript: &str, question: &str) -> Result<String, String> {
+    let context = relevant_context(transcript, question, QA_CONTEXT_WORDS);
+    let content = format!("Transcript excerpts:\n{}\n\nQuestion: {}", context, question.trim());
+    let answer = engine.summarize(&content, QA_SYSTEM_PROMPT).await?;
+    Ok(answer.trim().to_string())
+}
+
+const FOLLOWUP_SYSTEM_PROMPT: &str = "You write short, friendly follow-up emails after meetings. Using the meeting notes provided, thank the recipient, recap the key points and decisions in a few bullets, and list any action items with owners. Reply with the plain-text email body only: no subject line, no Markdown headings, no placeholders for information you don't have.";
+
+/// Draft a follow-up email body from the session summary, falling back to the transcript.
+pub async fn followup_email(engine: &dyn SummarizerEngine, title: &str, summary: Option<&str>, transcript: &str) -> Result<String, String> {
+    let notes = match summary.filter(|s| !s.trim().is_empty()) {
+        Some(summary) => format!("Meeting: {}\n\nSummary:\n{}", title, summary),
+        None => format!("Meeting: {}\n\nTranscript:\n{}", title, transcript),
+    };
+    let body = engine.summarize(&notes, FOLLOWUP_SYSTEM_PROMPT).await?;
+    Ok(clean_markdown(&body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(chapters: &[(f64, &str)]) -> Vec<RawChapter> {
+        chapters.iter().map(|(start_seconds, title)| RawChapter { start_seconds: *start_seconds, title: title.to_string() }).collect()
+    }
+
+    fn chapter(start_ms: u64, end_ms: u64, title: &str) -> Chapter {
+        Chapter { start_ms, end_ms, title: title.to_string() }
+    }
+
+    #[test]
+    fn chapters_are_sorted_and_cover_the_session() {
+        let chapters = normalize_chapters(raw(&[(600.0, "Budget"), (12.0, "Intro"), (300.0, "Roadmap")]), 900_000);
+        assert_eq!(
+            chapters,
+            vec![chapter(0, 300_000, "Intro"), chapter(300_000, 600_000, "Roadmap"), chapter(600_000, 900_000, "Budget")]
+        );
+    }
+
+    #[test]
+    fn overlapping_chapters_keep_the_first_at_each_start() {
+        let chapters = normalize_chapters(raw(&[(0.0, "Intro"), (120.0, "Hiring"), (120.0, "Hiring plan"), (120.4, "Offers")]), 300_000);
+        assert_eq!(
+            chapters,
+            vec![chapter(0, 120_000, "Intro"), chapter(120_000, 120_400, "Hiring"), chapter(120_400, 300_000, "Offers")]
+        );
+    }
+
+    #[test]
+    fn chapters_past_the_end_collapse_into_the_last() {
+        let chapters = normalize_chapters(raw(&[(0.0, "Intro"), (250.0, "Wrap up"), (400.0, "After the call")]), 300_000);
+        assert_eq!(chapters, vec![chapter(0, 250_000, "Intro"), chapter(250_000, 300_000, "Wrap up")]);
+    }
+
+    #[test]
+    fn empty_and_unusable_chapters_are_dropped() {
+        assert!(normalize_chapters(Vec::new(), 60_000).is_empty());
+        assert!(normalize_chapters(raw(&[(0.0, "Intro")]), 0).is_empty());
+
+        let chapters = normalize_chapters(
+            raw(&[(f64::NAN, "Lost"), (-5.0, " Intro "), (30.0, "   "), (f64::INFINITY, "Forever"), (45.0, "Demo")]),
+            60_000,
+        );
+        assert_eq!(chapters, vec![chapter(0, 45_000, "Intro"), chapter(45_000, 60_000, "Demo")]);
+    }
+
+    #[test]
+    fn first_chapter_starts_at_zero() {
+        let chapters = normalize_chapters(raw(&[(90.0, "Status"), (200.0, "Risks")]), 240_000);
+        assert_eq!(chapters, vec![chapter(0, 200_000, "Status"), chapter(200_000, 240_000, "Risks")]);
+    }
+
+    #[test]
+    fn base_urls_are_trimmed_and_given_a_scheme() {
+        for (input, expected) in [
+            ("http://localhost:11434", "http://localhost:11434"),
+            ("  http://localhost:11434/  ", "http://localhost:11434"),
+            ("127.0.0.1:11434", "http://127.0.0.1:11434"),
+            ("ollama.internal:11434///", "http://ollama.internal:11434"),
+            ("https://api.openai.com/v1/", "https://api.openai.com/v1"),
+            // The path is kept as typed: no `/v1` is added for gateways that don't use one
+            ("https://gateway.example.com", "https://gateway.example.com"),
+            ("https://gateway.example.com/openai/", "https://gateway.example.com/openai"),
+        ] {
+            assert_eq!(normalize_base_url("ollama_host", input).as_deref(), Ok(expected), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn bad_base_urls_are_rejected() {
+        for input in ["", "   ", "///", "ftp://files.example.com", "http://", "http://localhost:11434/?x=1", "http://localhost#top", "http://local host"] {
+            let err = normalize_base_url("ollama_host", input).unwrap_err();
+            assert!(err.starts_with("ollama_host"), "input: {:?}, error: {}", input, err);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! App logging. Everything the app logs goes to stderr and to `logs/oatmeal.log` in the
+//! app data dir, so packaged builds have a file users can attach to bug reports. Both
+//! sinks accept every level; the global max level (the `log_level` setting) does the
+//! filtering, so it can change without restarting.
+
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use log::LevelFilter;
+use once_cell::sync::OnceCell;
+use simplelog::{ColorChoice, CombinedLogger, ConfigBuilder, TermLogger, TerminalMode, WriteLogger};
+
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+const LOG_FILE: &str = "oatmeal.log";
+// Past this the log is moved to `oatmeal.log.1` at startup, replacing the previous one
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+static LOG_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// Start logging to stderr and `dir/oatmeal.log`. Only the first call has any effect.
+pub fn init(dir: &Path) -> Result<(), String> {
+    if LOG_PATH.get().is_some() {
+        return Ok(());
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create log dir: {}", e))?;
+    let path = dir.join(LOG_FILE);
+    if fs::metadata(&path).map(|m| m.len() > MAX_LOG_BYTES).unwrap_or(false) {
+        let _ = fs::rename(&path, dir.join(format!("{}.1", LOG_FILE)));
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    // Dependencies (sqlx statements, HTTP clients, the webview) would drown out the app
+    let config = ConfigBuilder::new()
+        .add_filter_allow_str("oatmeal_desktop")
+        .set_time_format_rfc3339()
+        .build();
+    CombinedLogger::init(vec![
+        TermLogger::new(LevelFilter::Trace, config.clone(), TerminalMode::Stderr, ColorChoice::Auto),
+        WriteLogger::new(LevelFilter::Trace, config, file),
+    ])
+    .map_err(|e| format!("Failed to start logging: {}", e))?;
+    log::set_max_level(DEFAULT_LEVEL);
+    let _ = LOG_PATH.set(path);
+    Ok(())
+}
+
+/// `off`, `error`, `warn`, `info`, `debug` or `trace`, case-insensitively.
+pub fn parse_level(level: &str) -> Option<LevelFilter> {
+    level.trim().parse().ok()
+}
+
+/// Apply a `log_level` setting; unknown values fall back to `info`.
+pub fn apply_level(level: &str) {
+    log::set_max_level(parse_level(level).unwrap_or(DEFAULT_LEVEL));
+}
+
+/// The log file, once `init` has succeeded.
+pub fn log_path() -> Option<&'static Path> {
+    LOG_PATH.get().map(PathBuf::as_path)
+}
@@ -6,83 +6,194 @@
 use tauri::Manager;
 use tauri::{GlobalShortcutManager, State};
 
+mod api;
 mod audio;
+mod backup;
+mod bundle;
 mod database;
+mod diarize;
+mod embeddings;
+mod encryption;
+mod error;
+mod export;
+mod gmail;
+mod hubspot;
+mod llm;
+mod logging;
+mod numbers;
+mod recording;
+mod redact;
+mod stats;
 mod transcribe;
+mod transcription_queue;
+mod webhook;
 mod sckit;
 
 use audio::{AudioRuntime, AudioSource};
-use database::{Database, Settings, SessionRecord};
-use transcribe::Transcriber;
+use database::{Database, SessionFilter, Settings, SessionRecord, SummaryConfig};
+use error::AppError;
+use recording::RecordingState;
+use transcribe::{Transcriber, TranscriptSegment};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use log::{debug, error, info, warn};
 
 #[cfg(target_os = "macos")]
 mod permissions;
+#[cfg(target_os = "macos")]
+mod reminders;
 
-struct AppState {
+pub(crate) struct AppState {
     audio_capture: AudioRuntime,
     database: Arc<Mutex<Option<Database>>>,
     transcriber: Arc<Mutex<Transcriber>>,
     recording_start_time: Arc<Mutex<Option<u64>>>, // Unix timestamp in milliseconds
+    summary_cancels: Arc<Mutex<HashMap<String, (u64, CancellationToken)>>>, // running summaries by session id
+    segments: Arc<Mutex<Vec<TranscriptSegment>>>, // live segments for the current recording
+    segment_origin: Arc<Mutex<SegmentOrigin>>, // how `segments` were transcribed
+    diarizer: Arc<Mutex<Option<diarize::Diarizer>>>, // Some while recording with diarization enabled
+    recording_audio_path: Arc<Mutex<Option<std::path::PathBuf>>>, // WAV being written for the current recording
+    recording_track2_path: Arc<Mutex<Option<std::path::PathBuf>>>, // track 2 WAV of a two-track recording
+    debug_audio: Arc<Mutex<Option<DebugAudioPaths>>>,
+    api_server: Arc<Mutex<Option<api::ApiHandle>>>,
+    transcription_queue: Arc<transcription_queue::TranscriptionQueue>,
+    live_transcription: Arc<AtomicBool>, // false while recording with transcription_engine = "none"
+    pending_notes: Arc<Mutex<Vec<String>>>, // quick notes taken during the current recording
+    recording_state: Arc<recording::RecordingStatus>,
+    draft_session_id: Arc<Mutex<Option<String>>>, // autosaved draft for the current recording
+    pending_input: Arc<Mutex<Option<audio::InputChoice>>>, // input to remember once this recording is saved
+    app_initialized: Arc<AtomicBool>, // initialize_app has run; a frontend reload calls it again
 }
 
-#[tauri::command]
-async fn initialize_app(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    // Initialize database
-    let db_path = app_handle.path_resolver()
+fn app_data_dir(app_handle: &tauri::AppHandle) -> std::path::PathBuf {
+    app_handle.path_resolver()
         .app_data_dir()
         .unwrap_or_else(|| std::env::current_dir().unwrap())
-        .join("oatmeal.db");
-    
-    let database = Database::new(db_path.to_str().unwrap())
-        .await
-        .map_err(|e| format!("Failed to initialize database: {}", e))?;
-    
-    *state.database.lock().await = Some(database);
+}
 
-    let mut shortcut_manager = app_handle.global_shortcut_manager();
-    
-    // Register global shortcuts
-    let app_handle_clone = app_handle.clone();
-    shortcut_manager
-        .register("CmdOrCtrl+Shift+R", move || {
-            let _ = app_handle_clone.emit_all("toggle-recording", ());
-        })
-        .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+#[tauri::command]
+async fn initialize_app(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    // Hot reloads and reconnects call this again; the first successful call did it all
+    if state.app_initialized.swap(true, Ordering::SeqCst) {
+        debug!("App already initialized");
+        return Ok(());
+    }
+    let result = initialize_app_once(&app_handle, &state).await;
+    if result.is_err() {
+        // Let the next call start over
+        state.app_initialized.store(false, Ordering::SeqCst);
+    }
+    result
+}
 
-    let app_handle_clone = app_handle.clone();
-    shortcut_manager
-        .register("CmdOrCtrl+Shift+N", move || {
-            let _ = app_handle_clone.emit_all("quick-note", ());
-        })
-        .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+/// Global shortcuts and the event each one emits.
+const SHORTCUTS: [(&str, &str); 2] = [
+    ("CmdOrCtrl+Shift+R", "toggle-recording"),
+    ("CmdOrCtrl+Shift+N", "quick-note"),
+];
+
+async fn initialize_app_once(app_handle: &tauri::AppHandle, state: &State<'_, AppState>) -> Result<(), AppError> {
+    // A command may already have opened the database on demand
+    ensure_database(app_handle, state).await?;
+    {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        // Permanently drop sessions that have been in the trash past the restore window
+        match database.purge_trashed_sessions(Some(database::TRASH_RETENTION_DAYS)).await {
+            Ok(purged) => remove_session_audio(&purged),
+            Err(e) => error!("Failed to purge trash: {}", e),
+        }
+    }
+
+    let settings = get_settings(app_handle.clone(), state.clone()).await?;
+    logging::apply_level(&settings.log_level);
+    if let Err(e) = sync_database_encryption(app_handle, state, &settings).await {
+        warn!("{}", e);
+    }
+
+    // Bring up the local API if the user turned it on
+    if let Err(e) = sync_api_server(app_handle, state, &settings).await {
+        warn!("{}", e);
+    }
+
+    let mut shortcut_manager = app_handle.global_shortcut_manager();
+    for (accelerator, event) in SHORTCUTS {
+        // Left over from an earlier attempt that failed part-way; registering twice errors
+        if shortcut_manager.is_registered(accelerator).unwrap_or(false) {
+            shortcut_manager
+                .unregister(accelerator)
+                .map_err(|e| format!("Failed to unregister shortcut {}: {}", accelerator, e))?;
+        }
+        let app_handle_clone = app_handle.clone();
+        shortcut_manager
+            .register(accelerator, move || {
+                let _ = app_handle_clone.emit_all(event, ());
+            })
+            .map_err(|e| format!("Failed to register shortcut {}: {}", accelerator, e))?;
+    }
 
     Ok(())
 }
 
+/// Allowed live-transcription chunk lengths, in seconds.
+const CHUNK_SECONDS_RANGE: std::ops::RangeInclusive<f32> = 0.5..=10.0;
+// `low_power_mode` chunks are at least this long: fewer, larger Whisper runs cost less overall
+const LOW_POWER_CHUNK_SECONDS: f32 = 5.0;
+
+/// Start recording. `chunk_seconds` overrides the stored setting for this session only;
+/// returns the chunk length the frontend should use.
 #[tauri::command]
-async fn start_recording(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+async fn start_recording(app_handle: tauri::AppHandle, state: State<'_, AppState>, chunk_seconds: Option<f32>) -> Result<f32, AppError> {
+    if let Some(seconds) = chunk_seconds {
+        if !CHUNK_SECONDS_RANGE.contains(&seconds) {
+            return Err(AppError::InvalidInput(format!(
+                "chunk_seconds must be between {} and {}, got {}",
+                CHUNK_SECONDS_RANGE.start(),
+                CHUNK_SECONDS_RANGE.end(),
+                seconds
+            )));
+        }
+    }
+    state
+        .recording_state
+        .transition(&app_handle, &[RecordingState::Idle], RecordingState::Starting)
+        .map_err(|_| AppError::InvalidInput(format!("Can't start: recording is {:?}", state.recording_state.get())))?;
+    let result = begin_recording(app_handle.clone(), &state, chunk_seconds).await;
+    if result.is_err() {
+        *state.recording_start_time.lock().await = None;
+        let _ = sckit::macos::stop_system_audio_capture().await;
+    } else if let Some(recording_start) = *state.recording_start_time.lock().await {
+        spawn_autosave(app_handle.clone(), recording_start);
+    }
+    let next = if result.is_ok() { RecordingState::Recording } else { RecordingState::Idle };
+    state.recording_state.set(&app_handle, next);
+    result
+}
+
+async fn begin_recording(app_handle: tauri::AppHandle, state: &State<'_, AppState>, chunk_seconds: Option<f32>) -> Result<f32, AppError> {
     #[cfg(target_os = "macos")]
     {
         // Check microphone permission before starting
-        let permission_status = permissions::check_microphone_permission()?;
+        let permission_status = permissions::check_microphone_permission().map_err(AppError::Permission)?;
         match permission_status.as_str() {
             "granted" => {
                 // Permission granted, proceed with recording
             },
             "denied" => {
-                return Err("Microphone permission denied. Please enable it in System Preferences > Security & Privacy > Microphone.".to_string());
+                return Err(AppError::Permission("Microphone permission denied. Please enable it in System Preferences > Security & Privacy > Microphone.".to_string()));
             },
             "undetermined" => {
                 // Request permission
-                let granted = permissions::request_microphone_permission().await?;
+                let granted = permissions::request_microphone_permission().await.map_err(AppError::Permission)?;
                 if !granted {
-                    return Err("Microphone permission is required to record audio.".to_string());
+                    return Err(AppError::Permission("Microphone permission is required to record audio.".to_string()));
                 }
             },
             _ => {
-                return Err("Unable to determine microphone permission status.".to_string());
+                return Err(AppError::Permission("Unable to determine microphone permission status.".to_string()));
             }
         }
     }
@@ -93,232 +204,2595 @@ async fn start_recording(app_handle: tauri::AppHandle, state: State<'_, AppState
         .unwrap()
         .as_millis() as u64;
     *state.recording_start_time.lock().await = Some(now);
+    state.pending_notes.lock().await.clear();
+    state.segments.lock().await.clear();
+    *state.segment_origin.lock().await = SegmentOrigin::default();
+    // A previous recording's unsaved draft stays in the database for recovery
+    *state.draft_session_id.lock().await = None;
 
     // Attempt to start macOS ScreenCaptureKit system-audio capture automatically.
     // If SCKit isn't available or not yet linked, fall back to our runtime mic capture.
-    let mut force_microphone = false;
+    let mut options = audio::CaptureOptions { on_capture_lost: Some(capture_lost_stop(&app_handle)), ..Default::default() };
+    let mut enable_diarization = false;
+    let mut save_audio = false;
+    let mut debug_save_audio = false;
+    let mut live_transcription = true;
+    let mut default_chunk_seconds = 2.5;
+    // (`low_power_mode`, whether the model has to be multilingual)
+    let mut low_power = (false, false);
+    let mut system_capture = sckit::SystemCaptureConfig::default();
     {
         // Ensure DB and read settings
-        ensure_database(&app_handle, &state).await?;
+        ensure_database(&app_handle, state).await?;
         let db_guard = state.database.lock().await;
         if let Some(database) = db_guard.as_ref() {
             if let Ok(s) = database.get_settings().await {
-                force_microphone = s.force_microphone;
+                default_chunk_seconds = s.chunk_seconds.clamp(*CHUNK_SECONDS_RANGE.start(), *CHUNK_SECONDS_RANGE.end());
+                if s.low_power_mode {
+                    default_chunk_seconds = default_chunk_seconds.max(LOW_POWER_CHUNK_SECONDS);
+                }
+                low_power = (s.low_power_mode, s.language != "en" || s.translate_to_english);
+                options.force_microphone = s.force_microphone;
+                options.prefer_builtin_mic = s.prefer_builtin_mic;
+                options.remembered_input = remembered_input(database).await;
+                system_capture = system_capture_config(&s);
+                options.frame_encoding = audio::FrameEncoding::from_setting(&s.audio_frame_encoding);
+                options.frame_ms = Some(s.frame_ms.max(0) as u32);
+                options.input_gain = Some(s.input_gain);
+                options.silence_watch = auto_stop_watch(&app_handle, s.auto_stop_silence_seconds);
+                options.processing = audio::AudioProcessingConfig {
+                    gate_threshold: s.audio_gate_threshold,
+                    attack_ms: s.audio_attack_ms,
+                    release_ms: s.audio_release_ms,
+                    makeup_gain: s.audio_makeup_gain,
+                    limiter_ceiling: s.audio_limiter_ceiling,
+                    mic_level: s.mic_mix_level,
+                    system_level: s.system_mix_level,
+                    auto_gain: s.mix_auto_gain,
+                };
+                options.separate_sources = s.capture_mode == "separate";
+                if s.capture_mode == "tracks" {
+                    options.tracks = Some(track_inputs(&s)?);
+                }
+                enable_diarization = s.enable_diarization;
+                // Record-only mode keeps the WAV so the session can be retranscribed later
+                live_transcription = s.transcription_engine != "none";
+                save_audio = s.save_audio || !live_transcription;
+                debug_save_audio = s.debug_save_audio;
             }
         }
     }
 
-    // Try SCKit for system audio capture; if it starts, do not start mic (avoid duplicate frames)
+    state.live_transcription.store(live_transcription, Ordering::SeqCst);
+
+    if live_transcription {
+        // Swapping models takes a while; do it now, off the async runtime, rather than in
+        // the worker while the first chunk waits
+        let transcriber = state.transcriber.clone();
+        let (enabled, multilingual) = low_power;
+        let applied = tokio::task::spawn_blocking(move || transcriber.blocking_lock().set_low_power(enabled, multilingual))
+            .await
+            .map_err(|e| format!("Switching low-power mode failed: {}", e))?;
+        if let Err(e) = applied {
+            warn!("Low-power mode not applied: {}", e);
+        }
+    }
+
+    *state.diarizer.lock().await = if enable_diarization {
+        Some(diarize::Diarizer::new(diarize::DEFAULT_MAX_SPEAKERS))
+    } else {
+        None
+    };
+
+    options.record_path = if save_audio {
+        Some(app_data_dir(&app_handle).join("recordings").join(format!("{}.wav", now)))
+    } else {
+        None
+    };
+    *state.recording_audio_path.lock().await = options.record_path.clone();
+    if let Some(tracks) = options.tracks.as_mut().filter(|_| save_audio) {
+        tracks.second_record_path = Some(app_data_dir(&app_handle).join("recordings").join(format!("{}-track2.wav", now)));
+    }
+    *state.recording_track2_path.lock().await = options.tracks.as_ref().and_then(|t| t.second_record_path.clone());
+    let debug_audio = if debug_save_audio {
+        let dir = app_data_dir(&app_handle).join("recordings");
+        Some(DebugAudioPaths {
+            raw: dir.join(format!("{}-raw.wav", now)),
+            whisper: dir.join(format!("{}-whisper.wav", now)),
+        })
+    } else {
+        None
+    };
+    options.debug_raw_path = debug_audio.as_ref().map(|d| d.raw.clone());
+    *state.debug_audio.lock().await = debug_audio;
+
+    // Try SCKit for system audio; when it starts, the runtime mixes it with the mic.
+    // Two-track recordings are mic-only.
     #[cfg(target_os = "macos")]
-    {
-        match sckit::macos::start_system_audio_capture(app_handle.clone()).await {
-            Ok(()) => {
-                println!("✅ ScreenCaptureKit system audio capture started");
-                return Ok(());
+    if options.tracks.is_none() {
+        let (tx, rx) = crossbeam_channel::bounded::<Vec<f32>>(64);
+        match sckit::macos::start_system_audio_capture(audio::SampleSink::new(tx, options.drops.clone(), "system"), system_capture).await {
+            Ok(sample_rate) => {
+                info!("✅ ScreenCaptureKit system audio capture started ({} Hz), mixing with microphone", sample_rate);
+                options.system_audio = Some(audio::SystemAudioInput { rx, sample_rate });
             }
             Err(e) => {
-                println!("⚠️ ScreenCaptureKit not available: {}. Using CPAL runtime capture only.", e);
+                warn!("⚠️ ScreenCaptureKit not available: {}. Using CPAL runtime capture only.", e);
+                options.system_audio_error = Some(e);
+            }
+        }
+    }
+
+    let remembered = options.remembered_input.clone();
+    let opened = state.audio_capture.start(app_handle, options).await.map_err(AppError::Audio)?;
+    // Only remembered once the recording is saved (see `remember_input`)
+    *state.pending_input.lock().await = opened.filter(|choice| Some(choice) != remembered.as_ref());
+    Ok(chunk_seconds.unwrap_or(default_chunk_seconds))
+}
+
+// Meta key holding the `audio::InputChoice` of the last recording that was saved
+const LAST_INPUT_KEY: &str = "last_input_device";
+
+/// Remember the input of the recording just saved, unless it disconnected partway.
+async fn remember_input(state: &AppState, database: &Database) {
+    let choice = match state.pending_input.lock().await.take() {
+        Some(choice) => choice,
+        None => return,
+    };
+    if state.audio_capture.input_lost() {
+        info!("Not remembering {}: it disconnected during the recording", choice.device_name);
+        return;
+    }
+    if let Ok(json) = serde_json::to_string(&choice) {
+        if let Err(e) = database.set_meta(LAST_INPUT_KEY, &json).await {
+            warn!("Failed to remember input device: {}", e);
+        }
+    }
+}
+
+async fn remembered_input(database: &Database) -> Option<audio::InputChoice> {
+    let json = database.get_meta(LAST_INPUT_KEY).await.ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// How the current recording's live segments were transcribed, noted per chunk so the
+/// saved session is labelled by what happened rather than by the settings at save time.
+#[derive(Default)]
+struct SegmentOrigin {
+    /// Models used, in order of first use; low power mode can swap them mid-recording
+    models: Vec<String>,
+    /// Some chunk was translated to English
+    translated: bool,
+}
+
+/// Where `debug_save_audio` writes a recording's two diagnostic WAVs.
+pub(crate) struct DebugAudioPaths {
+    /// The mic as captured, before gain, processing and mixing
+    raw: std::path::PathBuf,
+    /// Every live chunk as Whisper received it: 16 kHz mono, overlap included
+    whisper: std::path::PathBuf,
+}
+
+/// The two inputs of a "tracks" recording; both must be picked, and they must differ.
+fn track_inputs(settings: &Settings) -> Result<audio::TrackInputs, AppError> {
+    let first = settings.track1_device.trim();
+    let second = settings.track2_device.trim();
+    if first.is_empty() || second.is_empty() {
+        return Err(AppError::InvalidInput("Choose an input for both tracks in Settings".to_string()));
+    }
+    if first == second {
+        return Err(AppError::InvalidInput(format!("Track 1 and track 2 both use {}; choose two different inputs", first)));
+    }
+    Ok(audio::TrackInputs { first: first.to_string(), second: second.to_string(), second_record_path: None })
+}
+
+fn system_capture_config(settings: &Settings) -> sckit::SystemCaptureConfig {
+    sckit::SystemCaptureConfig {
+        app_bundle_id: Some(settings.system_audio_app.trim().to_string()).filter(|b| !b.is_empty()),
+        sample_rate: settings.system_audio_sample_rate.max(0) as u32,
+        channels: settings.system_audio_channels.clamp(1, 2) as u32,
+    }
+}
+
+/// Running apps whose audio can be recorded on its own (macOS); pick one with the
+/// `system_audio_app` setting.
+#[tauri::command]
+async fn list_capturable_apps() -> Result<Vec<sckit::CapturableApp>, AppError> {
+    sckit::macos::list_capturable_apps().map_err(AppError::Audio)
+}
+
+// Shorter limits would end recordings during ordinary lulls in a conversation
+const MIN_AUTO_STOP_SILENCE_SECONDS: f32 = 15.0;
+
+/// Watch for `seconds` of continuous silence, then emit `recording:auto-stop` and stop
+/// capture; the frontend saves the session as on a manual stop. 0 disables it.
+fn auto_stop_watch(app_handle: &tauri::AppHandle, seconds: f32) -> Option<Arc<audio::SilenceWatch>> {
+    if seconds.is_nan() || seconds <= 0.0 {
+        return None;
+    }
+    let seconds = seconds.max(MIN_AUTO_STOP_SILENCE_SECONDS);
+    let app_handle = app_handle.clone();
+    Some(audio::SilenceWatch::new(std::time::Duration::from_secs_f32(seconds), move || {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            info!("🔇 {}s of silence; stopping the recording", seconds);
+            let _ = app_handle.emit_all("recording:auto-stop", serde_json::json!({ "silence_seconds": seconds }));
+            if let Err(e) = stop_recording(app_handle.clone(), app_handle.state::<AppState>()).await {
+                error!("Auto-stop failed: {}", e);
+            }
+        });
+    }))
+}
+
+/// Stop the recording once its input is gone for good, so the recorder state doesn't
+/// stay "recording" when no window is around to stop it; the frontend saves as usual.
+fn capture_lost_stop(app_handle: &tauri::AppHandle) -> audio::CaptureLost {
+    let app_handle = app_handle.clone();
+    audio::CaptureLost::new(move || {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            info!("Input lost with nothing to replace it; stopping the recording");
+            if let Err(e) = stop_recording(app_handle.clone(), app_handle.state::<AppState>()).await {
+                error!("Stopping after a lost input failed: {}", e);
+            }
+        });
+    })
+}
+
+#[tauri::command]
+async fn stop_recording(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    let active = [RecordingState::Recording, RecordingState::Paused];
+    if state.recording_state.transition(&app_handle, &active, RecordingState::Stopping).is_err() {
+        // Nothing running (or already stopping); make sure capture is down anyway
+        return state.audio_capture.stop().map_err(AppError::Audio);
+    }
+    // Clear recording start time when stopping
+    *state.recording_start_time.lock().await = None;
+    let _ = sckit::macos::stop_system_audio_capture().await;
+    state.audio_capture.set_paused(false);
+    let result = state.audio_capture.stop().map_err(AppError::Audio);
+    state.recording_state.set(&app_handle, RecordingState::Idle);
+    result
+}
+
+/// Throw the current recording away: stop capture, drop its live transcript, notes and
+/// queued chunks, and delete its autosaved draft and audio files. Nothing is saved.
+#[tauri::command]
+async fn discard_recording(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    let active = [RecordingState::Recording, RecordingState::Paused];
+    let was_active = state.recording_state.transition(&app_handle, &active, RecordingState::Stopping).is_ok();
+    // Also ends the autosave loop for this recording
+    *state.recording_start_time.lock().await = None;
+    let _ = sckit::macos::stop_system_audio_capture().await;
+    state.audio_capture.set_paused(false);
+    let stopped = state.audio_capture.stop().map_err(AppError::Audio);
+    if was_active {
+        state.recording_state.set(&app_handle, RecordingState::Idle);
+    }
+
+    // Cancel first so the worker lets go of the transcriber
+    transcribe::cancel_all();
+    let discarded = state.transcription_queue.clear().await;
+    state.transcriber.lock().await.set_debug_recorder(None);
+    state.segments.lock().await.clear();
+    *state.segment_origin.lock().await = SegmentOrigin::default();
+    state.pending_notes.lock().await.clear();
+    *state.pending_input.lock().await = None;
+    *state.diarizer.lock().await = None;
+
+    let mut files = Vec::new();
+    files.extend(state.recording_audio_path.lock().await.take());
+    files.extend(state.recording_track2_path.lock().await.take());
+    if let Some(debug) = state.debug_audio.lock().await.take() {
+        files.push(debug.raw);
+        files.push(debug.whisper);
+    }
+    for path in &files {
+        if let Err(e) = std::fs::remove_file(path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                error!("Failed to remove audio {}: {}", path.display(), e);
             }
         }
     }
 
-    // Fallback mic/system runtime capture
-    state.audio_capture.start(app_handle, force_microphone)
+    if let Some(draft_id) = state.draft_session_id.lock().await.take() {
+        if let Some(database) = state.database.lock().await.as_ref() {
+            database
+                .delete_session(&draft_id)
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to delete draft: {}", e)))?;
+        }
+    }
+    info!("Recording discarded ({} queued chunks dropped)", discarded);
+    stopped
+}
+
+/// How often the in-progress transcript is written to a draft session.
+const AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Periodically autosave the recording that started at `recording_start` until it ends.
+fn spawn_autosave(app_handle: tauri::AppHandle, recording_start: u64) {
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        loop {
+            tokio::time::sleep(AUTOSAVE_INTERVAL).await;
+            if *state.recording_start_time.lock().await != Some(recording_start) {
+                break;
+            }
+            if let Err(e) = autosave_draft(&state, recording_start).await {
+                error!("Autosave failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Upsert the live segments so far into this recording's draft session, returning its id
+/// (`None` until something has been transcribed).
+async fn autosave_draft(state: &AppState, recording_start: u64) -> Result<Option<String>, String> {
+    let segments = state.segments.lock().await.clone();
+    if segments.is_empty() {
+        return Ok(None);
+    }
+    let transcript = transcribe::running_transcript(&segments);
+    let segments_json = serde_json::to_string(&segments).map_err(|e| format!("Failed to encode segments: {}", e))?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let duration = (now.saturating_sub(recording_start) / 1000) as i32;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or("Database not initialized")?;
+    let mut draft = state.draft_session_id.lock().await;
+    let session_id = match draft.as_ref() {
+        Some(id) => id.clone(),
+        None => {
+            let id = database
+                .create_session("Unsaved recording", duration)
+                .await
+                .map_err(|e| format!("Failed to create draft: {}", e))?;
+            database.set_session_draft(&id, true).await.map_err(|e| format!("Failed to mark draft: {}", e))?;
+            *draft = Some(id.clone());
+            id
+        }
+    };
+    let settings = database.get_settings().await.unwrap_or_default();
+    let (transcript, segments_json, raw) = redact::for_storage(&settings, &transcript, Some(&segments_json));
+    database
+        .update_session_transcript(&session_id, &transcript)
+        .await
+        .map_err(|e| format!("Failed to autosave transcript: {}", e))?;
+    database
+        .update_session_segments(&session_id, segments_json.as_deref().unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to autosave segments: {}", e))?;
+    database
+        .update_session_raw_transcript(&session_id, raw.as_deref())
+        .await
+        .map_err(|e| format!("Failed to autosave transcript: {}", e))?;
+    database
+        .update_session_duration(&session_id, duration)
+        .await
+        .map_err(|e| format!("Failed to autosave duration: {}", e))?;
+    Ok(Some(session_id))
+}
+
+/// Save the transcript so far to the recording's draft session now, without waiting for
+/// the next autosave or stopping capture. Returns the draft's id, or `None` if nothing
+/// has been transcribed yet.
+#[tauri::command]
+async fn checkpoint_session(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Option<String>, AppError> {
+    let recording_start = state
+        .recording_start_time
+        .lock()
+        .await
+        .ok_or_else(|| AppError::InvalidInput("Not recording".to_string()))?;
+    ensure_database(&app_handle, &state).await?;
+    let session_id = autosave_draft(&state, recording_start).await.map_err(AppError::Database)?;
+    if let Some(id) = &session_id {
+        info!("Checkpoint saved to draft session {}", id);
+    }
+    Ok(session_id)
+}
+
+/// The running transcript of the current recording so far, for a window that opened (or
+/// reloaded) after it started and missed the `transcript:updated` events. Empty when
+/// nothing has been transcribed.
+#[tauri::command]
+async fn get_live_transcript(state: State<'_, AppState>) -> Result<String, AppError> {
+    let segments = state.segments.lock().await;
+    Ok(transcribe::running_transcript(&segments))
+}
+
+/// Drafts from recordings that were interrupted (crash, power loss) before being saved.
+#[tauri::command]
+async fn list_recoverable_sessions(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<SessionRecord>, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let current = state.draft_session_id.lock().await.clone();
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let drafts = database
+        .list_draft_sessions()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to list drafts: {}", e)))?;
+    Ok(drafts.into_iter().filter(|d| Some(&d.id) != current.as_ref()).collect())
+}
+
+/// Keep a recovered draft as a regular session.
+#[tauri::command]
+async fn recover_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database
+        .set_session_draft(&session_id, false)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to recover session: {}", e)))
+}
+
+#[tauri::command]
+async fn discard_recoverable_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let draft = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .filter(|s| s.is_draft)
+        .ok_or_else(|| AppError::NotFound(format!("No unsaved recording {}", session_id)))?;
+    database
+        .delete_session(&draft.id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to discard session: {}", e)))
+}
+
+#[tauri::command]
+async fn pause_recording(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    state
+        .recording_state
+        .transition(&app_handle, &[RecordingState::Recording], RecordingState::Paused)
+        .map_err(AppError::InvalidInput)?;
+    state.audio_capture.set_paused(true);
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_recording(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    state
+        .recording_state
+        .transition(&app_handle, &[RecordingState::Paused], RecordingState::Recording)
+        .map_err(AppError::InvalidInput)?;
+    state.audio_capture.set_paused(false);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_recording_state(state: State<'_, AppState>) -> Result<RecordingState, AppError> {
+    Ok(state.recording_state.get())
+}
+
+
+#[tauri::command]
+async fn is_recording(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(matches!(state.recording_state.get(), RecordingState::Recording | RecordingState::Paused))
+}
+
+#[tauri::command]
+async fn get_recording_duration(state: State<'_, AppState>) -> Result<u32, AppError> {
+    let start_time_guard = state.recording_start_time.lock().await;
+    if let Some(start_time) = *start_time_guard {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let duration_ms = now - start_time;
+        Ok((duration_ms / 1000) as u32) // Return duration in seconds
+    } else {
+        Ok(0)
+    }
+}
+
+/// Save a timestamped note. With no `session_id`, a note taken while recording is held
+/// for the session that recording is saved as.
+#[tauri::command]
+async fn create_quick_note(text: String, session_id: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<database::NoteRecord, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::InvalidInput("Note is empty".to_string()));
+    }
+    ensure_database(&app_handle, &state).await?;
+    let note = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        database
+            .create_note(text, session_id.as_deref())
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to save note: {}", e)))?
+    };
+    if session_id.is_none() && state.audio_capture.is_capturing() {
+        state.pending_notes.lock().await.push(note.id.clone());
+    }
+    Ok(note)
+}
+
+#[tauri::command]
+async fn list_notes(session_id: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<database::NoteRecord>, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database
+        .list_notes(session_id.as_deref())
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to list notes: {}", e)))
+}
+
+async fn ensure_database(app_handle: &tauri::AppHandle, state: &State<'_, AppState>) -> Result<(), AppError> {
+    let mut db_guard = state.database.lock().await;
+    if db_guard.is_none() {
+        *db_guard = Some(open_database(&database_path(app_handle)).await.map_err(AppError::Database)?);
+    }
+    Ok(())
+}
+
+fn database_path(app_handle: &tauri::AppHandle) -> std::path::PathBuf {
+    app_data_dir(app_handle).join("oatmeal.db")
+}
+
+/// Open the database, unlocking it with the keychain passphrase if it's encrypted.
+async fn open_database(db_path: &std::path::Path) -> Result<Database, String> {
+    let key = if encryption::is_encrypted(db_path) {
+        let key = encryption::stored_passphrase()?
+            .ok_or("The database is encrypted but its key is missing from the keychain")?;
+        Some(key)
+    } else {
+        None
+    };
+    Database::new(db_path.to_str().ok_or("Invalid DB path")?, key.as_deref())
+        .await
+        .map_err(|e| format!("Failed to initialize database: {}", e))
+}
+
+/// Encrypt or decrypt the database file in place so it matches `encrypt_database`.
+async fn sync_database_encryption(app_handle: &tauri::AppHandle, state: &State<'_, AppState>, settings: &Settings) -> Result<(), String> {
+    let db_path = database_path(app_handle);
+    if encryption::is_encrypted(&db_path) == settings.encrypt_database {
+        return Ok(());
+    }
+    let key = if settings.encrypt_database {
+        Some(encryption::get_or_create_passphrase()?)
+    } else {
+        None
+    };
+
+    let mut db_guard = state.database.lock().await;
+    let database = db_guard.take().ok_or("Database not initialized")?;
+    let migrated = db_path.with_extension("db.migrating");
+    let _ = std::fs::remove_file(&migrated);
+    let exported = database
+        .export_to(&migrated, key.as_deref())
+        .await
+        .map_err(|e| format!("Failed to migrate database: {}", e));
+    database.close().await;
+
+    let result = exported.and_then(|()| {
+        // The export has everything, so leftover WAL files from the old file must not be replayed
+        for suffix in ["-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+        std::fs::rename(&migrated, &db_path).map_err(|e| format!("Failed to replace database: {}", e))
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&migrated);
+    }
+    // Reopen whatever is on disk now, whether or not the migration went through
+    *db_guard = Some(open_database(&db_path).await?);
+    result
+}
+
+fn remove_database_files(db_path: &std::path::Path) -> Result<(), String> {
+    for suffix in ["", "-wal", "-shm"] {
+        let path = format!("{}{}", db_path.display(), suffix);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to delete {}: {}", path, e)),
+        }
+    }
+    Ok(())
+}
+
+/// Delete the database and every saved recording, then start over with an empty
+/// database. Does nothing unless `confirm` is true. Returns how many sessions were removed.
+#[tauri::command]
+async fn reset_database(confirm: bool, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<i64, AppError> {
+    if !confirm {
+        return Err(AppError::InvalidInput("Resetting deletes every session and recording; pass confirm=true to proceed".to_string()));
+    }
+    if state.audio_capture.is_capturing() {
+        return Err(AppError::InvalidInput("Stop the recording before resetting the database".to_string()));
+    }
+    ensure_database(&app_handle, &state).await?;
+
+    let db_path = database_path(&app_handle);
+    let mut db_guard = state.database.lock().await;
+    let removed = db_guard
+        .as_ref()
+        .ok_or_else(AppError::no_database)?
+        .count_sessions()
+        .await
+        .map_err(|e| format!("Failed to count sessions: {}", e))?;
+    if let Some(database) = db_guard.take() {
+        database.close().await;
+    }
+    let deleted = remove_database_files(&db_path);
+    // Reopen whatever is on disk now; a fresh file gets the schema and default settings
+    *db_guard = Some(open_database(&db_path).await?);
+    drop(db_guard);
+    deleted?;
+
+    let recordings = app_data_dir(&app_handle).join("recordings");
+    if recordings.exists() {
+        std::fs::remove_dir_all(&recordings).map_err(|e| format!("Failed to delete recordings: {}", e))?;
+    }
+    *state.draft_session_id.lock().await = None;
+
+    info!("Database reset: removed {} sessions", removed);
+    Ok(removed)
+}
+
+#[tauri::command]
+async fn get_settings(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Settings, AppError> {
+    ensure_database(&app_handle, &state).await?;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+
+    database
+        .get_settings()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))
+}
+
+/// A user-entered base URL normalized with `llm::normalize_base_url`, or `default` when blank.
+fn base_url_setting(field: &str, value: &str, default: String) -> Result<String, String> {
+    if value.trim().is_empty() {
+        Ok(default)
+    } else {
+        llm::normalize_base_url(field, value)
+    }
+}
+
+/// Apply the settings in `settings` on top of the stored ones. Only the fields present are
+/// changed, and the read-merge-write happens under the database lock, so two screens saving
+/// different fields don't undo each other.
+#[tauri::command]
+async fn update_settings(settings: serde_json::Value, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Settings, AppError> {
+    ensure_database(&app_handle, &state).await?;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let current = database
+        .get_settings()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+    let mut settings = current.merged(&settings).map_err(AppError::InvalidInput)?;
+    llm::validate_summary_template(&settings.summary_prompt_template)?;
+    let defaults = Settings::default();
+    settings.ollama_host = base_url_setting("Ollama host", &settings.ollama_host, defaults.ollama_host)?;
+    settings.openai_base_url = base_url_setting("OpenAI base URL", &settings.openai_base_url, defaults.openai_base_url)?;
+    settings.anthropic_base_url = base_url_setting("Anthropic base URL", &settings.anthropic_base_url, defaults.anthropic_base_url)?;
+    settings.log_level = log_level_setting(&settings.log_level)?;
+    debug!("Saving settings: chunk_seconds={}, engine={}, model={}, host={}", settings.chunk_seconds, settings.summary_engine, settings.ollama_model, settings.ollama_host);
+
+    database
+        .update_settings(&settings)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update settings: {}", e)))?;
+
+    // Return the persisted settings
+    let reloaded = database
+        .get_settings()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to reload settings: {}", e)))?;
+    debug!("Reloaded settings: chunk_seconds={}, engine={}, model={}, host={}", reloaded.chunk_seconds, reloaded.summary_engine, reloaded.ollama_model, reloaded.ollama_host);
+    drop(db_guard);
+
+    logging::apply_level(&reloaded.log_level);
+    sync_database_encryption(&app_handle, &state, &reloaded).await?;
+    sync_api_server(&app_handle, &state, &reloaded).await?;
+    Ok(reloaded)
+}
+
+/// The summary engine with its model and host, without the rest of `Settings`.
+#[tauri::command]
+async fn get_summary_config(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<SummaryConfig, AppError> {
+    let settings = get_settings(app_handle, state).await?;
+    Ok(SummaryConfig::from_settings(&settings))
+}
+
+/// Switch summary engine, model and host without a full `update_settings` round-trip, so a
+/// stale copy of the other settings can't overwrite them. A blank model or host falls back to
+/// the engine's default.
+#[tauri::command]
+async fn set_summary_config(
+    engine: String,
+    model: String,
+    host: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SummaryConfig, AppError> {
+    let engine = engine.trim().to_lowercase();
+    let defaults = Settings::default();
+    let model = model.trim().to_string();
+    let (model, host) = match engine.as_str() {
+        "ollama" => (
+            if model.is_empty() { defaults.ollama_model } else { model },
+            base_url_setting("Ollama host", &host, defaults.ollama_host)?,
+        ),
+        "anthropic" => (model, base_url_setting("Anthropic base URL", &host, defaults.anthropic_base_url)?),
+        "openai" => (model, base_url_setting("OpenAI base URL", &host, defaults.openai_base_url)?),
+        "none" => (String::new(), String::new()),
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown summary engine '{}'; use ollama, anthropic, openai or none",
+                other
+            )))
+        }
+    };
+    let config = SummaryConfig { engine, model, host };
+
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database
+        .update_summary_config(&config)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update summary settings: {}", e)))?;
+    let settings = database
+        .get_settings()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to reload settings: {}", e)))?;
+    info!("Summary engine set to {}", settings.summary_engine);
+    Ok(SummaryConfig::from_settings(&settings))
+}
+
+fn log_level_setting(level: &str) -> Result<String, AppError> {
+    logging::parse_level(level)
+        .map(|filter| filter.as_str().to_lowercase())
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown log level '{}'; use off, error, warn, info, debug or trace", level)))
+}
+
+/// Change how much is logged, without a restart, and keep it for next launch.
+#[tauri::command]
+async fn set_log_level(level: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    let level = log_level_setting(&level)?;
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let mut settings = database
+        .get_settings()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+    settings.log_level = level;
+    database
+        .update_settings(&settings)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update settings: {}", e)))?;
+    logging::apply_level(&settings.log_level);
+    info!("Log level set to {}", settings.log_level);
+    Ok(())
+}
+
+/// Where the log file is, so users can attach it to a bug report.
+#[tauri::command]
+async fn get_log_path() -> Result<String, AppError> {
+    logging::log_path()
+        .map(|path| path.display().to_string())
+        .ok_or_else(|| AppError::Internal("Logging to a file isn't available".to_string()))
+}
+
+/// Start, restart or stop the local API so it matches `api_enabled`/`api_port`.
+async fn sync_api_server(app_handle: &tauri::AppHandle, state: &State<'_, AppState>, settings: &Settings) -> Result<(), String> {
+    let mut server = state.api_server.lock().await;
+    let port = u16::try_from(settings.api_port).map_err(|_| format!("Invalid API port {}", settings.api_port))?;
+    let wanted = settings.api_enabled;
+    if server.as_ref().map(|s| s.port) == Some(port) && wanted {
+        return Ok(());
+    }
+    if let Some(running) = server.take() {
+        running.stop();
+    }
+    if wanted {
+        let token = {
+            let db_guard = state.database.lock().await;
+            let database = db_guard.as_ref().ok_or("Database not initialized")?;
+            database.api_token().await.map_err(|e| format!("Failed to load API token: {}", e))?
+        };
+        *server = Some(api::start(app_handle.clone(), port, token).await?);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_api_token(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database.api_token().await.map_err(|e| AppError::Database(format!("Failed to load API token: {}", e)))
+}
+
+/// File name of the `whisper_model` setting, or base.en when none is chosen.
+async fn preferred_model_file(app_handle: &tauri::AppHandle, state: &State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(app_handle, state).await?;
+    let preferred = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        database.get_settings().await.map(|s| s.whisper_model).unwrap_or_default()
+    };
+    Ok(if preferred.trim().is_empty() {
+        "ggml-base.en.bin".to_string()
+    } else {
+        Transcriber::model_file_name(&preferred)
+    })
+}
+
+#[tauri::command]
+async fn initialize_transcriber(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    let preferred = preferred_model_file(&app_handle, &state).await?;
+    let mut transcriber = state.transcriber.lock().await;
+    transcriber.initialize(Some(&preferred)).await.map_err(AppError::Transcription)
+}
+
+/// Load the model if needed and prime it with a dummy inference, for the record screen to
+/// call before the meeting starts. Returns false when it was already warm.
+#[tauri::command]
+async fn warm_up_transcriber(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<bool, AppError> {
+    let preferred = preferred_model_file(&app_handle, &state).await?;
+    let mut transcriber = state.transcriber.lock().await;
+    if !transcriber.is_initialized() {
+        transcriber.initialize(Some(&preferred)).await.map_err(AppError::Transcription)?;
+    }
+    transcriber.warm_up().await.map_err(AppError::Transcription)
+}
+
+#[tauri::command]
+async fn list_available_models() -> Result<Vec<String>, AppError> {
+    Ok(Transcriber::list_available_models())
+}
+
+#[tauri::command]
+async fn get_active_model(state: State<'_, AppState>) -> Result<Option<String>, AppError> {
+    Ok(state.transcriber.lock().await.model_name())
+}
+
+/// Load `name` for live and file transcription and remember it for next launch.
+#[tauri::command]
+async fn set_active_model(name: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let active = {
+        let mut transcriber = state.transcriber.lock().await;
+        transcriber.set_active_model(&name).await?;
+        transcriber.model_name().unwrap_or(name)
+    };
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let mut settings = database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+    settings.whisper_model = active.clone();
+    database
+        .update_settings(&settings)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to save model choice: {}", e)))?;
+    let _ = app_handle.emit_all("model:changed", serde_json::json!({ "model": active }));
+    Ok(active)
+}
+
+#[tauri::command]
+async fn get_model_status(state: State<'_, AppState>) -> Result<transcribe::ModelStatus, AppError> {
+    Ok(state.transcriber.lock().await.model_status())
+}
+
+#[tauri::command]
+async fn download_whisper_model(model_name: String, app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let name = model_name.clone();
+    Transcriber::download_model(&model_name, |downloaded, total| {
+        let _ = app_handle.emit_all("model:download-progress", serde_json::json!({
+            "model": name,
+            "downloaded": downloaded,
+            "total": total,
+        }));
+    })
+    .await
+    .map(|_| ())
+    .map_err(|e| {
+        if e == transcribe::DOWNLOAD_CANCELLED {
+            let _ = app_handle.emit_all("model:download-cancelled", serde_json::json!({ "model": model_name }));
+        }
+        AppError::Network(e)
+    })
+}
+
+/// Stop a `download_whisper_model` in progress. Returns false when that model isn't
+/// downloading; otherwise `model:download-cancelled` follows once it has stopped.
+#[tauri::command]
+async fn cancel_model_download(model_name: String) -> Result<bool, AppError> {
+    Ok(transcribe::cancel_download(&model_name))
+}
+
+/// Queue a chunk for the transcription worker and return immediately; the text
+/// arrives as a `transcript:partial` event.
+#[tauri::command]
+async fn transcribe_audio(audio_frames: Vec<f32>, sample_rate: Option<u32>, source: Option<String>, state: State<'_, AppState>) -> Result<(), AppError> {
+    if !state.live_transcription.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    let recording_start = *state.recording_start_time.lock().await;
+    let end_ms = recording_start.map(|start| {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        now.saturating_sub(start)
+    });
+    state.transcription_queue.push(transcription_queue::AudioChunk {
+        samples: audio_frames,
+        sample_rate: sample_rate.unwrap_or(16_000),
+        source,
+        end_ms,
+        recording_start,
+        t_ms: None,
+    }).await;
+    Ok(())
+}
+
+/// Abort running transcriptions (live chunk, re-transcribe) and discard queued live chunks.
+#[tauri::command]
+async fn cancel_transcription(state: State<'_, AppState>) -> Result<(), AppError> {
+    transcribe::cancel_all();
+    let discarded = state.transcription_queue.clear().await;
+    info!("Transcription cancelled ({} queued chunks discarded)", discarded);
+    Ok(())
+}
+
+/// Consecutive chunks slower than real time before warning that the model can't keep up.
+const SLOW_CHUNKS_BEFORE_WARNING: u32 = 5;
+
+/// Transcribe queued chunks one at a time, in order, for the life of the app.
+async fn run_transcription_worker(app_handle: tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let mut assembler = transcription_queue::ChunkAssembler::default();
+    let mut current_recording: Option<Option<u64>> = None;
+    let mut slow_chunks = 0u32;
+    let mut adaptive = false;
+    let mut adapter = transcription_queue::AdaptiveChunking::default();
+    loop {
+        let chunk = if adaptive {
+            state.transcription_queue.pop_merged(adapter.current().merge_chunks).await
+        } else {
+            state.transcription_queue.pop().await
+        };
+        if current_recording != Some(chunk.recording_start) {
+            current_recording = Some(chunk.recording_start);
+            let settings = match state.database.lock().await.as_ref() {
+                Some(database) => database.get_settings().await.ok(),
+                None => None,
+            };
+            let settings = settings.unwrap_or_default();
+            let debug_path = state.debug_audio.lock().await.as_ref().map(|d| d.whisper.clone());
+            let debug_recorder = debug_path.and_then(|path| match audio::recorder::WavRecorder::create(&path, 16000) {
+                Ok(recorder) => Some(recorder),
+                Err(e) => {
+                    warn!("Whisper debug audio will not be saved: {}", e);
+                    None
+                }
+            });
+            assembler.reset(settings.chunk_overlap_ms.max(0) as u32, settings.normalize_numbers);
+            {
+                let mut transcriber = state.transcriber.lock().await;
+                transcriber.set_language(&settings.language);
+                transcriber.set_translate(settings.translate_to_english);
+                transcriber.set_preset(transcribe::TranscriptionPreset::from_setting(&settings.transcription_preset));
+                transcriber.set_thresholds(transcribe::DecodingThresholds {
+                    temperature: settings.whisper_temperature,
+                    entropy_thold: settings.whisper_entropy_thold,
+                    logprob_thold: settings.whisper_logprob_thold,
+                });
+                transcriber.set_collect_confidence(settings.live_confidence);
+                // Anything near a whole chunk would skip most of the recording
+                transcriber.set_min_transcribe_ms(settings.min_transcribe_ms.clamp(0, 2000) as u32);
+                transcriber.set_debug_recorder(debug_recorder);
+            }
+            slow_chunks = 0;
+            adaptive = settings.adaptive_chunking;
+            adapter.reset();
+        }
+        if adaptive && adapter.current().skip_quiet && transcribe::energy_db(&chunk.samples) <= transcription_queue::CATCH_UP_QUIET_DB {
+            let backlog = state.transcription_queue.backlog().await;
+            if let Some(level) = adapter.observe(backlog, None) {
+                report_adaptive_level(&app_handle, level, backlog, None);
+            }
+            assembler.skip(&chunk.source);
+            continue;
+        }
+        match transcribe_chunk(&state, &mut assembler, chunk).await {
+            Ok(Some((segment, running))) => {
+                let _ = app_handle.emit_all("transcript:partial", &segment);
+                if let Some(text) = running {
+                    let _ = app_handle.emit_all("transcript:updated", serde_json::json!({
+                        "text": text,
+                        "delta": segment.text,
+                        "source": segment.source,
+                    }));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!("Transcription failed: {}", e);
+                let _ = app_handle.emit_all("transcript:error", serde_json::json!({ "error": e }));
+            }
+        }
+
+        let (timing, model) = {
+            let mut transcriber = state.transcriber.lock().await;
+            (transcriber.take_timing(), transcriber.model_name())
+        };
+        let rtf = timing.as_ref().map(|t| t.rtf);
+        if let Some(timing) = timing {
+            let _ = app_handle.emit_all("transcript:timing", &timing);
+            slow_chunks = if timing.rtf > 1.0 { slow_chunks + 1 } else { 0 };
+            if slow_chunks == SLOW_CHUNKS_BEFORE_WARNING {
+                warn!("⚠️ Transcription is slower than real time (RTF {:.2}); consider a smaller model", timing.rtf);
+                let _ = app_handle.emit_all("transcript:lagging", serde_json::json!({
+                    "rtf": timing.rtf,
+                    "model": model,
+                }));
+            }
+        }
+        if adaptive {
+            let backlog = state.transcription_queue.backlog().await;
+            if let Some(level) = adapter.observe(backlog, rtf) {
+                report_adaptive_level(&app_handle, level, backlog, rtf);
+            }
+        }
+    }
+}
+
+fn report_adaptive_level(app_handle: &tauri::AppHandle, level: transcription_queue::AdaptiveLevel, backlog: usize, rtf: Option<f32>) {
+    info!(
+        "Adaptive chunking: {} chunk(s) per pass, quiet chunks {} (backlog {}, RTF {:?})",
+        level.merge_chunks,
+        if level.skip_quiet { "skipped" } else { "kept" },
+        backlog,
+        rtf
+    );
+    let _ = app_handle.emit_all("transcript:adapted", serde_json::json!({
+        "merge_chunks": level.merge_chunks,
+        "skip_quiet": level.skip_quiet,
+        "backlog": backlog,
+        "rtf": rtf,
+    }));
+}
+
+async fn transcribe_chunk(
+    state: &AppState,
+    assembler: &mut transcription_queue::ChunkAssembler,
+    chunk: transcription_queue::AudioChunk,
+) -> Result<Option<(TranscriptSegment, Option<String>)>, String> {
+    let sr = chunk.sample_rate;
+    let samples = assembler.with_context(&chunk);
+    let (text, language, words, model, translated) = {
+        let mut transcriber = state.transcriber.lock().await;
+        if !transcriber.is_initialized() {
+            info!("Transcriber not initialized; attempting lazy initialization...");
+            // Try default selection; initialize() will search for an available model
+            match transcriber.initialize(None).await {
+                Ok(()) => info!("✅ Lazy initialization successful"),
+                Err(e) => {
+                    error!("❌ Lazy initialization failed: {}", e);
+                    return Err(e);
+                }
+            }
+        }
+        let text = transcriber.transcribe_audio_data(&samples, sr).await?;
+        (text, transcriber.last_language(), transcriber.take_words(), transcriber.model_name(), transcriber.is_translating())
+    };
+
+    let cleaned = assembler.merge_text(&chunk.source, text.trim());
+    if cleaned.is_empty() {
+        return Ok(None);
+    }
+    let chunk_ms = (chunk.samples.len() as u64 * 1000) / sr.max(1) as u64;
+    let end_ms = chunk.end_ms.unwrap_or(chunk_ms);
+    // Diarization labels (S1, S2, ...) take precedence over the me/them source label
+    let diarized = match state.diarizer.lock().await.as_mut() {
+        Some(diarizer) => diarizer.assign(&chunk.samples, sr),
+        None => None,
+    };
+    let speaker = diarized.or_else(|| chunk.source.as_deref().and_then(TranscriptSegment::speaker_for_source));
+    let (confidence, words) = match words {
+        Some(words) => transcribe::chunk_confidence(&words, &cleaned),
+        None => (None, None),
+    };
+    let segment = TranscriptSegment {
+        start_ms: end_ms.saturating_sub(chunk_ms),
+        end_ms,
+        text: cleaned,
+        source: chunk.source,
+        speaker,
+        language,
+        confidence,
+        t_ms: chunk.t_ms,
+        words,
+    };
+    // Only chunks from a running recording belong to the session being captured; their
+    // running transcript is what `save_session` stores
+    let running = if chunk.end_ms.is_some() {
+        let mut segments = state.segments.lock().await;
+        // Word-level confidence is only for live captions
+        segments.push(TranscriptSegment { words: None, ..segment.clone() });
+        let mut origin = state.segment_origin.lock().await;
+        if let Some(model) = model.filter(|m| !origin.models.contains(m)) {
+            origin.models.push(model);
+        }
+        origin.translated |= translated;
+        Some(transcribe::running_transcript(&segments))
+    } else {
+        None
+    };
+    Ok(Some((segment, running)))
+}
+
+#[tauri::command]
+async fn save_session(title: String, duration: i32, transcript: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+
+    // Attach the live segments captured during this recording. Their running transcript
+    // wins over the text passed in, which only matters when nothing was transcribed live.
+    let (transcript, segments, language, speakers) = {
+        let mut segments = state.segments.lock().await;
+        if segments.is_empty() {
+            (transcript, None, None, Vec::new())
+        } else {
+            let json = serde_json::to_string(&*segments).map_err(|e| format!("Failed to encode segments: {}", e))?;
+            let language = transcribe::dominant_language(&segments);
+            let running = transcribe::running_transcript(&segments);
+            let speakers = transcribe::speakers(&segments);
+            segments.clear();
+            (running, Some(json), language, speakers)
+        }
+    };
+
+    let audio_path = state
+        .recording_audio_path
+        .lock()
+        .await
+        .take()
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string());
+    let track2_audio_path = state
+        .recording_track2_path
+        .lock()
+        .await
+        .take()
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string());
+
+    let debug_audio = state.debug_audio.lock().await.take();
+    // Whatever transcribed the segments, not whatever model and settings are current now
+    let origin = std::mem::take(&mut *state.segment_origin.lock().await);
+    let transcription_model = Some(origin.models.join(", ")).filter(|m| !m.is_empty());
+    let translated = origin.translated;
+    if debug_audio.is_some() {
+        // Closes the Whisper WAV; chunks still queued aren't worth keeping it open for
+        state.transcriber.lock().await.set_debug_recorder(None);
+    }
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+
+    let settings = database.get_settings().await.unwrap_or_default();
+    let (transcript, segments, raw) = redact::for_storage(&settings, &transcript, segments.as_deref());
+    let session_id = database
+        .save_session(&title, duration, &transcript, segments.as_deref(), audio_path.as_deref(), transcription_model.as_deref())
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to save session: {}", e)))?;
+    if raw.is_some() {
+        database
+            .update_session_raw_transcript(&session_id, raw.as_deref())
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to save raw transcript: {}", e)))?;
+    }
+    if let Some(language) = language {
+        database
+            .update_session_language(&session_id, &language)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to save session language: {}", e)))?;
+    }
+    if translated {
+        database
+            .update_session_translated(&session_id, true)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to save translation flag: {}", e)))?;
+    }
+    if !speakers.is_empty() {
+        if let Err(e) = database.replace_session_participants(&session_id, "label", &speakers).await {
+            error!("Failed to save participants: {}", e);
+        }
+    }
+    if let Some(path) = track2_audio_path {
+        database
+            .update_session_track2_audio(&session_id, &path)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to save track 2 audio path: {}", e)))?;
+    }
+    if let Some(debug) = debug_audio {
+        let saved = |path: &std::path::Path| Some(path).filter(|p| p.exists()).map(|p| p.to_string_lossy().to_string());
+        database
+            .update_session_debug_audio(&session_id, saved(&debug.raw).as_deref(), saved(&debug.whisper).as_deref())
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to save debug audio paths: {}", e)))?;
+    }
+    // The real session supersedes the autosaved draft
+    if let Some(draft_id) = state.draft_session_id.lock().await.take() {
+        if let Err(e) = database.delete_session(&draft_id).await {
+            error!("Failed to remove autosave draft: {}", e);
+        }
+    }
+    let pending_notes = std::mem::take(&mut *state.pending_notes.lock().await);
+    if !pending_notes.is_empty() {
+        database
+            .attach_notes(&pending_notes, &session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to attach notes: {}", e)))?;
+    }
+    remember_input(&state, database).await;
+    notify_webhook(database, "session.saved", &session_id).await;
+    drop(db_guard);
+
+    spawn_embedding_index(app_handle.clone(), session_id.clone());
+
+    // Name untitled sessions in the background so saving never waits on the LLM
+    if llm::is_default_title(&title) {
+        let app_handle_title = app_handle.clone();
+        let session_id_title = session_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app_handle_title.state::<AppState>();
+            if let Err(e) = generate_session_title(&app_handle_title, &state, &session_id_title).await {
+                error!("Automatic title generation failed: {}", e);
+            }
+        });
+    }
+
+    Ok(session_id)
+}
+
+/// Embed a session's transcript for semantic search in the background, if enabled.
+fn spawn_embedding_index(app_handle: tauri::AppHandle, session_id: String) {
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        if let Err(e) = index_session_embeddings(&state, &session_id).await {
+            error!("Semantic indexing of {} failed: {}", session_id, e);
+        }
+    });
+}
+
+async fn index_session_embeddings(state: &State<'_, AppState>, session_id: &str) -> Result<(), String> {
+    let (transcript, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or("Database not initialized")?;
+        let settings = database.get_settings().await.map_err(|e| format!("Failed to get settings: {}", e))?;
+        if !embeddings::enabled(&settings) {
+            return Ok(());
+        }
+        let session = database
+            .get_session(session_id)
+            .await
+            .map_err(|e| format!("Failed to get session: {}", e))?
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        (session.transcript.unwrap_or_default(), settings)
+    };
+
+    // Embed without holding the database lock
+    let mut vectors = Vec::new();
+    for chunk in embeddings::chunks(&transcript) {
+        vectors.push(embeddings::to_blob(&embeddings::embed(&settings, &chunk).await?));
+    }
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or("Database not initialized")?;
+    database
+        .replace_session_embeddings(session_id, &embeddings::model_id(&settings), &vectors)
+        .await
+        .map_err(|e| format!("Failed to store embeddings: {}", e))
+}
+
+#[derive(serde::Serialize)]
+struct SemanticMatch {
+    session: SessionRecord,
+    score: f32,
+}
+
+#[tauri::command]
+async fn semantic_search(query: String, k: Option<usize>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<SemanticMatch>, AppError> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    ensure_database(&app_handle, &state).await?;
+    let settings = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?
+    };
+    if !embeddings::enabled(&settings) {
+        return Err(AppError::InvalidInput("Semantic search is off; choose an embedding engine in Settings".to_string()));
+    }
+    let query_vector = embeddings::embed(&settings, query.trim()).await?;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let stored = database
+        .session_embeddings(&embeddings::model_id(&settings))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to load embeddings: {}", e)))?;
+
+    // A session scores as well as its best-matching chunk
+    let mut best: std::collections::HashMap<String, f32> = std::collections::HashMap::new();
+    for (session_id, blob) in stored {
+        let score = embeddings::cosine(&query_vector, &embeddings::from_blob(&blob));
+        let entry = best.entry(session_id).or_insert(f32::MIN);
+        *entry = entry.max(score);
+    }
+    let mut ranked: Vec<(String, f32)> = best.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(k.unwrap_or(10).clamp(1, 100));
+
+    let mut matches = Vec::with_capacity(ranked.len());
+    for (session_id, score) in ranked {
+        if let Some(session) = database.get_session(&session_id).await.map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))? {
+            matches.push(SemanticMatch { session, score });
+        }
+    }
+    Ok(matches)
+}
+
+/// Embed every session that isn't indexed with the current model yet; returns how many were.
+#[tauri::command]
+async fn rebuild_semantic_index(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<usize, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let missing = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let settings = database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        if !embeddings::enabled(&settings) {
+            return Err(AppError::InvalidInput("Semantic search is off; choose an embedding engine in Settings".to_string()));
+        }
+        database
+            .sessions_missing_embeddings(&embeddings::model_id(&settings))
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to list sessions: {}", e)))?
+    };
+    for session_id in &missing {
+        index_session_embeddings(&state, session_id).await?;
+    }
+    Ok(missing.len())
+}
+
+async fn generate_session_title(app_handle: &tauri::AppHandle, state: &State<'_, AppState>, session_id: &str) -> Result<String, String> {
+    ensure_database(app_handle, state).await?;
+
+    // Read what we need and release the lock before calling out to the LLM
+    let (session, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or("Database not initialized")?;
+        let session = database
+            .get_session(session_id)
+            .await
+            .map_err(|e| format!("Failed to get session: {}", e))?
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| format!("Failed to get settings: {}", e))?;
+        (session, settings)
+    };
+
+    let transcript = session.transcript.as_deref().unwrap_or("");
+    let title = match llm::generate_title(&settings, transcript).await {
+        Ok(title) => title,
+        Err(e) => {
+            warn!("⚠️ Title generation unavailable ({}); using date", e);
+            llm::date_title(&session.date)
+        }
+    };
+
+    {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or("Database not initialized")?;
+        database
+            .update_session_title(session_id, &title)
+            .await
+            .map_err(|e| format!("Failed to update session title: {}", e))?;
+    }
+
+    let _ = app_handle.emit_all(
+        "session:title-updated",
+        serde_json::json!({ "session_id": session_id, "title": title }),
+    );
+    Ok(title)
+}
+
+#[tauri::command]
+async fn generate_title(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    Ok(generate_session_title(&app_handle, &state, &session_id).await?)
+}
+
+#[tauri::command]
+async fn retranscribe_session(session_id: String, model_name: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let (audio_path, language, translate) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session.audio_path.map(std::path::PathBuf::from), settings.language, settings.translate_to_english)
+    };
+    let audio_path = match audio_path {
+        Some(p) if p.exists() => p,
+        Some(_) => return Err(AppError::NotFound("The saved audio for this session is missing".to_string())),
+        None => return Err(AppError::NotFound("No audio was saved for this session. Turn on \"Save audio\" to enable re-transcription.".to_string())),
+    };
+
+    // Download + transcription can take minutes; report through events instead of blocking
+    let app_handle_task = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let emit_progress = |stage: &str, progress: f32| {
+            let _ = app_handle_task.emit_all("retranscribe:progress", serde_json::json!({
+                "session_id": session_id,
+                "stage": stage,
+                "progress": progress,
+            }));
+        };
+        let result: Result<String, String> = async {
+            let model_path = Transcriber::download_model(&model_name, |downloaded, total| {
+                if let Some(total) = total.filter(|t| *t > 0) {
+                    emit_progress("downloading", downloaded as f32 / total as f32);
+                }
+            }).await?;
+
+            emit_progress("transcribing", 0.0);
+            let model_label = Transcriber::model_label(&model_path);
+            let app_handle_blocking = app_handle_task.clone();
+            let session_id_blocking = session_id.clone();
+            let segments = tauri::async_runtime::spawn_blocking(move || {
+                let (samples, sample_rate) = audio::recorder::read_wav(&audio_path)?;
+                let audio_16k = audio::resample::StreamResampler::new(sample_rate, 16_000).process(&samples);
+                Transcriber::transcribe_file(&model_path, &audio_16k, &language, translate, |progress| {
+                    let _ = app_handle_blocking.emit_all("retranscribe:progress", serde_json::json!({
+                        "session_id": session_id_blocking,
+                        "stage": "transcribing",
+                        "progress": progress,
+                    }));
+                })
+            })
+            .await
+            .map_err(|e| format!("Transcription task failed: {}", e))??;
+
+            let transcript = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+            let segments_json = serde_json::to_string(&segments).map_err(|e| format!("Failed to encode segments: {}", e))?;
+            let state = app_handle_task.state::<AppState>();
+            let db_guard = state.database.lock().await;
+            let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+            let settings = database.get_settings().await.unwrap_or_default();
+            let (transcript, segments_json, raw) = redact::for_storage(&settings, &transcript, Some(&segments_json));
+            database
+                .update_session_transcript(&session_id, &transcript)
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to update transcript: {}", e)))?;
+            database
+                .update_session_segments(&session_id, segments_json.as_deref().unwrap_or_default())
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to update segments: {}", e)))?;
+            database
+                .update_session_raw_transcript(&session_id, raw.as_deref())
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to update raw transcript: {}", e)))?;
+            database
+                .update_session_transcription_model(&session_id, &model_label)
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to update transcription model: {}", e)))?;
+            database
+                .update_session_translated(&session_id, translate)
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to update translation flag: {}", e)))?;
+            if let Some(language) = transcribe::dominant_language(&segments) {
+                database
+                    .update_session_language(&session_id, &language)
+                    .await
+                    .map_err(|e| AppError::Database(format!("Failed to update language: {}", e)))?;
+            }
+            Ok(transcript)
+        }.await;
+
+        match result {
+            Ok(transcript) => {
+                let _ = app_handle_task.emit_all("retranscribe:done", serde_json::json!({
+                    "session_id": session_id,
+                    "transcript": transcript,
+                }));
+            }
+            Err(e) => {
+                error!("Re-transcription of {} failed: {}", session_id, e);
+                let _ = app_handle_task.emit_all("retranscribe:error", serde_json::json!({
+                    "session_id": session_id,
+                    "error": e,
+                }));
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Option<SessionRecord>, AppError> {
+    ensure_database(&app_handle, &state).await?;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+
+    database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))
+}
+
+#[tauri::command]
+async fn get_session_stats(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<stats::SessionStats, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let session = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+    Ok(stats::session_stats(&session))
+}
+
+#[tauri::command]
+async fn get_overall_stats(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<stats::OverallStats, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    // -1 lifts the default page limit; stats cover every saved session
+    let sessions = database.list_sessions(Some(-1)).await.map_err(|e| AppError::Database(format!("Failed to list sessions: {}", e)))?;
+    let folders = database.list_folders().await.map_err(|e| AppError::Database(format!("Failed to list folders: {}", e)))?;
+    Ok(stats::overall_stats(&sessions, &folders))
+}
+
+#[tauri::command]
+async fn export_session_markdown(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let session = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+    Ok(export::session_markdown(&session))
+}
+
+/// Summary and action items formatted for the clipboard (`format` is "text" or
+/// "markdown"). Errors when the session hasn't been summarized so the UI can offer to.
+#[tauri::command]
+async fn get_shareable_summary(
+    session_id: String,
+    format: export::ShareFormat,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let session = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+    export::shareable_summary(&session, format).ok_or_else(|| AppError::NotFound("Session has no summary yet".to_string()))
+}
+
+#[tauri::command]
+async fn export_session_json(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<export::SessionExport, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let session = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+    let folder = match session.folder_id.as_deref() {
+        Some(folder_id) => database.get_folder(folder_id).await.map_err(|e| AppError::Database(format!("Failed to get folder: {}", e)))?,
+        None => None,
+    };
+    Ok(export::session_export(&session, folder.as_ref()))
+}
+
+/// Zip the session's audio (if saved), transcript, Markdown notes, segments and metadata
+/// into `path`, returning the path written.
+#[tauri::command]
+async fn export_session_bundle(session_id: String, path: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let (session, folder) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let folder = match session.folder_id.as_deref() {
+            Some(folder_id) => database.get_folder(folder_id).await.map_err(|e| AppError::Database(format!("Failed to get folder: {}", e)))?,
+            None => None,
+        };
+        (session, folder)
+    };
+    let written = tokio::task::spawn_blocking(move || bundle::write_bundle(&session, folder.as_ref(), std::path::Path::new(&path)))
+        .await
+        .map_err(|e| format!("Bundle export failed: {}", e))??;
+    let written = written.to_string_lossy().to_string();
+    info!("Exported session {} to {}", session_id, written);
+    Ok(written)
+}
+
+#[tauri::command]
+async fn export_all_sessions(path: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<backup::BackupReport, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let report = backup::export_all(database, std::path::Path::new(&path)).await?;
+    info!("Exported {} sessions and {} folders to {}", report.sessions, report.folders, path);
+    Ok(report)
+}
+
+#[tauri::command]
+async fn import_sessions(path: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<backup::BackupReport, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let report = backup::import(database, std::path::Path::new(&path)).await?;
+    info!("Imported {} sessions and {} folders from {} ({} skipped)", report.sessions, report.folders, path, report.skipped);
+    Ok(report)
+}
+
+#[tauri::command]
+async fn create_hubspot_note(session_id: String, contact_email: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let (session, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session, settings)
+    };
+    if !settings.enable_hubspot {
+        return Err(AppError::InvalidInput("HubSpot integration is disabled".to_string()));
+    }
+    hubspot::create_note(&session, &contact_email).await.map_err(AppError::Network)
+}
+
+#[tauri::command]
+async fn create_followup_draft(session_id: String, to: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let (session, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session, settings)
+    };
+    if !settings.enable_gmail {
+        return Err(AppError::InvalidInput("Gmail integration is disabled".to_string()));
+    }
+    let engine = llm::engine_from_settings(&settings)?
+        .ok_or_else(|| AppError::InvalidInput("Summary engine is set to 'none'".to_string()))?;
+    let transcript = session.transcript.as_deref().unwrap_or_default();
+    if transcript.trim().is_empty() && session.summary.is_none() {
+        return Err(AppError::InvalidInput("Session has no transcript to follow up on".to_string()));
+    }
+
+    let body = llm::followup_email(engine.as_ref(), &session.title, session.summary.as_deref(), transcript)
+        .await
+        .map_err(AppError::Network)?;
+    gmail::create_draft(&to, &format!("Follow-up: {}", session.title), &body).await.map_err(AppError::Network)
+}
+
+#[tauri::command]
+async fn list_sessions(
+    limit: Option<i32>,
+    favorites_only: Option<bool>,
+    favorites_first: Option<bool>,
+    from: Option<String>,
+    to: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<SessionRecord>, AppError> {
+    ensure_database(&app_handle, &state).await?;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+
+    let from = date_bound(database, "from", from.as_deref()).await?;
+    let to = date_bound(database, "to", to.as_deref()).await?;
+    if let (Some(from), Some(to)) = (&from, &to) {
+        if from > to {
+            return Err(AppError::InvalidInput(format!("from ({}) is after to ({})", from, to)));
+        }
+    }
+
+    let filter = SessionFilter {
+        limit,
+        favorites_only: favorites_only.unwrap_or(false),
+        favorites_first: favorites_first.unwrap_or(false),
+        from,
+        to,
+    };
+    database
+        .query_sessions(&filter)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to list sessions: {}", e)))
+}
+
+/// A `list_sessions` date bound in the database's timestamp form. Missing or blank
+/// bounds leave that end of the range open. A plain date as `to` includes that whole day.
+async fn date_bound(database: &Database, name: &str, value: Option<&str>) -> Result<Option<String>, AppError> {
+    let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    database
+        .parse_timestamp(value, name == "to" && is_plain_date(value))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to parse {}: {}", name, e)))?
+        .map(Some)
+        .ok_or_else(|| AppError::InvalidInput(format!("Invalid {} timestamp: {}", name, value)))
+}
+
+/// "YYYY-MM-DD" with no time part.
+fn is_plain_date(value: &str) -> bool {
+    value.len() == 10
+        && value
+            .bytes()
+            .enumerate()
+            .all(|(i, b)| if i == 4 || i == 7 { b == b'-' } else { b.is_ascii_digit() })
+}
+
+#[tauri::command]
+async fn set_session_favorite(session_id: String, is_favorite: bool, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+
+    let updated = database
+        .set_session_favorite(&session_id, is_favorite)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update session: {}", e)))?;
+    if !updated {
+        return Err(AppError::NotFound(format!("Session {} not found", session_id)));
+    }
+    Ok(())
+}
+
+/// Move a session to the trash; it can be restored for `TRASH_RETENTION_DAYS`.
+#[tauri::command]
+async fn delete_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let trashed = database
+        .trash_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to delete session: {}", e)))?;
+    if !trashed {
+        return Err(AppError::NotFound(format!("Session {} not found", session_id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn restore_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let restored = database
+        .restore_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to restore session: {}", e)))?;
+    if !restored {
+        return Err(AppError::NotFound(format!("Session {} is not in the trash", session_id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_trashed_sessions(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<SessionRecord>, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database
+        .list_trashed_sessions()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to list trash: {}", e)))
+}
+
+/// Permanently delete everything in the trash, including saved audio. Returns the count.
+#[tauri::command]
+async fn empty_trash(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<usize, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let purged = database
+        .purge_trashed_sessions(None)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to empty trash: {}", e)))?;
+    remove_session_audio(&purged);
+    Ok(purged.len())
+}
+
+fn join_transcripts(first: Option<&str>, second: Option<&str>) -> Option<String> {
+    let parts: Vec<&str> = [first, second]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("\n\n"))
+    }
+}
+
+/// `secondary` appended to `primary`: transcripts concatenated, and the secondary's
+/// segments and chapters shifted to start where the primary ends.
+fn merge_session_content(primary: &SessionRecord, secondary: &SessionRecord) -> Result<database::SessionMerge, String> {
+    let mut segments = export::parse_segments(primary);
+    let mut chapters = export::parse_chapters(primary);
+    let offset_ms = merge_offset_ms(primary);
+    segments.extend(export::parse_segments(secondary).into_iter().map(|mut s| {
+        s.start_ms += offset_ms;
+        s.end_ms += offset_ms;
+        s
+    }));
+    chapters.extend(export::parse_chapters(secondary).into_iter().map(|mut c| {
+        c.start_ms += offset_ms;
+        c.end_ms += offset_ms;
+        c
+    }));
+
+    // Only keep a raw copy when one side has been redacted
+    let raw_transcript = if primary.raw_transcript.is_some() || secondary.raw_transcript.is_some() {
+        join_transcripts(
+            primary.raw_transcript.as_deref().or(primary.transcript.as_deref()),
+            secondary.raw_transcript.as_deref().or(secondary.transcript.as_deref()),
+        )
+    } else {
+        None
+    };
+    Ok(database::SessionMerge {
+        transcript: join_transcripts(primary.transcript.as_deref(), secondary.transcript.as_deref()),
+        segments: if segments.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&segments).map_err(|e| format!("Failed to encode segments: {}", e))?)
+        },
+        chapters: if chapters.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&chapters).map_err(|e| format!("Failed to encode chapters: {}", e))?)
+        },
+        raw_transcript,
+        duration: primary.duration.max(0).saturating_add(secondary.duration.max(0)),
+        ..Default::default()
+    })
+}
+
+/// Where the secondary's timeline starts in a merged session: after the primary's
+/// duration and anything it has timed past that.
+fn merge_offset_ms(primary: &SessionRecord) -> u64 {
+    export::parse_segments(primary)
+        .iter()
+        .map(|s| s.end_ms)
+        .chain(export::parse_chapters(primary).iter().map(|c| c.end_ms))
+        .fold(primary.duration.max(0) as u64 * 1000, u64::max)
+}
+
+/// Build each merged recording, given as (primary's, secondary's) path, next to where it
+/// will end up: the primary's audio padded to `offset_ms`, then the secondary's. Returns
+/// the (temporary file, final path) of each one built; a secondary recording that can't
+/// be appended is left out, to be kept on disk rather than lost.
+fn stage_merged_audio(recordings: &[(Option<String>, Option<String>)], offset_ms: u64) -> Vec<Option<(String, String)>> {
+    use std::path::Path;
+    recordings
+        .iter()
+        .map(|(first, second)| {
+            let second = second.as_deref().filter(|s| Some(*s) != first.as_deref())?;
+            let target = first.as_deref().unwrap_or(second).to_string();
+            let staged = format!("{}.merging.wav", target);
+            match audio::recorder::concat_wav(first.as_deref().map(Path::new), Path::new(second), offset_ms, Path::new(&staged)) {
+                Ok(()) => Some((staged, target)),
+                Err(e) => {
+                    warn!("Keeping {} unmerged: {}", second, e);
+                    let _ = std::fs::remove_file(&staged);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Append `secondary_id` to `primary_id` for a recording that got split in two. The
+/// primary keeps its title, summary and other metadata, and its recording gets the
+/// secondary's appended; the secondary is deleted. Returns the merged session.
+#[tauri::command]
+async fn merge_sessions(primary_id: String, secondary_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<SessionRecord, AppError> {
+    if primary_id == secondary_id {
+        return Err(AppError::InvalidInput("Can't merge a session into itself".to_string()));
+    }
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+
+    let mut sessions = Vec::with_capacity(2);
+    for id in [&primary_id, &secondary_id] {
+        let session = database
+            .get_session(id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .filter(|s| s.deleted_at.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", id)))?;
+        sessions.push(session);
+    }
+    let secondary = sessions.pop().expect("two sessions");
+    let primary = sessions.pop().expect("two sessions");
+
+    let mut merged = merge_session_content(&primary, &secondary)?;
+    // The secondary's segments now sit past the primary's audio; its recording goes there too
+    let recordings = [
+        (primary.audio_path.clone(), secondary.audio_path.clone()),
+        (primary.track2_audio_path.clone(), secondary.track2_audio_path.clone()),
+    ];
+    let offset_ms = merge_offset_ms(&primary);
+    let staged = tokio::task::spawn_blocking(move || stage_merged_audio(&recordings, offset_ms))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to merge audio: {}", e)))?;
+    merged.audio_path = staged[0].as_ref().map(|(_, target)| target.clone());
+    merged.track2_audio_path = staged[1].as_ref().map(|(_, target)| target.clone());
+    let discard_staged = || {
+        for (path, _) in staged.iter().flatten() {
+            let _ = std::fs::remove_file(path);
+        }
+    };
+    let done = match database.merge_sessions(&primary_id, &secondary_id, &merged).await {
+        Ok(done) => done,
+        Err(e) => {
+            discard_staged();
+            return Err(AppError::Database(format!("Failed to merge sessions: {}", e)));
+        }
+    };
+    if !done {
+        discard_staged();
+        return Err(AppError::NotFound("One of the sessions was deleted while merging".to_string()));
+    }
+    for (path, target) in staged.iter().flatten() {
+        if let Err(e) = std::fs::rename(path, target) {
+            error!("Failed to move merged audio into {}: {}", target, e);
+        }
+    }
+    // The secondary's recordings are now part of the primary's, unless they couldn't be
+    // appended (kept) or became the primary's outright (moved over in place)
+    let mut leftover: Vec<&str> = [&secondary.debug_raw_audio_path, &secondary.debug_whisper_audio_path]
+        .into_iter()
+        .filter_map(|path| path.as_deref())
+        .collect();
+    for (path, staged) in [&secondary.audio_path, &secondary.track2_audio_path].into_iter().zip(&staged) {
+        if let (Some(path), Some((_, target))) = (path, staged) {
+            if path != target {
+                leftover.push(path);
+            }
+        }
+    }
+    remove_audio_files(leftover.into_iter());
+    let session = database
+        .get_session(&primary_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", primary_id)))?;
+    drop(db_guard);
+    spawn_embedding_index(app_handle, primary_id);
+    Ok(session)
+}
+
+/// Best-effort removal of the WAV files behind permanently deleted sessions.
+fn remove_session_audio(sessions: &[SessionRecord]) {
+    remove_audio_files(
+        sessions
+            .iter()
+            .flat_map(|s| [&s.audio_path, &s.track2_audio_path, &s.debug_raw_audio_path, &s.debug_whisper_audio_path])
+            .filter_map(|path| path.as_deref()),
+    );
+}
+
+fn remove_audio_files<'a>(paths: impl Iterator<Item = &'a str>) {
+    for path in paths {
+        if let Err(e) = std::fs::remove_file(path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                error!("Failed to remove audio {}: {}", path, e);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+async fn update_session_summary(session_id: String, summary: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database
+        .update_session_summary(&session_id, &summary)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update session summary: {}", e)))
 }
 
+/// Save the user's notes on a session; blank notes clear the field.
 #[tauri::command]
-async fn stop_recording(state: State<'_, AppState>) -> Result<(), String> {
-    // Clear recording start time when stopping
-    *state.recording_start_time.lock().await = None;
-    state.audio_capture.stop()
+async fn update_session_notes(session_id: String, notes: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let notes = Some(notes.trim_end()).filter(|n| !n.trim().is_empty());
+    let updated = database
+        .update_session_notes(&session_id, notes)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update session notes: {}", e)))?;
+    if !updated {
+        return Err(AppError::NotFound(format!("Session {} not found", session_id)));
+    }
+    Ok(())
 }
 
-
+/// The session's structured outputs; empty lists when none have been extracted yet.
 #[tauri::command]
-async fn is_recording(state: State<'_, AppState>) -> Result<bool, String> {
-    Ok(state.audio_capture.is_capturing())
+async fn get_artifacts(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<export::Artifacts, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let session = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+    Ok(session.artifacts.unwrap_or_default())
 }
 
+/// Add the session's action items to Reminders, asking for access the first time. Items
+/// are taken from the stored artifacts, or pulled out of the summary and stored first.
+/// Returns how many reminders were created.
 #[tauri::command]
-async fn get_recording_duration(state: State<'_, AppState>) -> Result<u32, String> {
-    let start_time_guard = state.recording_start_time.lock().await;
-    if let Some(start_time) = *start_time_guard {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-        let duration_ms = now - start_time;
-        Ok((duration_ms / 1000) as u32) // Return duration in seconds
-    } else {
-        Ok(0)
+async fn export_action_items_to_reminders(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<usize, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let (title, items) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let mut artifacts = session.artifacts.clone().unwrap_or_default();
+        if artifacts.action_items.is_empty() {
+            if let Some(summary) = session.summary.as_deref() {
+                artifacts.merge_summary(summary);
+                if !artifacts.action_items.is_empty() {
+                    database
+                        .update_session_artifacts(&session_id, Some(&artifacts))
+                        .await
+                        .map_err(|e| AppError::Database(format!("Failed to update artifacts: {}", e)))?;
+                }
+            }
+        }
+        (session.title, artifacts.action_items)
+    };
+    if items.is_empty() {
+        return Err(AppError::InvalidInput("This session has no action items".to_string()));
     }
+    add_reminders(title, items).await
 }
 
-#[tauri::command]
-async fn create_quick_note() -> Result<(), String> {
-    println!("Creating quick note");
-    Ok(())
+#[cfg(target_os = "macos")]
+async fn add_reminders(title: String, items: Vec<export::ActionItem>) -> Result<usize, AppError> {
+    let added = tokio::task::spawn_blocking(move || reminders::export_action_items(&title, &items))
+        .await
+        .map_err(|e| format!("Adding reminders failed: {}", e))?
+        .map_err(|e| if e == reminders::ACCESS_DENIED { AppError::Permission(e) } else { AppError::Internal(e) })?;
+    info!("Added {} reminders", added);
+    Ok(added)
 }
 
-async fn ensure_database(app_handle: &tauri::AppHandle, state: &State<'_, AppState>) -> Result<(), String> {
-    let mut db_guard = state.database.lock().await;
-    if db_guard.is_none() {
-        let db_path = app_handle
-            .path_resolver()
-            .app_data_dir()
-            .unwrap_or_else(|| std::env::current_dir().unwrap())
-            .join("oatmeal.db");
+#[cfg(not(target_os = "macos"))]
+async fn add_reminders(_title: String, _items: Vec<export::ActionItem>) -> Result<usize, AppError> {
+    Err(AppError::InvalidInput("Exporting to Reminders is only available on macOS".to_string()))
+}
 
-        let database = Database::new(db_path.to_str().ok_or("Invalid DB path")?)
-            .await
-            .map_err(|e| format!("Failed to initialize database: {}", e))?;
-        *db_guard = Some(database);
+/// Replace the session's structured outputs, e.g. after the user edits an action item.
+#[tauri::command]
+async fn set_artifacts(session_id: String, artifacts: export::Artifacts, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let updated = database
+        .update_session_artifacts(&session_id, Some(&artifacts).filter(|a| !a.is_empty()))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update artifacts: {}", e)))?;
+    if !updated {
+        return Err(AppError::NotFound(format!("Session {} not found", session_id)));
     }
     Ok(())
 }
 
+/// Replace a saved transcript with the user's corrected text. It's redacted like a new
+/// transcript would be; the timed segments can't follow the edit and are dropped, so
+/// exports use the edited text. The unredacted copy becomes the edited text when
+/// `keep_raw_transcript` is on and is dropped otherwise, since the old one no longer matches.
 #[tauri::command]
-async fn get_settings(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Settings, String> {
+async fn edit_transcript(session_id: String, transcript: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
     ensure_database(&app_handle, &state).await?;
-
     let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let settings = database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+    let (transcript, _, raw) = redact::for_storage(&settings, &transcript, None);
+    let updated = database
+        .replace_session_transcript(&session_id, &transcript, raw.as_deref())
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update transcript: {}", e)))?;
+    if !updated {
+        return Err(AppError::NotFound(format!("Session {} not found", session_id)));
+    }
+    drop(db_guard);
+    spawn_embedding_index(app_handle, session_id);
+    Ok(())
+}
 
+/// Run PII redaction over an already saved session's transcript and segments. The raw
+/// copy is kept (or, if this is the first redaction, taken) only when `keep_raw_transcript`
+/// is on, and dropped otherwise. Summaries and artifacts are left as they are.
+#[tauri::command]
+async fn redact_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let settings = database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+    let session = database
+        .get_session(&session_id)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+    let transcript = session.transcript.unwrap_or_default();
+    let redacted = redact::redact(&transcript);
+    let raw = if settings.keep_raw_transcript {
+        Some(session.raw_transcript.unwrap_or(transcript))
+    } else {
+        None
+    };
     database
-        .get_settings()
+        .update_session_transcript(&session_id, &redacted)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to update transcript: {}", e)))?;
+    if let Some(segments) = session.segments.as_deref() {
+        database
+            .update_session_segments(&session_id, &redact::redact_segments_json(segments))
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to update segments: {}", e)))?;
+    }
+    database
+        .update_session_raw_transcript(&session_id, raw.as_deref())
         .await
-        .map_err(|e| format!("Failed to get settings: {}", e))
+        .map_err(|e| AppError::Database(format!("Failed to update raw transcript: {}", e)))?;
+    drop(db_guard);
+    spawn_embedding_index(app_handle, session_id);
+    Ok(redacted)
 }
 
+// Tells a finished summary apart from a newer one of the same session in `summary_cancels`
+static NEXT_SUMMARY_ID: AtomicU64 = AtomicU64::new(0);
+
 #[tauri::command]
-async fn update_settings(settings: Settings, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Settings, String> {
+async fn generate_summary(session_id: String, prompt: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Option<String>, AppError> {
     ensure_database(&app_handle, &state).await?;
 
-    let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
-    println!("Saving settings: chunk_seconds={}, engine={}, model={}, host={}", settings.chunk_seconds, settings.summary_engine, settings.ollama_model, settings.ollama_host);
+    let (session, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session, settings)
+    };
+    let transcript = session.transcript.clone().unwrap_or_default();
+
+    // "none" disables summaries entirely
+    let engine = match llm::engine_from_settings(&settings)? {
+        Some(engine) => engine,
+        None => return Ok(None),
+    };
+    if transcript.trim().is_empty() {
+        return Err(AppError::InvalidInput("Session has no transcript to summarize".to_string()));
+    }
+
+    // An explicit prompt wins over the configured template
+    let (prompt, content) = match prompt {
+        Some(prompt) => (prompt, transcript),
+        None => llm::summary_request(&settings, &session.title, session.duration, &transcript)?,
+    };
+    info!("Summarizing session {} with {} ({})", session_id, engine.name(), engine.model());
 
+    // Stream tokens to the UI as they arrive; cancel_summary drops the request mid-flight
+    let app_handle_tokens = app_handle.clone();
+    let session_id_tokens = session_id.clone();
+    let on_token = move |token: &str| {
+        let _ = app_handle_tokens.emit_all(
+            "summary:token",
+            serde_json::json!({ "session_id": session_id_tokens, "token": token }),
+        );
+    };
+    let fallback = llm::fallback_engine_from_settings(&settings);
+    let primary_name = engine.name();
+    let fallback_name = fallback.as_ref().map(|f| f.name());
+    let app_handle_fallback = app_handle.clone();
+    let session_id_fallback = session_id.clone();
+    let on_fallback = move |error: &str| {
+        let _ = app_handle_fallback.emit_all(
+            "summary:fallback",
+            serde_json::json!({ "session_id": session_id_fallback, "from": primary_name, "to": fallback_name, "error": error }),
+        );
+    };
+    // Registered before the request starts so a cancel that arrives early still counts
+    let summary_id = NEXT_SUMMARY_ID.fetch_add(1, Ordering::Relaxed);
+    let cancel = CancellationToken::new();
+    state.summary_cancels.lock().await.insert(session_id.clone(), (summary_id, cancel.clone()));
+    let result = tokio::select! {
+        result = llm::summarize_with_fallback(engine.as_ref(), fallback.as_deref(), &content, &prompt, &on_token, on_fallback) => Some(result),
+        _ = cancel.cancelled() => None,
+    };
+    {
+        // A newer summary of the same session may have taken the slot meanwhile
+        let mut cancels = state.summary_cancels.lock().await;
+        if cancels.get(&session_id).map(|(id, _)| *id) == Some(summary_id) {
+            cancels.remove(&session_id);
+        }
+    }
+    let (raw, used) = match result {
+        Some(result) => result.map_err(AppError::Network)?,
+        None => {
+            info!("Summary for session {} cancelled", session_id);
+            let _ = app_handle.emit_all(
+                "summary:done",
+                serde_json::json!({ "session_id": session_id, "cancelled": true }),
+            );
+            return Err(AppError::Cancelled("Summary cancelled".to_string()));
+        }
+    };
+    let summary = llm::clean_markdown(&raw);
+    let _ = app_handle.emit_all(
+        "summary:done",
+        serde_json::json!({ "session_id": session_id, "summary": summary, "engine": used.name(), "cancelled": false }),
+    );
+
+    let usage = llm::TokenUsage::of(used, &[&prompt, &content], &raw);
+
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    if let Err(e) = database.record_token_usage(Some(&session_id), used.name(), used.model(), &usage).await {
+        error!("Failed to record token usage: {}", e);
+    }
     database
-        .update_settings(&settings)
+        .update_session_summary(&session_id, &summary)
         .await
-        .map_err(|e| format!("Failed to update settings: {}", e))?;
-
-    // Return the persisted settings
-    let reloaded = database
-        .get_settings()
+        .map_err(|e| AppError::Database(format!("Failed to update session summary: {}", e)))?;
+    database
+        .update_session_summary_engine(&session_id, used.name(), used.model())
         .await
-        .map_err(|e| format!("Failed to reload settings: {}", e))?;
-    println!("Reloaded settings: chunk_seconds={}, engine={}, model={}, host={}", reloaded.chunk_seconds, reloaded.summary_engine, reloaded.ollama_model, reloaded.ollama_host);
-    Ok(reloaded)
+        .map_err(|e| AppError::Database(format!("Failed to update summary engine: {}", e)))?;
+    store_summary_artifacts(database, &session_id, &summary).await;
+    notify_webhook(database, "session.summarized", &session_id).await;
+    Ok(Some(summary))
 }
 
+/// Re-run summarization with the current engine, model and prompt template, replacing
+/// the stored summary and its engine metadata. Streams the same `summary:*` events as
+/// `generate_summary`.
 #[tauri::command]
-async fn initialize_transcriber(state: State<'_, AppState>) -> Result<(), String> {
-    let mut transcriber = state.transcriber.lock().await;
-    transcriber.initialize(Some("ggml-base.en.bin")).await
+async fn regenerate_summary(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    generate_summary(session_id, None, app_handle, state)
+        .await?
+        .ok_or_else(|| AppError::InvalidInput("Summary engine is set to 'none'".to_string()))
 }
 
-#[tauri::command]
-async fn download_whisper_model(model_name: String, state: State<'_, AppState>) -> Result<(), String> {
-    let mut transcriber = state.transcriber.lock().await;
-    transcriber.download_model_from_hf(&model_name).await
+#[derive(serde::Serialize)]
+struct UsageSummary {
+    input_tokens: i64,
+    output_tokens: i64,
+    /// Cloud engines only, priced with the per-1K settings; local models cost nothing
+    estimated_cost_usd: f64,
+    by_model: Vec<database::UsageTotal>,
 }
 
 #[tauri::command]
-async fn transcribe_audio(audio_frames: Vec<f32>, sample_rate: Option<u32>, state: State<'_, AppState>) -> Result<String, String> {
-    let mut transcriber = state.transcriber.lock().await;
-    if !transcriber.is_initialized() {
-        println!("Transcriber not initialized; attempting lazy initialization...");
-        // Try default selection; initialize() will search for an available model
-        match transcriber.initialize(None).await {
-            Ok(()) => println!("✅ Lazy initialization successful"),
-            Err(e) => {
-                eprintln!("❌ Lazy initialization failed: {}", e);
-                return Err(e);
-            }
-        }
-    }
-    let sr = sample_rate.unwrap_or(16_000);
-    transcriber.transcribe_audio_data(&audio_frames, sr).await
+async fn get_usage_summary(session_id: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<UsageSummary, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    let settings = database.get_settings().await.map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+    let by_model = database
+        .token_usage_totals(session_id.as_deref())
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to load token usage: {}", e)))?;
+    let estimated_cost_usd = by_model
+        .iter()
+        .filter(|t| t.engine != "ollama")
+        .map(|t| {
+            t.input_tokens as f64 / 1000.0 * settings.usage_price_input_per_1k
+                + t.output_tokens as f64 / 1000.0 * settings.usage_price_output_per_1k
+        })
+        .sum();
+    Ok(UsageSummary {
+        input_tokens: by_model.iter().map(|t| t.input_tokens).sum(),
+        output_tokens: by_model.iter().map(|t| t.output_tokens).sum(),
+        estimated_cost_usd,
+        by_model,
+    })
 }
 
 #[tauri::command]
-async fn save_session(title: String, duration: i32, transcript: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+async fn generate_chapters(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<llm::Chapter>, AppError> {
     ensure_database(&app_handle, &state).await?;
 
-    let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
+    let (session, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session, settings)
+    };
+    let engine = llm::engine_from_settings(&settings)?
+        .ok_or_else(|| AppError::InvalidInput("Summary engine is set to 'none'".to_string()))?;
+    let segments = export::parse_segments(&session);
+    let duration_ms = session.duration.max(0) as u64 * 1000;
+    let (chapters, usage) = llm::generate_chapters(engine.as_ref(), &segments, duration_ms).await?;
 
+    let json = serde_json::to_string(&chapters).map_err(|e| format!("Failed to encode chapters: {}", e))?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    if let Err(e) = database.record_token_usage(Some(&session_id), engine.name(), engine.model(), &usage).await {
+        error!("Failed to record token usage: {}", e);
+    }
     database
-        .save_session(&title, duration, &transcript)
+        .update_session_chapters(&session_id, &json)
         .await
-        .map_err(|e| format!("Failed to save session: {}", e))
+        .map_err(|e| AppError::Database(format!("Failed to save chapters: {}", e)))?;
+    Ok(chapters)
 }
 
+/// Find participant names in the transcript with the summary engine and remember them
+/// alongside the session's speaker labels.
 #[tauri::command]
-async fn get_session(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Option<SessionRecord>, String> {
+async fn extract_participants(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
     ensure_database(&app_handle, &state).await?;
 
-    let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
+    let (session, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session, settings)
+    };
+    let engine = llm::engine_from_settings(&settings)?
+        .ok_or_else(|| AppError::InvalidInput("Summary engine is set to 'none'".to_string()))?;
+    let transcript = session.transcript.unwrap_or_default();
+    let (names, usage) = llm::extract_participants(engine.as_ref(), &transcript).await?;
 
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    if let Err(e) = database.record_token_usage(Some(&session_id), engine.name(), engine.model(), &usage).await {
+        error!("Failed to record token usage: {}", e);
+    }
     database
-        .get_session(&session_id)
+        .replace_session_participants(&session_id, "llm", &names)
         .await
-        .map_err(|e| format!("Failed to get session: {}", e))
+        .map_err(|e| AppError::Database(format!("Failed to save participants: {}", e)))?;
+    Ok(names)
 }
 
+/// People seen across sessions with how many sessions each was in, most frequent first.
 #[tauri::command]
-async fn list_sessions(limit: Option<i32>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<SessionRecord>, String> {
+async fn list_participants(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<database::ParticipantSummary>, AppError> {
     ensure_database(&app_handle, &state).await?;
-
     let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
-
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
     database
-        .list_sessions(limit)
+        .list_participants()
         .await
-        .map_err(|e| format!("Failed to list sessions: {}", e))
+        .map_err(|e| AppError::Database(format!("Failed to list participants: {}", e)))
+}
+
+/// Extract action items, decisions and open questions from a new summary into the
+/// session's artifacts. Lists the summary has no section for keep their current entries.
+async fn store_summary_artifacts(database: &Database, session_id: &str, summary: &str) {
+    let mut artifacts = match database.get_session(session_id).await {
+        Ok(Some(session)) => session.artifacts.unwrap_or_default(),
+        _ => return,
+    };
+    artifacts.merge_summary(summary);
+    if artifacts.is_empty() {
+        return;
+    }
+    if let Err(e) = database.update_session_artifacts(session_id, Some(&artifacts)).await {
+        error!("Failed to save artifacts for {}: {}", session_id, e);
+    }
+}
+
+/// Post the session to `webhook_url` in the background, if one is configured.
+async fn notify_webhook(database: &Database, event: &str, session_id: &str) {
+    let url = match database.get_settings().await {
+        Ok(settings) if !settings.webhook_url.trim().is_empty() => settings.webhook_url,
+        _ => return,
+    };
+    if let Ok(Some(session)) = database.get_session(session_id).await {
+        webhook::notify(&url, event, &session);
+    }
 }
 
 #[tauri::command]
-async fn update_session_summary(session_id: String, summary: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+async fn ask_transcript(session_id: String, question: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    if question.trim().is_empty() {
+        return Err(AppError::InvalidInput("Question is empty".to_string()));
+    }
     ensure_database(&app_handle, &state).await?;
-    let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
-    database
-        .update_session_summary(&session_id, &summary)
-        .await
-        .map_err(|e| format!("Failed to update session summary: {}", e))
+
+    let (transcript, settings) = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        let session = database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+        let settings = database
+            .get_settings()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get settings: {}", e)))?;
+        (session.transcript.unwrap_or_default(), settings)
+    };
+    if transcript.trim().is_empty() {
+        return Err(AppError::InvalidInput("Session has no transcript".to_string()));
+    }
+
+    let engine = llm::engine_from_settings(&settings)?
+        .ok_or_else(|| AppError::InvalidInput("Summary engine is set to 'none'".to_string()))?;
+    llm::ask_transcript(engine.as_ref(), &transcript, &question).await.map_err(AppError::Network)
+}
+
+/// Stop the summary being generated for `session_id`. Returns false if none is running.
+#[tauri::command]
+async fn cancel_summary(session_id: String, state: State<'_, AppState>) -> Result<bool, AppError> {
+    match state.summary_cancels.lock().await.remove(&session_id) {
+        Some((_, cancel)) => {
+            cancel.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+async fn test_summary_engine(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<llm::EngineTestResult, AppError> {
+    let settings = get_settings(app_handle, state).await?;
+    llm::test_engine(&settings).await.map_err(AppError::Network)
+}
+
+#[tauri::command]
+async fn test_ollama_connection(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<llm::OllamaConnectionResult, AppError> {
+    let settings = get_settings(app_handle, state).await?;
+    Ok(llm::test_ollama_connection(&settings).await)
 }
 
 #[tauri::command]
-async fn create_folder(name: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+async fn create_folder(name: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
     ensure_database(&app_handle, &state).await?;
     let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
-    database.create_folder(&name).await.map_err(|e| format!("Failed to create folder: {}", e))
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database.create_folder(&name).await.map_err(|e| AppError::Database(format!("Failed to create folder: {}", e)))
 }
 
 #[tauri::command]
-async fn list_folders(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<database::FolderRecord>, String> {
+async fn list_folders(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Vec<database::FolderRecord>, AppError> {
     ensure_database(&app_handle, &state).await?;
     let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
-    database.list_folders().await.map_err(|e| format!("Failed to list folders: {}", e))
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database.list_folders().await.map_err(|e| AppError::Database(format!("Failed to list folders: {}", e)))
 }
 
 #[tauri::command]
-async fn assign_session_folder(session_id: String, folder_id: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+async fn assign_session_folder(session_id: String, folder_id: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
     ensure_database(&app_handle, &state).await?;
     let db_guard = state.database.lock().await;
-    let database = db_guard.as_ref().ok_or("Database not initialized")?;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
     let folder_id_ref = folder_id.as_deref();
-    database.assign_session_folder(&session_id, folder_id_ref).await.map_err(|e| format!("Failed to assign folder: {}", e))
+    database.assign_session_folder(&session_id, folder_id_ref).await.map_err(|e| AppError::Database(format!("Failed to assign folder: {}", e)))
+}
+
+/// Bulk version of `assign_session_folder`; ids that don't exist are skipped.
+#[tauri::command]
+async fn assign_sessions_folder(session_ids: Vec<String>, folder_id: Option<String>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<u64, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    if let Some(folder_id) = folder_id.as_deref() {
+        database
+            .get_folder(folder_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get folder: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Folder {} not found", folder_id)))?;
+    }
+    database
+        .assign_sessions_folder(&session_ids, folder_id.as_deref())
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to assign folder: {}", e)))
 }
 
 #[tauri::command]
-async fn get_env_var(name: String) -> Result<Option<String>, String> {
+async fn get_env_var(name: String) -> Result<Option<String>, AppError> {
     Ok(std::env::var(&name).ok())
 }
 
@@ -330,13 +2804,13 @@ async fn store_summary_preference(
     rating: i32,
     chosen: bool,
     feedback: Option<String>
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let db_guard = state.database.lock().await;
-    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    let db = db_guard.as_ref().ok_or_else(AppError::no_database)?;
     
     // For now we'll just log this since we'd need to implement the full database methods
     // In a full implementation, you'd add these methods to the Database struct
-    println!("Storing preference: session_id={}, variant_id={}, rating={}, chosen={}, feedback={:?}", 
+    debug!("Storing preference: session_id={}, variant_id={}, rating={}, chosen={}, feedback={:?}", 
              session_id, variant_id, rating, chosen, feedback);
     
     // Return a success ID
@@ -345,25 +2819,25 @@ async fn store_summary_preference(
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn check_microphone_permission() -> Result<String, String> {
-    permissions::check_microphone_permission()
+async fn check_microphone_permission() -> Result<String, AppError> {
+    permissions::check_microphone_permission().map_err(AppError::Permission)
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn request_microphone_permission() -> Result<bool, String> {
-    permissions::request_microphone_permission().await
+async fn request_microphone_permission() -> Result<bool, AppError> {
+    permissions::request_microphone_permission().await.map_err(AppError::Permission)
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn check_microphone_permission() -> Result<String, String> {
+async fn check_microphone_permission() -> Result<String, AppError> {
     Ok("granted".to_string())
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn request_microphone_permission() -> Result<bool, String> {
+async fn request_microphone_permission() -> Result<bool, AppError> {
     Ok(true)
 }
 
@@ -384,28 +2858,120 @@ fn main() {
             database: Arc::new(Mutex::new(None)),
             transcriber: Arc::new(Mutex::new(Transcriber::new())),
             recording_start_time: Arc::new(Mutex::new(None)),
+            summary_cancels: Arc::new(Mutex::new(HashMap::new())),
+            segments: Arc::new(Mutex::new(Vec::new())),
+            segment_origin: Arc::new(Mutex::new(SegmentOrigin::default())),
+            diarizer: Arc::new(Mutex::new(None)),
+            recording_audio_path: Arc::new(Mutex::new(None)),
+            recording_track2_path: Arc::new(Mutex::new(None)),
+            debug_audio: Arc::new(Mutex::new(None)),
+            api_server: Arc::new(Mutex::new(None)),
+            transcription_queue: Arc::new(transcription_queue::TranscriptionQueue::default()),
+            live_transcription: Arc::new(AtomicBool::new(true)),
+            pending_notes: Arc::new(Mutex::new(Vec::new())),
+            pending_input: Arc::new(Mutex::new(None)),
+            recording_state: Arc::new(recording::RecordingStatus::default()),
+            draft_session_id: Arc::new(Mutex::new(None)),
+            app_initialized: Arc::new(AtomicBool::new(false)),
+        })
+        .setup(|app| {
+            let app_handle = app.handle();
+            if let Err(e) = logging::init(&app_data_dir(&app_handle).join("logs")) {
+                eprintln!("{}", e);
+            }
+            transcribe::set_models_dir(app_data_dir(&app_handle).join("models"));
+            tauri::async_runtime::spawn(run_transcription_worker(app_handle));
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             initialize_app,
             start_recording,
             stop_recording,
+            discard_recording,
             is_recording,
+            pause_recording,
+            resume_recording,
+            get_recording_state,
+            list_recoverable_sessions,
+            recover_session,
+            discard_recoverable_session,
+            checkpoint_session,
+            get_live_transcript,
             get_recording_duration,
             create_quick_note,
+            list_notes,
+            mic_check,
+            diagnose_audio,
+            forget_input_device,
+            list_input_devices,
+            test_transcription,
             check_screen_capture_permission,
             open_screen_capture_settings,
             get_settings,
             update_settings,
+            get_summary_config,
+            set_summary_config,
+            set_log_level,
+            get_log_path,
+            get_api_token,
             update_session_summary,
+            update_session_notes,
+            get_artifacts,
+            extract_participants,
+            list_participants,
+            set_artifacts,
+            export_action_items_to_reminders,
+            edit_transcript,
+            generate_summary,
+            regenerate_summary,
+            cancel_summary,
+            ask_transcript,
+            test_summary_engine,
+            test_ollama_connection,
             initialize_transcriber,
+            warm_up_transcriber,
+            list_capturable_apps,
+            get_capabilities,
+            reveal_audio_in_file_manager,
+            list_available_models,
+            get_active_model,
+            set_active_model,
             download_whisper_model,
+            cancel_model_download,
+            get_model_status,
             transcribe_audio,
+            cancel_transcription,
             save_session,
+            generate_title,
             get_session,
+            get_session_stats,
+            get_overall_stats,
+            get_usage_summary,
+            semantic_search,
+            generate_chapters,
+            redact_session,
+            rebuild_semantic_index,
+            export_session_markdown,
+            export_session_json,
+            export_session_bundle,
+            get_shareable_summary,
+            export_all_sessions,
+            import_sessions,
+            create_hubspot_note,
+            create_followup_draft,
+            retranscribe_session,
             list_sessions,
+            reset_database,
+            merge_sessions,
+            set_session_favorite,
+            delete_session,
+            restore_session,
+            list_trashed_sessions,
+            empty_trash,
             create_folder,
             list_folders,
             assign_session_folder,
+            assign_sessions_folder,
             get_env_var,
             store_summary_preference,
             check_microphone_permission,
@@ -415,11 +2981,201 @@ fn main() {
         .expect("error while running tauri application");
 }
 
+// Long enough to say a sentence, short enough not to hang the UI
+const MAX_MIC_CHECK_SECONDS: f64 = 15.0;
+
+#[tauri::command]
+async fn mic_check(app_handle: tauri::AppHandle, state: State<'_, AppState>, seconds: f64) -> Result<audio::MicCheck, AppError> {
+    if state.recording_state.get() != recording::RecordingState::Idle {
+        return Err(AppError::InvalidInput("Can't run a mic check while recording".to_string()));
+    }
+    ensure_database(&app_handle, &state).await?;
+    let prefer_builtin_mic = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        database.get_settings().await.map(|s| s.prefer_builtin_mic).unwrap_or(false)
+    };
+    let duration = std::time::Duration::from_secs_f64(seconds.clamp(0.5, MAX_MIC_CHECK_SECONDS));
+    tokio::task::spawn_blocking(move || audio::mic_check(prefer_builtin_mic, duration))
+        .await
+        .map_err(|e| format!("Mic check failed: {}", e))?
+        .map_err(AppError::Audio)
+}
+
+/// Spoken clip `test_transcription` runs through Whisper; bundled via `bundle.resources`.
+const TRANSCRIPTION_SAMPLE: &str = "resources/transcription-sample.wav";
+
+/// Result of `test_transcription`.
+#[derive(serde::Serialize)]
+struct TranscriptionTest {
+    model: Option<String>,
+    text: String,
+    timing: Option<transcribe::ChunkTiming>,
+}
+
+/// Run a known clip through the live transcription path and report the text and timing,
+/// to tell a broken model apart from broken audio without touching the mic.
+#[tauri::command]
+async fn test_transcription(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<TranscriptionTest, AppError> {
+    if state.recording_state.get() != recording::RecordingState::Idle {
+        return Err(AppError::InvalidInput("Can't test transcription while recording".to_string()));
+    }
+    let path = app_handle
+        .path_resolver()
+        .resolve_resource(TRANSCRIPTION_SAMPLE)
+        .filter(|p| p.exists())
+        .ok_or_else(|| AppError::Internal("The transcription test clip is missing from the app bundle".to_string()))?;
+    let (samples, sample_rate) = tokio::task::spawn_blocking(move || audio::recorder::read_wav(&path))
+        .await
+        .map_err(|e| format!("Reading the sample failed: {}", e))??;
+
+    let preferred = preferred_model_file(&app_handle, &state).await?;
+    let mut transcriber = state.transcriber.lock().await;
+    if !transcriber.is_initialized() {
+        transcriber.initialize(Some(&preferred)).await.map_err(AppError::Transcription)?;
+    }
+    let text = transcriber
+        .transcribe_audio_data(&samples, sample_rate)
+        .await
+        .map_err(AppError::Transcription)?;
+    let timing = transcriber.take_timing();
+    info!("Test transcription: {:?} {:?}", text.trim(), timing);
+    // The clip is speech; a model that hears nothing in it isn't working
+    if text.trim().is_empty() {
+        return Err(AppError::Transcription(format!(
+            "{} returned no text for the test clip; the model may be broken",
+            transcriber.model_name().unwrap_or_else(|| "Whisper".to_string())
+        )));
+    }
+    Ok(TranscriptionTest {
+        model: transcriber.model_name(),
+        text: text.trim().to_string(),
+        timing,
+    })
+}
+
+/// Names of the connected input devices, for picking the inputs of a two-track recording.
+#[tauri::command]
+async fn list_input_devices() -> Result<Vec<String>, AppError> {
+    tokio::task::spawn_blocking(audio::input_device_names)
+        .await
+        .map_err(|e| AppError::Audio(format!("Failed to list input devices: {}", e)))
+}
+
+/// Drop the remembered input device so the next recording picks one from scratch.
+/// Returns whether anything was remembered.
+#[tauri::command]
+async fn forget_input_device(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<bool, AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let db_guard = state.database.lock().await;
+    let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+    database
+        .delete_meta(LAST_INPUT_KEY)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to forget input device: {}", e)))
+}
+
+/// Result of `diagnose_audio`. Each side has either its levels or the reason it failed;
+/// `system` and `system_error` are both empty where system audio isn't supported.
+#[derive(serde::Serialize)]
+struct AudioDiagnosis {
+    mic: Option<audio::MicCheck>,
+    mic_error: Option<String>,
+    system: Option<audio::SourceLevels>,
+    system_error: Option<String>,
+    system_supported: bool,
+}
+
+const DEFAULT_DIAGNOSE_SECONDS: f64 = 2.0;
+
+/// Pre-flight check: capture the mic and system audio at the same time for a couple of
+/// seconds and report levels for each. Nothing is emitted or saved, and both streams are
+/// stopped before returning.
+#[tauri::command]
+async fn diagnose_audio(seconds: Option<f64>, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<AudioDiagnosis, AppError> {
+    if state.recording_state.get() != recording::RecordingState::Idle {
+        return Err(AppError::InvalidInput("Can't run audio diagnostics while recording".to_string()));
+    }
+    ensure_database(&app_handle, &state).await?;
+    let settings = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        database.get_settings().await.unwrap_or_default()
+    };
+    let duration = std::time::Duration::from_secs_f64(seconds.unwrap_or(DEFAULT_DIAGNOSE_SECONDS).clamp(0.5, MAX_MIC_CHECK_SECONDS));
+    let system_supported = cfg!(target_os = "macos");
+
+    let (tx, rx) = crossbeam_channel::bounded::<Vec<f32>>(256);
+    let system = if system_supported {
+        let sink = audio::SampleSink::new(tx, Arc::default(), "system");
+        match sckit::macos::start_system_audio_capture(sink, system_capture_config(&settings)).await {
+            Ok(sample_rate) => Some(Ok(tokio::task::spawn_blocking(move || audio::system_audio_check(rx, sample_rate, duration)))),
+            Err(e) => Some(Err(e)),
+        }
+    } else {
+        None
+    };
+
+    let prefer_builtin_mic = settings.prefer_builtin_mic;
+    let mic = tokio::task::spawn_blocking(move || audio::mic_check(prefer_builtin_mic, duration))
+        .await
+        .map_err(|e| format!("Mic check failed: {}", e))
+        .and_then(|result| result);
+    let system = match system {
+        Some(Ok(task)) => {
+            let result = task.await.map_err(|e| format!("System audio check failed: {}", e)).and_then(|result| result);
+            let _ = sckit::macos::stop_system_audio_capture().await;
+            Some(result)
+        }
+        Some(Err(e)) => Some(Err(e)),
+        None => None,
+    };
+
+    Ok(AudioDiagnosis {
+        mic_error: mic.as_ref().err().cloned(),
+        mic: mic.ok(),
+        system_error: system.as_ref().and_then(|r| r.as_ref().err().cloned()),
+        system: system.and_then(Result::ok),
+        system_supported,
+    })
+}
+
+/// What this build can do on this OS, so the UI can hide options that would only fall back.
+#[derive(serde::Serialize)]
+struct Capabilities {
+    /// "macos", "windows", "linux", ...
+    os: String,
+    /// System audio via ScreenCaptureKit; elsewhere recordings are mic-only
+    system_audio: bool,
+    /// Capturing a single app's audio (`system_audio_app`)
+    per_app_audio: bool,
+    /// Whisper runs on the GPU (Metal)
+    gpu: bool,
+    /// Screen Recording permission has to be granted for system audio
+    screen_permission_needed: bool,
+    /// Microphone access goes through an OS permission prompt
+    mic_permission_needed: bool,
+}
+
+#[tauri::command]
+async fn get_capabilities() -> Result<Capabilities, AppError> {
+    let macos = cfg!(target_os = "macos");
+    Ok(Capabilities {
+        os: std::env::consts::OS.to_string(),
+        system_audio: macos,
+        per_app_audio: macos,
+        // whisper-rs is built with the `metal` feature, which only applies on macOS
+        gpu: macos,
+        screen_permission_needed: macos,
+        mic_permission_needed: macos,
+    })
+}
+
 #[tauri::command]
-async fn check_screen_capture_permission() -> Result<bool, String> {
+async fn check_screen_capture_permission() -> Result<bool, AppError> {
     #[cfg(target_os = "macos")]
     {
-        return sckit::macos::check_permission();
+        return sckit::macos::check_permission().map_err(AppError::Permission);
     }
     #[cfg(not(target_os = "macos"))]
     {
@@ -427,8 +3183,100 @@ async fn check_screen_capture_permission() -> Result<bool, String> {
     }
 }
 
+/// Show a session's saved recording selected in Finder / Explorer / the Linux file manager.
+#[tauri::command]
+async fn reveal_audio_in_file_manager(session_id: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    ensure_database(&app_handle, &state).await?;
+    let audio_path = {
+        let db_guard = state.database.lock().await;
+        let database = db_guard.as_ref().ok_or_else(AppError::no_database)?;
+        database
+            .get_session(&session_id)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to get session: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?
+            .audio_path
+            .ok_or_else(|| AppError::NotFound("No audio was saved for this session".to_string()))?
+    };
+    let path = std::path::PathBuf::from(&audio_path);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!("The audio file is missing: {}", audio_path)));
+    }
+    Ok(reveal_in_file_manager(&path)?)
+}
+
+fn reveal_in_file_manager(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let status = std::process::Command::new("open")
+            .arg("-R")
+            .arg(path)
+            .status()
+            .map_err(|e| format!("Failed to open Finder: {}", e))?;
+        if !status.success() {
+            return Err(format!("Failed to reveal {} in Finder ({})", path.display(), status));
+        }
+        Ok(())
+    }
+    #[cfg(target_os = "windows")]
+    {
+        // Explorer wants "/select,<path>" as a single argument. It exits with 1 even when
+        // it shows the file, so only failing to start it is an error.
+        std::process::Command::new("explorer")
+            .arg(format!("/select,{}", path.display()))
+            .status()
+            .map_err(|e| format!("Failed to open Explorer: {}", e))?;
+        Ok(())
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        // Most desktop file managers implement FileManager1 and select the file; otherwise
+        // just open the containing folder
+        let uri = file_uri(path);
+        let selected = std::process::Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(format!("array:string:{}", uri))
+            .arg("string:")
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+        if !selected {
+            let dir = path.parent().unwrap_or(path);
+            let status = std::process::Command::new("xdg-open")
+                .arg(dir)
+                .status()
+                .map_err(|e| format!("Failed to open file manager: {}", e))?;
+            if !status.success() {
+                return Err(format!("Failed to open {} in the file manager ({})", dir.display(), status));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `file://` URI for an absolute path, percent-encoding everything but unreserved
+/// characters and separators so spaces, `#` and non-UTF-8 names survive.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn file_uri(path: &std::path::Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
 #[tauri::command]
-async fn open_screen_capture_settings() -> Result<(), String> {
+async fn open_screen_capture_settings() -> Result<(), AppError> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
@@ -439,6 +3287,40 @@ async fn open_screen_capture_settings() -> Result<(), String> {
     }
     #[cfg(not(target_os = "macos"))]
     {
-        Err("Not supported on this OS".to_string())
+        Err(AppError::InvalidInput("Not supported on this OS".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcription_sample() -> (Vec<f32>, u32) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(TRANSCRIPTION_SAMPLE);
+        audio::recorder::read_wav(&path).expect("transcription sample should be readable")
+    }
+
+    #[test]
+    fn transcription_sample_is_bundled_audio() {
+        let (samples, sample_rate) = transcription_sample();
+        assert!(samples.len() as u32 >= sample_rate, "sample should be at least a second long");
+        assert!(!transcribe::is_silent(&samples));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a downloaded Whisper model"]
+    async fn transcription_sample_transcribes_to_text() {
+        let (samples, sample_rate) = transcription_sample();
+        let mut transcriber = Transcriber::new();
+        transcriber.initialize(None).await.expect("a Whisper model should be available");
+        let text = transcriber.transcribe_audio_data(&samples, sample_rate).await.expect("transcription should succeed");
+        assert!(!text.trim().is_empty(), "model returned no text for the sample");
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[test]
+    fn file_uri_percent_encodes_reserved_characters() {
+        let uri = file_uri(std::path::Path::new("/home/me/Oatmeal exports/Q&A #2 – café.zip"));
+        assert_eq!(uri, "file:///home/me/Oatmeal%20exports/Q%26A%20%232%20%E2%80%93%20caf%C3%A9.zip");
     }
 }
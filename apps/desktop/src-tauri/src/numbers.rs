@@ -0,0 +1,306 @@
+//! Rewrites spelled-out English numbers in transcript text as digits: "three hundred
+//! dollars" -> "$300", "twenty twenty five" -> "2025", "four point five percent" -> "4.5%".
+//! Single words below ten are left alone unless a unit follows, since "one on one" or
+//! "a one-off" read worse as digits.
+
+#[derive(Clone, Copy, PartialEq)]
+enum Word {
+    Unit(u64),
+    Teen(u64),
+    Tens(u64),
+    /// Hyphenated "twenty-five"
+    TensUnit(u64),
+    Scale(u64),
+    And,
+    Point,
+}
+
+impl Word {
+    fn is_number(self) -> bool {
+        matches!(self, Word::Unit(_) | Word::Teen(_) | Word::Tens(_) | Word::TensUnit(_))
+    }
+}
+
+const UNITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const TEENS: [&str; 10] = [
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+fn position(list: &[&str], word: &str) -> Option<u64> {
+    list.iter().position(|w| *w == word).map(|i| i as u64)
+}
+
+fn classify(word: &str) -> Option<Word> {
+    if let Some((tens, unit)) = word.split_once('-') {
+        let tens = position(&TENS, tens)?;
+        let unit = position(&UNITS, unit).filter(|u| *u > 0)?;
+        return Some(Word::TensUnit((tens + 2) * 10 + unit));
+    }
+    if let Some(n) = position(&UNITS, word) {
+        return Some(Word::Unit(n));
+    }
+    if let Some(n) = position(&TEENS, word) {
+        return Some(Word::Teen(n + 10));
+    }
+    if let Some(n) = position(&TENS, word) {
+        return Some(Word::Tens((n + 2) * 10));
+    }
+    match word {
+        "hundred" => Some(Word::Scale(100)),
+        "thousand" => Some(Word::Scale(1_000)),
+        "million" => Some(Word::Scale(1_000_000)),
+        "billion" => Some(Word::Scale(1_000_000_000)),
+        "and" => Some(Word::And),
+        "point" => Some(Word::Point),
+        _ => None,
+    }
+}
+
+/// A whitespace-separated word split into leading punctuation, the word, and trailing
+/// punctuation.
+struct Token<'a> {
+    lead: &'a str,
+    core: &'a str,
+    trail: &'a str,
+    word: Option<Word>,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    text.split_whitespace()
+        .map(|raw| {
+            let start = raw.find(|c: char| c.is_alphanumeric()).unwrap_or(raw.len());
+            let end = raw
+                .char_indices()
+                .filter(|(_, c)| c.is_alphanumeric())
+                .last()
+                .map(|(i, c)| i + c.len_utf8())
+                .unwrap_or(start);
+            let core = &raw[start..end.max(start)];
+            Token {
+                lead: &raw[..start],
+                core,
+                trail: &raw[end.max(start)..],
+                word: classify(&core.to_lowercase()),
+            }
+        })
+        .collect()
+}
+
+struct Parsed {
+    value: u64,
+    decimals: String,
+    /// Tokens consumed
+    len: usize,
+    /// Whether "hundred", "thousand", ... was part of it
+    scaled: bool,
+}
+
+/// The longest well-formed number starting at `start`. Punctuation ends a number.
+fn parse_at(tokens: &[Token], start: usize) -> Option<Parsed> {
+    let (mut total, mut current) = (0u64, 0u64);
+    let mut last_big = u64::MAX;
+    let mut scaled = false;
+    let mut prev: Option<Word> = None;
+    let mut decimals = String::new();
+    let mut i = start;
+    while let Some(token) = tokens.get(i) {
+        if i > start && !token.lead.is_empty() {
+            break;
+        }
+        let word = match token.word {
+            Some(word) => word,
+            None => break,
+        };
+        let next_is_number = tokens
+            .get(i + 1)
+            .map(|t| t.lead.is_empty() && t.word.map_or(false, Word::is_number))
+            .unwrap_or(false);
+        let ok = match (prev, word) {
+            (None, w) => w.is_number(),
+            (Some(Word::Tens(_)), Word::Unit(n)) => n > 0,
+            (Some(Word::Scale(_)), w) | (Some(Word::And), w) if w.is_number() => true,
+            (Some(Word::Scale(_)), Word::And) => next_is_number && token.trail.is_empty(),
+            (Some(p), Word::Scale(s)) if p != Word::And => {
+                if s == 100 {
+                    (1..100).contains(&current)
+                } else {
+                    current > 0 && s < last_big
+                }
+            }
+            (Some(p), Word::Point) if p != Word::And => {
+                if !token.trail.is_empty() {
+                    break;
+                }
+                let mut j = i + 1;
+                while let Some(Token { lead: "", word: Some(Word::Unit(d)), trail, .. }) = tokens.get(j) {
+                    decimals.push_str(&d.to_string());
+                    j += 1;
+                    if !trail.is_empty() {
+                        break;
+                    }
+                }
+                if !decimals.is_empty() {
+                    i = j;
+                }
+                break;
+            }
+            _ => false,
+        };
+        if !ok {
+            break;
+        }
+        match word {
+            Word::Unit(n) | Word::Teen(n) | Word::Tens(n) | Word::TensUnit(n) => current += n,
+            Word::Scale(100) => {
+                current *= 100;
+                scaled = true;
+            }
+            Word::Scale(s) => {
+                scaled = true;
+                total += current * s;
+                current = 0;
+                last_big = s;
+            }
+            _ => {}
+        }
+        prev = Some(word);
+        i += 1;
+        if !token.trail.is_empty() {
+            break;
+        }
+    }
+    if i == start {
+        return None;
+    }
+    Some(Parsed {
+        value: total + current,
+        decimals,
+        len: i - start,
+        scaled,
+    })
+}
+
+fn with_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// `text` with spelled-out numbers, money and percentages written as digits.
+pub fn normalize_numbers(text: &str) -> String {
+    let tokens = tokenize(text);
+    let mut out: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut parsed = match parse_at(&tokens, i) {
+            Some(parsed) => parsed,
+            None => {
+                let t = &tokens[i];
+                out.push(format!("{}{}{}", t.lead, t.core, t.trail));
+                i += 1;
+                continue;
+            }
+        };
+        let mut end = i + parsed.len;
+
+        // Years read as two pairs: "nineteen eighty four", "twenty twenty five"
+        let mut year = false;
+        if parsed.len == 1 && matches!(parsed.value, 19 | 20) && tokens[i].trail.is_empty() {
+            if let Some(second) = parse_at(&tokens, end) {
+                if second.len <= 2 && second.decimals.is_empty() && !second.scaled && (10..100).contains(&second.value) {
+                    parsed.value = parsed.value * 100 + second.value;
+                    end += second.len;
+                    year = true;
+                }
+            }
+        }
+
+        let unit = tokens
+            .get(end)
+            .filter(|t| t.lead.is_empty() && tokens[end - 1].trail.is_empty())
+            .map(|t| t.core.to_lowercase());
+        let (prefix, suffix) = match unit.as_deref() {
+            Some("dollars" | "dollar" | "bucks") => ("$", ""),
+            Some("percent") => ("", "%"),
+            _ => ("", ""),
+        };
+        let has_unit = !prefix.is_empty() || !suffix.is_empty();
+
+        if !has_unit && end - i == 1 && parsed.value < 10 {
+            let t = &tokens[i];
+            out.push(format!("{}{}{}", t.lead, t.core, t.trail));
+            i += 1;
+            continue;
+        }
+
+        let mut number = if year { parsed.value.to_string() } else { with_thousands(parsed.value) };
+        if !parsed.decimals.is_empty() {
+            number.push('.');
+            number.push_str(&parsed.decimals);
+        }
+        let last = if has_unit { end } else { end - 1 };
+        out.push(format!("{}{}{}{}{}", tokens[i].lead, prefix, number, suffix, tokens[last].trail));
+        i = last + 1;
+    }
+    out.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_numbers;
+
+    fn check(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(normalize_numbers(input), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn compound_numbers() {
+        check(&[
+            ("we hired twenty-five people", "we hired 25 people"),
+            ("about forty two of them", "about 42 of them"),
+            ("three hundred and twelve tickets", "312 tickets"),
+            ("two thousand five hundred users", "2,500 users"),
+            ("one million two hundred thousand rows", "1,200,000 rows"),
+            ("it costs three hundred dollars", "it costs $300"),
+            ("growth was four point five percent", "growth was 4.5%"),
+            ("seven percent churn", "7% churn"),
+            ("back in nineteen eighty four", "back in 1984"),
+            ("ship it in twenty twenty five.", "ship it in 2025."),
+            ("We need twelve, maybe fifteen", "We need 12, maybe 15"),
+        ]);
+    }
+
+    #[test]
+    fn ordinals_are_left_as_words() {
+        check(&[
+            ("the third quarter", "the third quarter"),
+            ("on the twenty-first of May", "on the twenty-first of May"),
+            ("our first hundredth customer", "our first hundredth customer"),
+        ]);
+    }
+
+    #[test]
+    fn words_that_are_not_counts_stay() {
+        check(&[
+            ("one of them said no", "one of them said no"),
+            ("someone will follow up", "someone will follow up"),
+            ("let's do a one on one", "let's do a one on one"),
+            ("it was a one-off", "it was a one-off"),
+            ("nine times out of ten", "nine times out of 10"),
+            ("ninety-nine problems", "99 problems"),
+            ("and then", "and then"),
+            ("a hundred things", "a hundred things"),
+            ("the point is", "the point is"),
+            ("eleven and", "11 and"),
+        ]);
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::Manager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingState {
+    Idle,
+    Starting,
+    Recording,
+    Paused,
+    Stopping,
+}
+
+/// The recorder's current state. Every change is emitted as `recording:state` so the UI
+/// follows the backend instead of guessing from `is_recording`.
+pub struct RecordingStatus {
+    state: Mutex<RecordingState>,
+}
+
+impl Default for RecordingStatus {
+    fn default() -> Self {
+        Self { state: Mutex::new(RecordingState::Idle) }
+    }
+}
+
+impl RecordingStatus {
+    pub fn get(&self) -> RecordingState {
+        *self.state.lock().unwrap()
+    }
+
+    /// Move to `next` if the current state is one of `from`; returns the previous state.
+    pub fn transition(
+        &self,
+        app_handle: &tauri::AppHandle,
+        from: &[RecordingState],
+        next: RecordingState,
+    ) -> Result<RecordingState, String> {
+        let mut state = self.state.lock().unwrap();
+        let previous = *state;
+        if !from.contains(&previous) {
+            return Err(format!("Can't go from {:?} to {:?}", previous, next));
+        }
+        *state = next;
+        drop(state);
+        if previous != next {
+            let _ = app_handle.emit_all("recording:state", serde_json::json!({
+                "state": next,
+                "previous": previous,
+            }));
+        }
+        Ok(previous)
+    }
+
+    /// Move to `next` unconditionally (used to unwind after a failure).
+    pub fn set(&self, app_handle: &tauri::AppHandle, next: RecordingState) {
+        let all = [
+            RecordingState::Idle,
+            RecordingState::Starting,
+            RecordingState::Recording,
+            RecordingState::Paused,
+            RecordingState::Stopping,
+        ];
+        let _ = self.transition(app_handle, &all, next);
+    }
+}
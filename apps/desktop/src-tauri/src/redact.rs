@@ -0,0 +1,146 @@
+//! Optional PII redaction for stored transcripts. Emails, phone numbers and
+//! credit-card-like digit runs are replaced with `[REDACTED]` before a transcript is
+//! written to the database. Pattern-based, so it errs on the side of redacting too much
+//! (any long digit run goes); it's not a substitute for reviewing sensitive recordings.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::database::Settings;
+use crate::transcribe::TranscriptSegment;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+// Applied in order: card numbers before phone numbers, since a phone pattern can match
+// the tail of a longer digit run
+static PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        // Emails
+        r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b",
+        // 13-19 digits, optionally grouped with spaces or dashes
+        r"\b(?:\d[ -]?){12,18}\d\b",
+        // Phone numbers: optional country code, (555) 123-4567 / 555.123.4567 / 5551234567
+        r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{3}\)\s?|\b\d{3}[\s.-]?)\d{3}[\s.-]?\d{4}\b",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).expect("valid redaction pattern"))
+    .collect()
+});
+
+/// `text` with every match replaced by `[REDACTED]`.
+pub fn redact(text: &str) -> String {
+    PATTERNS
+        .iter()
+        .fold(text.to_string(), |acc, re| re.replace_all(&acc, REDACTED).into_owned())
+}
+
+pub fn redact_segments(segments: &mut [TranscriptSegment]) {
+    for segment in segments {
+        segment.text = redact(&segment.text);
+    }
+}
+
+/// Redact a segments JSON column; returns it unchanged if it doesn't parse.
+pub fn redact_segments_json(json: &str) -> String {
+    match serde_json::from_str::<Vec<TranscriptSegment>>(json) {
+        Ok(mut segments) => {
+            redact_segments(&mut segments);
+            serde_json::to_string(&segments).unwrap_or_else(|_| json.to_string())
+        }
+        Err(_) => json.to_string(),
+    }
+}
+
+/// Apply the redaction settings to a freshly transcribed `transcript` and its segments
+/// JSON. Returns what to store: the transcript, the segments, and the unredacted
+/// transcript when `keep_raw_transcript` is on.
+pub fn for_storage(settings: &Settings, transcript: &str, segments: Option<&str>) -> (String, Option<String>, Option<String>) {
+    if !settings.redact_transcript {
+        return (transcript.to_string(), segments.map(str::to_string), None);
+    }
+    let raw = settings.keep_raw_transcript.then(|| transcript.to_string());
+    (redact(transcript), segments.map(redact_segments_json), raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redacted(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(redact(input), *expected, "input: {:?}", input);
+        }
+    }
+
+    fn untouched(cases: &[&str]) {
+        for input in cases {
+            assert_eq!(redact(input), *input, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn emails() {
+        redacted(&[
+            ("mail jane.doe@example.com today", "mail [REDACTED] today"),
+            ("Ops+alerts@Mail.Example.co.uk.", "[REDACTED]."),
+        ]);
+        untouched(&["meet @ noon", "ping @jane on slack", "user@localhost", "a@b.c"]);
+    }
+
+    #[test]
+    fn card_numbers() {
+        redacted(&[
+            ("card 4111111111111111 expires", "card [REDACTED] expires"),
+            ("4111 1111 1111 1111", "[REDACTED]"),
+            ("3782-822463-10005", "[REDACTED]"),
+        ]);
+        // Under 13 digits is left to the phone pattern, or alone
+        untouched(&["order 123456 shipped", "in 2024 we grew 35%", "version 1.2.3"]);
+    }
+
+    #[test]
+    fn phone_numbers() {
+        redacted(&[
+            ("call (555) 123-4567 now", "call [REDACTED] now"),
+            ("555.123.4567", "[REDACTED]"),
+            ("5551234567", "[REDACTED]"),
+            ("+1 555-123-4567", "[REDACTED]"),
+            ("+44 207 123 4567", "[REDACTED]"),
+        ]);
+        untouched(&["123-4567", "room 555 on floor 12", "12:30 to 14:45", "ext 4567"]);
+    }
+
+    #[test]
+    fn plain_text_is_untouched() {
+        untouched(&["", "Let's sync on the Q3 roadmap next week.", "twenty five people"]);
+    }
+
+    #[test]
+    fn segments_json_is_redacted_per_segment() {
+        let json = r#"[{"start_ms":0,"end_ms":900,"text":"reach me at bob@example.com","source":"mic","speaker":"me"},{"start_ms":900,"end_ms":2000,"text":"thanks","source":null,"speaker":null}]"#;
+        let segments: Vec<TranscriptSegment> = serde_json::from_str(&redact_segments_json(json)).unwrap();
+        assert_eq!(segments[0].text, "reach me at [REDACTED]");
+        assert_eq!(segments[0].speaker.as_deref(), Some("me"));
+        assert_eq!(segments[1].text, "thanks");
+
+        assert_eq!(redact_segments_json("not json 555-123-4567"), "not json 555-123-4567");
+    }
+
+    #[test]
+    fn for_storage_follows_settings() {
+        let transcript = "call 555-123-4567";
+        let mut settings = Settings { redact_transcript: false, ..Settings::default() };
+        assert_eq!(for_storage(&settings, transcript, None), (transcript.to_string(), None, None));
+
+        settings.redact_transcript = true;
+        settings.keep_raw_transcript = false;
+        let (stored, segments, raw) = for_storage(&settings, transcript, Some("[]"));
+        assert_eq!(stored, "call [REDACTED]");
+        assert_eq!(segments.as_deref(), Some("[]"));
+        assert_eq!(raw, None);
+
+        settings.keep_raw_transcript = true;
+        let (_, _, raw) = for_storage(&settings, transcript, None);
+        assert_eq!(raw.as_deref(), Some(transcript));
+    }
+}
@@ -0,0 +1,148 @@
+//! Push a session's action items into the Reminders app through EventKit.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use block::ConcreteBlock;
+use cocoa::base::{id, nil, BOOL, NO};
+use cocoa::foundation::{NSAutoreleasePool, NSString};
+use objc::runtime::Class;
+use objc::{msg_send, sel, sel_impl};
+
+use crate::export::ActionItem;
+
+// EventKit isn't linked by anything else; the classes below only exist once it is
+#[link(name = "EventKit", kind = "framework")]
+extern "C" {}
+
+// EKEntityType / EKAuthorizationStatus
+const EK_ENTITY_TYPE_REMINDER: u64 = 1;
+const EK_AUTHORIZATION_NOT_DETERMINED: i64 = 0;
+const EK_AUTHORIZATION_AUTHORIZED: i64 = 3; // EKAuthorizationStatusFullAccess on macOS 14+
+
+// The user answers the prompt at their own pace
+const PERMISSION_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub const ACCESS_DENIED: &str =
+    "Oatmeal doesn't have access to Reminders. Allow it in System Settings > Privacy & Security > Reminders.";
+
+fn class(name: &str) -> Result<&'static Class, String> {
+    Class::get(name).ok_or_else(|| format!("{} class not found", name))
+}
+
+unsafe fn ns_string(s: &str) -> id {
+    NSString::alloc(nil).init_str(s)
+}
+
+unsafe fn error_description(error: id) -> String {
+    if error == nil {
+        return "unknown error".to_string();
+    }
+    let description: id = msg_send![error, localizedDescription];
+    let utf8: *const std::os::raw::c_char = msg_send![description, UTF8String];
+    if utf8.is_null() {
+        return "unknown error".to_string();
+    }
+    std::ffi::CStr::from_ptr(utf8).to_string_lossy().into_owned()
+}
+
+/// Ask for Reminders access if it hasn't been decided yet. Blocks until the user answers.
+unsafe fn ensure_access(store: id) -> Result<(), String> {
+    let store_class = class("EKEventStore")?;
+    let status: i64 = msg_send![store_class, authorizationStatusForEntityType: EK_ENTITY_TYPE_REMINDER];
+    if status == EK_AUTHORIZATION_AUTHORIZED {
+        return Ok(());
+    }
+    if status != EK_AUTHORIZATION_NOT_DETERMINED {
+        return Err(ACCESS_DENIED.to_string());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let completion = ConcreteBlock::new(move |granted: BOOL, _error: id| {
+        let _ = tx.send(granted != NO);
+    })
+    .copy();
+    // macOS 14 split the old request into full and write-only access
+    let full_access = sel!(requestFullAccessToRemindersWithCompletion:);
+    let responds: BOOL = msg_send![store, respondsToSelector: full_access];
+    if responds != NO {
+        let _: () = msg_send![store, requestFullAccessToRemindersWithCompletion: &*completion];
+    } else {
+        let _: () = msg_send![store, requestAccessToEntityType: EK_ENTITY_TYPE_REMINDER completion: &*completion];
+    }
+    match rx.recv_timeout(PERMISSION_TIMEOUT) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ACCESS_DENIED.to_string()),
+        Err(_) => Err("Timed out waiting for Reminders permission".to_string()),
+    }
+}
+
+/// A due date Reminders can use: only plain `YYYY-MM-DD`; anything vaguer goes in the notes.
+fn due_date(due: &str) -> Option<(isize, isize, isize)> {
+    let mut parts = due.trim().splitn(3, '-').map(|p| p.parse::<isize>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if year >= 1970 && (1..=12).contains(&month) && (1..=31).contains(&day) {
+        Some((year, month, day))
+    } else {
+        None
+    }
+}
+
+/// Create one reminder per action item in the default Reminders list, noting the meeting
+/// they came from. Returns how many were created. Blocking: call from `spawn_blocking`.
+pub fn export_action_items(meeting_title: &str, items: &[ActionItem]) -> Result<usize, String> {
+    unsafe {
+        let _pool = NSAutoreleasePool::new(nil);
+
+        let store_class = class("EKEventStore")?;
+        let store: id = msg_send![store_class, new];
+        if store == nil {
+            return Err("Failed to create EKEventStore".to_string());
+        }
+        ensure_access(store)?;
+
+        let calendar: id = msg_send![store, defaultCalendarForNewReminders];
+        if calendar == nil {
+            return Err("There's no default Reminders list to add to".to_string());
+        }
+
+        let reminder_class = class("EKReminder")?;
+        let components_class = class("NSDateComponents")?;
+        for item in items {
+            let reminder: id = msg_send![reminder_class, reminderWithEventStore: store];
+            let _: () = msg_send![reminder, setTitle: ns_string(&item.text)];
+            let _: () = msg_send![reminder, setCalendar: calendar];
+
+            let mut notes = format!("From \"{}\"", meeting_title);
+            if let Some(owner) = item.owner.as_deref().filter(|o| !o.trim().is_empty()) {
+                notes.push_str(&format!("\nOwner: {}", owner.trim()));
+            }
+            if let Some(due) = item.due.as_deref().filter(|d| !d.trim().is_empty()) {
+                match due_date(due) {
+                    Some((year, month, day)) => {
+                        let components: id = msg_send![components_class, new];
+                        let _: () = msg_send![components, setYear: year];
+                        let _: () = msg_send![components, setMonth: month];
+                        let _: () = msg_send![components, setDay: day];
+                        let _: () = msg_send![reminder, setDueDateComponents: components];
+                    }
+                    None => notes.push_str(&format!("\nDue: {}", due.trim())),
+                }
+            }
+            let _: () = msg_send![reminder, setNotes: ns_string(&notes)];
+
+            let mut error: id = nil;
+            let saved: BOOL = msg_send![store, saveReminder: reminder commit: NO error: &mut error];
+            if saved == NO {
+                return Err(format!("Failed to add reminder \"{}\": {}", item.text, error_description(error)));
+            }
+        }
+
+        let mut error: id = nil;
+        let committed: BOOL = msg_send![store, commit: &mut error];
+        if committed == NO {
+            return Err(format!("Failed to save reminders: {}", error_description(error)));
+        }
+        Ok(items.len())
+    }
+}
@@ -1,10 +1,40 @@
+use serde::Serialize;
+
+/// Whisper's rate; capturing at it directly skips most of the resampling
+pub const DEFAULT_SAMPLE_RATE: u32 = 16_000;
+// Always accepted by ScreenCaptureKit; used when the configured rate is rejected
+pub const FALLBACK_SAMPLE_RATE: u32 = 48_000;
+
+/// How to set up the ScreenCaptureKit audio stream, from settings.
+#[derive(Debug, Clone)]
+pub struct SystemCaptureConfig {
+    /// Capture only this application's audio; `None` captures everything
+    pub app_bundle_id: Option<String>,
+    pub sample_rate: u32,
+    pub channels: u32,
+}
+
+impl Default for SystemCaptureConfig {
+    fn default() -> Self {
+        Self { app_bundle_id: None, sample_rate: DEFAULT_SAMPLE_RATE, channels: 1 }
+    }
+}
+
+/// An application whose audio can be captured on its own.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturableApp {
+    pub bundle_id: String,
+    pub name: String,
+    pub pid: i32,
+}
+
 #[cfg(target_os = "macos")]
 pub mod macos {
-    use tauri::Manager;
     use once_cell::sync::Lazy;
+    use log::{error, warn};
     use core_media_rs::cm_sample_buffer::CMSampleBuffer;
     use screencapturekit::{
-        shareable_content::SCShareableContent,
+        shareable_content::{SCRunningApplication, SCShareableContent},
         stream::{
             configuration::SCStreamConfiguration,
             content_filter::SCContentFilter,
@@ -15,107 +45,266 @@ pub mod macos {
     };
     use std::sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Mutex,
     };
-    use crossbeam_channel as channel;
+    use crate::audio::resample::StreamResampler;
+    use crate::audio::SampleSink;
+    use super::{CapturableApp, SystemCaptureConfig, FALLBACK_SAMPLE_RATE};
 
     static STREAM_HOLDER: Lazy<Mutex<Option<SCStream>>> = Lazy::new(|| Mutex::new(None));
     static RUNNING: AtomicBool = AtomicBool::new(false);
 
+    // AudioStreamBasicDescription constants (CoreAudioTypes.h)
+    const FORMAT_LINEAR_PCM: u32 = u32::from_be_bytes(*b"lpcm");
+    const FLAG_IS_FLOAT: u32 = 1 << 0;
+    const FLAG_IS_BIG_ENDIAN: u32 = 1 << 1;
+    const FLAG_IS_NON_INTERLEAVED: u32 = 1 << 5;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum SampleFormat {
+        F32,
+        I16,
+        I32,
+    }
+
+    /// Layout of the PCM in a sample buffer, from its stream description.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct PcmLayout {
+        format: SampleFormat,
+        big_endian: bool,
+        interleaved: bool,
+        channels: usize,
+        /// `None` when the description doesn't say; the negotiated rate is assumed
+        sample_rate: Option<u32>,
+    }
+
+    /// What the stream was configured for (f32 with the negotiated channel count); assumed
+    /// when a buffer has no readable format description
+    fn configured_layout(channels: u32) -> PcmLayout {
+        PcmLayout { format: SampleFormat::F32, big_endian: false, interleaved: true, channels: channels.max(1) as usize, sample_rate: None }
+    }
+
+    fn pcm_layout(sample: &CMSampleBuffer, configured: PcmLayout) -> Result<PcmLayout, String> {
+        let description = match sample.get_format_description() {
+            Ok(description) => description,
+            Err(_) => return Ok(configured),
+        };
+        let asbd = match description.audio_format_description_get_stream_basic_description() {
+            Some(asbd) => asbd,
+            None => return Ok(configured),
+        };
+        let flags = asbd.format_flags;
+        let format = match (asbd.format_id == FORMAT_LINEAR_PCM, flags & FLAG_IS_FLOAT != 0, asbd.bits_per_channel) {
+            (true, true, 32) => SampleFormat::F32,
+            (true, false, 16) => SampleFormat::I16,
+            (true, false, 32) => SampleFormat::I32,
+            _ => {
+                return Err(format!(
+                    "unsupported format '{}' ({} bits, flags {:#x})",
+                    String::from_utf8_lossy(&asbd.format_id.to_be_bytes()),
+                    asbd.bits_per_channel,
+                    flags
+                ))
+            }
+        };
+        Ok(PcmLayout {
+            format,
+            big_endian: flags & FLAG_IS_BIG_ENDIAN != 0,
+            interleaved: flags & FLAG_IS_NON_INTERLEAVED == 0,
+            channels: asbd.channels_per_frame.max(1) as usize,
+            sample_rate: Some(asbd.sample_rate.round() as u32).filter(|&rate| rate > 0),
+        })
+    }
+
+    fn decode(bytes: &[u8], layout: PcmLayout) -> Vec<f32> {
+        let width = match layout.format {
+            SampleFormat::I16 => 2,
+            SampleFormat::F32 | SampleFormat::I32 => 4,
+        };
+        bytes
+            .chunks_exact(width)
+            .map(|b| match (layout.format, layout.big_endian) {
+                (SampleFormat::F32, false) => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                (SampleFormat::F32, true) => f32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+                (SampleFormat::I16, false) => i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+                (SampleFormat::I16, true) => i16::from_be_bytes([b[0], b[1]]) as f32 / 32768.0,
+                (SampleFormat::I32, false) => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+                (SampleFormat::I32, true) => i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+            })
+            .map(|v| if v.is_finite() { v } else { 0.0 })
+            .collect()
+    }
+
+    /// Average `channels` interleaved channels down to mono.
+    fn downmix(samples: &[f32], channels: usize) -> Vec<f32> {
+        if channels <= 1 {
+            return samples.to_vec();
+        }
+        samples.chunks_exact(channels).map(|frame| frame.iter().sum::<f32>() / channels as f32).collect()
+    }
+
     struct AudioOutput {
-        tx: channel::Sender<Vec<f32>>,
+        sink: SampleSink,
         sample_rate: u32,
+        // Layout assumed for buffers that don't describe themselves
+        configured: PcmLayout,
+        // Set once an unsupported format has been reported, so the log isn't flooded
+        format_error_logged: AtomicBool,
+        // Converts buffers delivered at some other rate (the first field) to `sample_rate`
+        resampler: Mutex<Option<(u32, StreamResampler)>>,
     }
+
+    impl AudioOutput {
+        /// Downstream frames are tagged with `sample_rate`; audio at any other rate would come
+        /// out pitch-shifted after `resample_to_16k`, so bring it to `sample_rate` first.
+        fn conform_rate(&self, samples: Vec<f32>, delivered: Option<u32>) -> Vec<f32> {
+            let delivered = match delivered {
+                Some(rate) if rate != self.sample_rate => rate,
+                _ => return samples,
+            };
+            let mut resampler = self.resampler.lock().unwrap_or_else(|e| e.into_inner());
+            if resampler.as_ref().map(|(rate, _)| *rate) != Some(delivered) {
+                warn!(
+                    "⚠️ ScreenCaptureKit is delivering {} Hz audio instead of the negotiated {} Hz; resampling",
+                    delivered, self.sample_rate
+                );
+                *resampler = Some((delivered, StreamResampler::new(delivered, self.sample_rate)));
+            }
+            match resampler.as_mut() {
+                Some((_, r)) => r.process(&samples),
+                None => samples,
+            }
+        }
+    }
+
     impl SCStreamOutputTrait for AudioOutput {
         fn did_output_sample_buffer(&self, sample: CMSampleBuffer, of_type: SCStreamOutputType) {
             if let SCStreamOutputType::Audio = of_type {
-                if let Ok(list) = sample.get_audio_buffer_list() {
-                    let mut out: Vec<f32> = Vec::new();
-                    for buf in list.buffers() {
-                        let bytes = buf.data();
-                        let mut i = 0;
-                        while i + 3 < bytes.len() {
-                            let v = f32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
-                            if v.is_finite() { out.push(v); }
-                            i += 4;
+                let layout = match pcm_layout(&sample, self.configured) {
+                    Ok(layout) => layout,
+                    Err(e) => {
+                        if !self.format_error_logged.swap(true, Ordering::Relaxed) {
+                            error!("❌ ScreenCaptureKit delivered audio Oatmeal can't read ({}); system audio is being dropped", e);
                         }
+                        return;
                     }
-                    if !out.is_empty() { let _ = self.tx.try_send(out); }
+                };
+                if let Ok(list) = sample.get_audio_buffer_list() {
+                    let buffers: Vec<Vec<f32>> = list.buffers().into_iter().map(|buf| decode(buf.data(), layout)).collect();
+                    let out = if layout.interleaved || buffers.len() <= 1 {
+                        buffers.iter().flat_map(|b| downmix(b, if layout.interleaved { layout.channels } else { 1 })).collect()
+                    } else {
+                        // One buffer per channel: average them sample by sample
+                        let len = buffers.iter().map(|b| b.len()).min().unwrap_or(0);
+                        (0..len).map(|i| buffers.iter().map(|b| b[i]).sum::<f32>() / buffers.len() as f32).collect()
+                    };
+                    let out = self.conform_rate(out, layout.sample_rate);
+                    if !out.is_empty() { self.sink.push(out); }
                 }
             }
         }
     }
 
-    pub async fn start_system_audio_capture(app_handle: tauri::AppHandle) -> Result<(), String> {
+    /// Running applications that have a bundle id, sorted by name.
+    pub fn list_capturable_apps() -> Result<Vec<CapturableApp>, String> {
+        let content = SCShareableContent::get().map_err(|e| format!("SCShareableContent error: {e:?}"))?;
+        let mut apps: Vec<CapturableApp> = content
+            .applications()
+            .iter()
+            .filter(|app| !app.bundle_identifier().is_empty())
+            .map(|app| CapturableApp {
+                bundle_id: app.bundle_identifier(),
+                name: app.application_name(),
+                pid: app.process_id(),
+            })
+            .collect();
+        apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        apps.dedup_by(|a, b| a.bundle_id == b.bundle_id);
+        Ok(apps)
+    }
+
+    /// Start capturing system audio and forward mono f32 chunks to `sink`. With
+    /// `app_bundle_id`, only that application's audio is captured.
+    /// Returns the negotiated sample rate; mixing with the mic happens in `AudioRuntime`.
+    pub async fn start_system_audio_capture(sink: SampleSink, config: SystemCaptureConfig) -> Result<u32, String> {
         // Avoid double-start
         if RUNNING.swap(true, Ordering::SeqCst) {
-            return Ok(());
+            return Err("System audio capture is already running".to_string());
         }
+        let mut result = start_stream(sink.clone(), &config, config.sample_rate);
+        if let Err(e) = &result {
+            if config.sample_rate != FALLBACK_SAMPLE_RATE {
+                warn!("⚠️ ScreenCaptureKit rejected {} Hz ({}); retrying at {} Hz", config.sample_rate, e, FALLBACK_SAMPLE_RATE);
+                result = start_stream(sink, &config, FALLBACK_SAMPLE_RATE);
+            }
+        }
+        if result.is_err() {
+            RUNNING.store(false, Ordering::SeqCst);
+        }
+        result
+    }
 
-        // Build SCKit stream for current display with audio enabled
-        let display = SCShareableContent::get()
-            .map_err(|e| format!("SCShareableContent error: {e:?}"))?
-            .displays()
-            .into_iter()
-            .next()
-            .ok_or_else(|| "No displays available for ScreenCaptureKit".to_string())?;
-        let filter = SCContentFilter::new().with_display_excluding_windows(&display, &[]);
-        let config = SCStreamConfiguration::new()
+    fn stream_configuration(sample_rate: u32, channels: u32) -> Result<SCStreamConfiguration, String> {
+        SCStreamConfiguration::new()
             .set_captures_audio(true)
             .map_err(|e| format!("SCK set_captures_audio failed: {e:?}"))?
-            .set_sample_rate(48_000)
+            .set_sample_rate(sample_rate)
             .map_err(|e| format!("SCK set_sample_rate failed: {e:?}"))?
-            .set_channel_count(1)
+            .set_channel_count(channels)
             .map_err(|e| format!("SCK set_channel_count failed: {e:?}"))?
             .set_width(1)
             .and_then(|c| c.set_height(1))
-            .map_err(|e| format!("SCK set dimensions failed: {e:?}"))?;
-
-        // Channel to decouple SCK callback from emission aggregator
-        let (tx, rx) = channel::bounded::<Vec<f32>>(4);
+            .map_err(|e| format!("SCK set dimensions failed: {e:?}"))
+    }
 
-        // Aggregator to emit ~20ms frames consistently
-        let sr = config.get_sample_rate();
-        let frame_len = (sr as usize / 50).max(1);
-        let running_ref = Arc::new(AtomicBool::new(true));
-        let running_emit = running_ref.clone();
-        let app_handle_emit = app_handle.clone();
-        std::thread::spawn(move || {
-            let mut buf: Vec<f32> = Vec::with_capacity(frame_len * 2);
-            while running_emit.load(Ordering::Relaxed) {
-                match rx.recv_timeout(std::time::Duration::from_millis(50)) {
-                    Ok(mut chunk) => {
-                        buf.append(&mut chunk);
-                    }
-                    Err(_) => {}
-                }
-                while buf.len() >= frame_len {
-                    let frame: Vec<f32> = buf.drain(0..frame_len).collect();
-                    let _ = app_handle_emit.emit_all(
-                        "audio:frame",
-                        serde_json::json!({
-                            "data": frame,
-                            "timestamp": std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_millis(),
-                            "sample_rate": sr
-                        }),
-                    );
-                }
+    fn start_stream(sink: SampleSink, capture: &SystemCaptureConfig, sample_rate: u32) -> Result<u32, String> {
+        // Build SCKit stream for current display with audio enabled
+        let content = SCShareableContent::get().map_err(|e| format!("SCShareableContent error: {e:?}"))?;
+        let display = content
+            .displays()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No displays available for ScreenCaptureKit".to_string())?;
+        let app: Option<SCRunningApplication> = capture.app_bundle_id.as_deref().and_then(|bundle_id| {
+            let found = content.applications().into_iter().find(|a| a.bundle_identifier() == bundle_id);
+            if found.is_none() {
+                // Better to hear everything than to silently miss the other side of the call
+                warn!("⚠️ {} is not running; capturing all system audio", bundle_id);
             }
+            found
         });
+        let filter = match &app {
+            Some(app) => SCContentFilter::new().with_display_including_application_excluding_windows(&display, &[app], &[]),
+            None => SCContentFilter::new().with_display_excluding_windows(&display, &[]),
+        };
+        let config = stream_configuration(sample_rate, capture.channels.max(1))?;
+
+        // What SCKit actually agreed to, which is what the frames are tagged with downstream
+        let sr = config.get_sample_rate();
+        if sr != sample_rate {
+            warn!("⚠️ ScreenCaptureKit negotiated {} Hz instead of the requested {} Hz", sr, sample_rate);
+        }
+        let channels = config.get_channel_count();
 
         // Create stream and start
         let mut stream = SCStream::new(&filter, &config);
-        stream.add_output_handler(AudioOutput { tx, sample_rate: sr }, SCStreamOutputType::Audio);
+        stream.add_output_handler(
+            AudioOutput {
+                sink,
+                sample_rate: sr,
+                configured: configured_layout(channels),
+                format_error_logged: AtomicBool::new(false),
+                resampler: Mutex::new(None),
+            },
+            SCStreamOutputType::Audio,
+        );
         stream
             .start_capture()
             .map_err(|e| format!("SCK start failed: {e:?}"))?;
 
         // Hold onto stream so it stays alive
         *STREAM_HOLDER.lock().unwrap() = Some(stream);
-        Ok(())
+        Ok(sr)
     }
 
     pub async fn stop_system_audio_capture() -> Result<(), String> {
@@ -130,7 +319,7 @@ pub mod macos {
         match SCShareableContent::get() {
             Ok(_) => Ok(true),
             Err(e) => {
-                eprintln!("SCKit permission check error: {:?}", e);
+                error!("SCKit permission check error: {:?}", e);
                 Ok(false)
             }
         }
@@ -139,7 +328,10 @@ pub mod macos {
 
 #[cfg(not(target_os = "macos"))]
 pub mod macos {
-    pub async fn start_system_audio_capture(_app_handle: tauri::AppHandle) -> Result<(), String> {
+    pub fn list_capturable_apps() -> Result<Vec<super::CapturableApp>, String> {
+        Err("Per-app audio capture is only available on macOS".to_string())
+    }
+    pub async fn start_system_audio_capture(_sink: crate::audio::SampleSink, _config: super::SystemCaptureConfig) -> Result<u32, String> {
         Err("ScreenCaptureKit is only available on macOS".to_string())
     }
     pub async fn stop_system_audio_capture() -> Result<(), String> { Ok(()) }
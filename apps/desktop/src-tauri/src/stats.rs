@@ -0,0 +1,137 @@
+//! Per-session and library-wide numbers for the stats dashboard. Everything here is
+//! derived from what's already stored (transcript, segments, duration); nothing new
+//! is recorded to produce it.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::database::{FolderRecord, SessionRecord};
+use crate::transcribe::TranscriptSegment;
+
+// Upper bounds (minutes) of the duration histogram buckets; the last bucket is open-ended
+const DURATION_BUCKETS_MIN: [i32; 4] = [5, 15, 30, 60];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStats {
+    pub session_id: String,
+    pub word_count: usize,
+    pub duration_seconds: i32,
+    /// `None` when the session has no duration to divide by
+    pub words_per_minute: Option<f64>,
+    /// Milliseconds of transcribed speech per source ("mic", "system", or "unknown");
+    /// empty when the session has no segments
+    pub talk_time_ms: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderCount {
+    /// `None` for sessions not in a folder
+    pub folder_id: Option<String>,
+    pub folder_name: Option<String>,
+    pub sessions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DurationBucket {
+    /// e.g. "<5m", "5-15m", "60m+"
+    pub label: String,
+    pub sessions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OverallStats {
+    pub total_sessions: usize,
+    pub total_hours: f64,
+    pub total_words: usize,
+    pub per_folder: Vec<FolderCount>,
+    pub duration_histogram: Vec<DurationBucket>,
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+pub fn session_stats(session: &SessionRecord) -> SessionStats {
+    let word_count = session.transcript.as_deref().map(word_count).unwrap_or(0);
+    let words_per_minute = if session.duration > 0 {
+        Some(word_count as f64 / (session.duration as f64 / 60.0))
+    } else {
+        None
+    };
+
+    let segments: Vec<TranscriptSegment> = session
+        .segments
+        .as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    let mut talk_time_ms = BTreeMap::new();
+    for segment in &segments {
+        let source = segment.source.clone().unwrap_or_else(|| "unknown".to_string());
+        *talk_time_ms.entry(source).or_insert(0) += segment.end_ms.saturating_sub(segment.start_ms);
+    }
+
+    SessionStats {
+        session_id: session.id.clone(),
+        word_count,
+        duration_seconds: session.duration,
+        words_per_minute,
+        talk_time_ms,
+    }
+}
+
+fn bucket_label(index: usize) -> String {
+    match index {
+        0 => format!("<{}m", DURATION_BUCKETS_MIN[0]),
+        i if i < DURATION_BUCKETS_MIN.len() => {
+            format!("{}-{}m", DURATION_BUCKETS_MIN[i - 1], DURATION_BUCKETS_MIN[i])
+        }
+        _ => format!("{}m+", DURATION_BUCKETS_MIN[DURATION_BUCKETS_MIN.len() - 1]),
+    }
+}
+
+pub fn overall_stats(sessions: &[SessionRecord], folders: &[FolderRecord]) -> OverallStats {
+    let total_seconds: i64 = sessions.iter().map(|s| s.duration.max(0) as i64).sum();
+    let total_words = sessions
+        .iter()
+        .map(|s| s.transcript.as_deref().map(word_count).unwrap_or(0))
+        .sum();
+
+    let mut by_folder: BTreeMap<Option<String>, usize> = BTreeMap::new();
+    for session in sessions {
+        *by_folder.entry(session.folder_id.clone()).or_insert(0) += 1;
+    }
+    let per_folder = by_folder
+        .into_iter()
+        .map(|(folder_id, count)| FolderCount {
+            folder_name: folder_id
+                .as_deref()
+                .and_then(|id| folders.iter().find(|f| f.id == id))
+                .map(|f| f.name.clone()),
+            folder_id,
+            sessions: count,
+        })
+        .collect();
+
+    let mut histogram = vec![0usize; DURATION_BUCKETS_MIN.len() + 1];
+    for session in sessions {
+        let minutes = session.duration.max(0) / 60;
+        let index = DURATION_BUCKETS_MIN
+            .iter()
+            .position(|&upper| minutes < upper)
+            .unwrap_or(DURATION_BUCKETS_MIN.len());
+        histogram[index] += 1;
+    }
+
+    OverallStats {
+        total_sessions: sessions.len(),
+        total_hours: total_seconds as f64 / 3600.0,
+        total_words,
+        per_folder,
+        duration_histogram: histogram
+            .into_iter()
+            .enumerate()
+            .map(|(i, sessions)| DurationBucket { label: bucket_label(i), sessions })
+            .collect(),
+    }
+}
@@ -1,16 +1,520 @@
+use once_cell::sync::{Lazy, OnceCell};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH, Instant};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH, Instant};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+use log::{debug, error, info, trace, warn};
+
+use crate::audio::recorder::WavRecorder;
+
+// `<app data>/models`, set once at startup; where models are downloaded and looked for first
+static APP_MODELS_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Use `dir` (the app-data models directory) for downloads and as the first place to load
+/// models from, so both agree no matter what the working directory is.
+pub fn set_models_dir(dir: PathBuf) {
+    let _ = APP_MODELS_DIR.set(dir);
+}
+
+// The most recently loaded model, reused by every `initialize`/model switch that asks for the
+// same file (and GPU use). Holding the lock while loading keeps concurrent inits from reading it twice.
+static LOADED_CONTEXT: Lazy<Mutex<Option<(PathBuf, bool, Arc<WhisperContext>)>>> = Lazy::new(|| Mutex::new(None));
+
+// Whisper threads in `low_power_mode`, whatever the preset asks for
+const LOW_POWER_THREADS: i32 = 2;
+
+// Model downloads in progress by file name, each with whether it's been asked to stop
+static ACTIVE_DOWNLOADS: Lazy<Mutex<HashMap<String, bool>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Error `download_model` returns when `cancel_download` stopped it.
+pub const DOWNLOAD_CANCELLED: &str = "Download cancelled";
+
+/// Registration in `ACTIVE_DOWNLOADS` for as long as a download runs.
+struct ActiveDownload(String);
+
+impl ActiveDownload {
+    fn start(file_name: &str) -> Result<Self, String> {
+        let mut active = ACTIVE_DOWNLOADS.lock().unwrap_or_else(|e| e.into_inner());
+        if active.contains_key(file_name) {
+            return Err(format!("{} is already downloading", file_name));
+        }
+        active.insert(file_name.to_string(), false);
+        Ok(Self(file_name.to_string()))
+    }
+
+    fn is_cancelled(&self) -> bool {
+        let active = ACTIVE_DOWNLOADS.lock().unwrap_or_else(|e| e.into_inner());
+        active.get(&self.0).copied().unwrap_or(false)
+    }
+}
+
+impl Drop for ActiveDownload {
+    fn drop(&mut self) {
+        ACTIVE_DOWNLOADS.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.0);
+    }
+}
+
+/// Ask the download of `model_name` to stop; it deletes its partial file and fails with
+/// `DOWNLOAD_CANCELLED`. Returns false when that model isn't downloading.
+pub fn cancel_download(model_name: &str) -> bool {
+    let file_name = Transcriber::model_file_name(model_name);
+    let mut active = ACTIVE_DOWNLOADS.lock().unwrap_or_else(|e| e.into_inner());
+    match active.get_mut(&file_name) {
+        Some(cancelled) => {
+            *cancelled = true;
+            true
+        }
+        None => false,
+    }
+}
+
+fn shared_context(model_path: &Path, use_gpu: bool) -> Result<Arc<WhisperContext>, String> {
+    let mut loaded = LOADED_CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((path, gpu, ctx)) = loaded.as_ref() {
+        if path == model_path && *gpu == use_gpu {
+            debug!("Reusing loaded Whisper model: {}", model_path.display());
+            return Ok(ctx.clone());
+        }
+    }
+    info!("Loading Whisper model: {}{}", model_path.display(), if use_gpu { "" } else { " (CPU only)" });
+    let mut params = WhisperContextParameters::default();
+    params.use_gpu(use_gpu);
+    let ctx = WhisperContext::new_with_params(model_path.to_str().ok_or("Model path is not valid UTF-8")?, params)
+        .map_err(|e| format!("Failed to create whisper context: {:?}", e))?;
+    let ctx = Arc::new(ctx);
+    *loaded = Some((model_path.to_path_buf(), use_gpu, ctx.clone()));
+    Ok(ctx)
+}
+
+// Mean energy at or below this is treated as silence (no voice worth transcribing)
+pub const SILENCE_DB: f32 = -50.0;
+
+// Whisper hallucinates on very short buffers ("Thank you.", "you", music notes) rather
+// than returning nothing, so live audio shorter than this is skipped
+pub const DEFAULT_MIN_TRANSCRIBE_MS: u32 = 300;
+
+/// Mean energy of `samples` in dB; -inf for digital silence.
+pub fn energy_db(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let energy: f32 = samples.iter().map(|&x| x * x).sum::<f32>() / samples.len() as f32;
+    10.0 * energy.log10()
+}
+
+pub fn is_silent(samples: &[f32]) -> bool {
+    energy_db(samples) <= SILENCE_DB
+}
+
+// Bumped by `cancel_all`; a transcription stops once this differs from the value it started with
+static CANCEL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Abort every transcription that is currently running (live chunk, file, or re-transcribe).
+/// Ones started afterwards are unaffected.
+pub fn cancel_all() {
+    CANCEL_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+#[derive(Clone, Copy)]
+struct CancelCheck(u64);
+
+impl CancelCheck {
+    fn new() -> Self {
+        Self(CANCEL_GENERATION.load(Ordering::SeqCst))
+    }
+
+    fn is_cancelled(self) -> bool {
+        CANCEL_GENERATION.load(Ordering::SeqCst) != self.0
+    }
+
+    /// Make whisper bail out of decoding as soon as a cancel comes in.
+    fn attach(self, params: &mut FullParams) {
+        params.set_abort_callback_safe(move || self.is_cancelled());
+    }
+}
+
+/// A timed piece of transcript. Times are milliseconds from the start of the recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// "mic" | "system" when streams are captured separately
+    pub source: Option<String>,
+    /// Two-party label derived from the source: the mic is "me", system audio is "them"
+    pub speaker: Option<String>,
+    /// Language whisper used for this piece (detected when the setting is "auto")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Mean word probability (0.0-1.0) when `live_confidence` is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Live chunks only: how much audio had been captured on this stream when the chunk
+    /// began, which tracks the saved WAV more closely than `start_ms`'s wall clock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t_ms: Option<u64>,
+    /// Per-word probabilities for caption shading; sent with `transcript:partial`, not stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<WordConfidence>>,
+}
+
+/// Whisper's probability for one word, averaged over its tokens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordConfidence {
+    pub word: String,
+    pub confidence: f32,
+}
+
+impl TranscriptSegment {
+    pub fn speaker_for_source(source: &str) -> Option<String> {
+        match source {
+            "mic" => Some("me".to_string()),
+            "system" => Some("them".to_string()),
+            "track1" => Some("track 1".to_string()),
+            "track2" => Some("track 2".to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Distinct speaker labels in order of first appearance.
+pub fn speakers(segments: &[TranscriptSegment]) -> Vec<String> {
+    let mut speakers: Vec<String> = Vec::new();
+    for speaker in segments.iter().filter_map(|s| s.speaker.as_ref()) {
+        if !speakers.contains(speaker) {
+            speakers.push(speaker.clone());
+        }
+    }
+    speakers
+}
+
+/// The live transcript as shown while recording: segment texts joined with spaces, with
+/// a new "Me:"/"Them:" (or "Track 1:"/"Track 2:") line whenever the source changes when
+/// streams are captured separately.
+pub fn running_transcript(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::new();
+    let mut last_label: Option<&str> = None;
+    for segment in segments {
+        let label = match segment.source.as_deref() {
+            Some("mic") => Some("Me"),
+            Some("system") => Some("Them"),
+            Some("track1") => Some("Track 1"),
+            Some("track2") => Some("Track 2"),
+            _ => None,
+        };
+        match label {
+            Some(label) if last_label != Some(label) => {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(label);
+                out.push_str(": ");
+                last_label = Some(label);
+            }
+            _ => {
+                if !out.is_empty() {
+                    out.push(' ');
+                }
+            }
+        }
+        out.push_str(segment.text.trim());
+    }
+    out
+}
+
+/// Most frequent language across `segments`; ties go to the one seen first so a
+/// mixed-language meeting doesn't flip between runs.
+pub fn dominant_language(segments: &[TranscriptSegment]) -> Option<String> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for language in segments.iter().filter_map(|s| s.language.as_deref()) {
+        match counts.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((language, 1)),
+        }
+    }
+    let mut best: Option<(&str, usize)> = None;
+    for (language, n) in counts {
+        if best.map_or(true, |(_, best_n)| n > best_n) {
+            best = Some((language, n));
+        }
+    }
+    best.map(|(language, _)| language.to_string())
+}
+
+/// Whisper language to request: `None` for "auto" (detect), otherwise the code.
+fn whisper_language(language: &str) -> Option<&str> {
+    match language.trim() {
+        "" | "auto" => None,
+        code => Some(code),
+    }
+}
+
+/// Words from the last `full` run on `state` with their probabilities. Tokens starting
+/// with a space begin a new word; special tokens ("[_BEG_]", "<|endoftext|>") are skipped.
+fn word_confidences(state: &WhisperState) -> Result<Vec<WordConfidence>, String> {
+    let mut words: Vec<(String, f32, u32)> = Vec::new();
+    let segments = state.full_n_segments().map_err(|e| format!("Failed to get segments: {:?}", e))?;
+    for segment in 0..segments {
+        let tokens = state.full_n_tokens(segment).map_err(|e| format!("Failed to get tokens: {:?}", e))?;
+        for token in 0..tokens {
+            let text = state
+                .full_get_token_text(segment, token)
+                .map_err(|e| format!("Failed to get token text: {:?}", e))?;
+            if text.starts_with("[_") || text.starts_with("<|") || text.trim().is_empty() {
+                continue;
+            }
+            let prob = state
+                .full_get_token_prob(segment, token)
+                .map_err(|e| format!("Failed to get token probability: {:?}", e))?;
+            match words.last_mut() {
+                Some((word, sum, count)) if !text.starts_with(' ') => {
+                    word.push_str(&text);
+                    *sum += prob;
+                    *count += 1;
+                }
+                _ => words.push((text.trim_start().to_string(), prob, 1)),
+            }
+        }
+    }
+    Ok(words
+        .into_iter()
+        .map(|(word, sum, count)| WordConfidence { word, confidence: sum / count as f32 })
+        .collect())
+}
+
+fn comparable_word(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Confidence for a cleaned live chunk from the raw words whisper produced: the mean over
+/// all of them, plus the per-word values when `text` is still exactly their tail. Overlap
+/// dedupe drops leading words and number normalization rewrites some, and misattributed
+/// shading is worse than none.
+pub fn chunk_confidence(words: &[WordConfidence], text: &str) -> (Option<f32>, Option<Vec<WordConfidence>>) {
+    if words.is_empty() {
+        return (None, None);
+    }
+    let mean = words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32;
+    let cleaned: Vec<&str> = text.split_whitespace().collect();
+    if cleaned.len() > words.len() {
+        return (Some(mean), None);
+    }
+    let tail = &words[words.len() - cleaned.len()..];
+    let matches = tail
+        .iter()
+        .zip(&cleaned)
+        .all(|(w, c)| comparable_word(&w.word) == comparable_word(c));
+    if !matches {
+        return (Some(mean), None);
+    }
+    let aligned = tail
+        .iter()
+        .zip(&cleaned)
+        .map(|(w, c)| WordConfidence { word: c.to_string(), confidence: w.confidence })
+        .collect();
+    (Some(mean), Some(aligned))
+}
+
+/// Language whisper ended up using for the last `full` run on `state`.
+fn detected_language(state: &WhisperState) -> Option<String> {
+    let id = state.full_lang_id_from_state().ok()?;
+    whisper_rs::get_lang_str(id).map(|l| l.to_string())
+}
+
+/// How long whisper took on one live chunk. `rtf` (real-time factor) is
+/// `process_ms / audio_ms`; above 1.0 transcription is falling behind the audio.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkTiming {
+    pub audio_ms: u64,
+    pub process_ms: u64,
+    pub rtf: f32,
+}
+
+impl ChunkTiming {
+    fn new(samples_16k: usize, elapsed: Duration) -> Self {
+        let audio_ms = (samples_16k / 16) as u64;
+        let process_ms = elapsed.as_millis() as u64;
+        let rtf = if audio_ms == 0 { 0.0 } else { process_ms as f32 / audio_ms as f32 };
+        Self { audio_ms, process_ms, rtf }
+    }
+}
+
+/// Bundles of live Whisper parameters behind the `transcription_preset` setting, trading
+/// caption latency against accuracy with one knob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptionPreset {
+    /// Greedy, no temperature fallback, one segment per chunk: keeps up on tiny/base models
+    Fast,
+    /// Greedy with temperature fallback; the long-standing defaults
+    Balanced,
+    /// Beam search, earlier text as context, free segmentation
+    Accurate,
+}
+
+impl TranscriptionPreset {
+    /// Unknown values get `Balanced`.
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "fast" => Self::Fast,
+            "accurate" => Self::Accurate,
+            _ => Self::Balanced,
+        }
+    }
+
+    fn strategy(self) -> SamplingStrategy {
+        match self {
+            Self::Accurate => SamplingStrategy::BeamSearch { beam_size: 5, patience: -1.0 },
+            Self::Fast | Self::Balanced => SamplingStrategy::Greedy { best_of: 1 },
+        }
+    }
+
+    fn apply(self, params: &mut FullParams<'_, '_>) {
+        match self {
+            Self::Fast => {
+                params.set_n_threads(4);
+                params.set_single_segment(true);
+                params.set_no_context(true);
+                params.set_temperature(0.0);
+                // No retries at higher temperatures: a shaky chunk costs one pass, not several
+                params.set_temperature_inc(0.0);
+                params.set_entropy_thold(2.4);
+                params.set_logprob_thold(-1.5);
+            }
+            Self::Balanced => {
+                params.set_n_threads(4);
+                params.set_single_segment(true);
+                params.set_no_context(true);
+                params.set_temperature(0.2);
+                params.set_temperature_inc(0.2);
+                params.set_entropy_thold(2.4);
+                params.set_logprob_thold(-1.5);
+            }
+            Self::Accurate => {
+                let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+                params.set_n_threads(cores.clamp(4, 8) as i32);
+                params.set_single_segment(false);
+                params.set_no_context(false);
+                params.set_temperature(0.0);
+                params.set_temperature_inc(0.2);
+                params.set_entropy_thold(2.4);
+                params.set_logprob_thold(-1.0);
+            }
+        }
+    }
+}
+
+/// Advanced overrides of a preset's decoding values (the `whisper_*` settings); `None`
+/// keeps the preset's. Whisper decodes at `temperature` and, if the result fails either
+/// threshold, retries at higher temperatures (when the preset allows it).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DecodingThresholds {
+    /// Randomness of the first decode: 0 always picks the likeliest token; higher can shake
+    /// Whisper out of repeating itself at the cost of reproducibility
+    pub temperature: Option<f32>,
+    /// Text whose token entropy is above this counts as a failed decode (repetitive, likely
+    /// hallucinated); lower retries more often
+    pub entropy_thold: Option<f32>,
+    /// Text whose average token log-probability is below this counts as a failed decode;
+    /// raising it (toward 0) drops more uncertain text, lowering it keeps more
+    pub logprob_thold: Option<f32>,
+}
+
+impl DecodingThresholds {
+    fn apply(self, params: &mut FullParams<'_, '_>) {
+        if let Some(temperature) = self.temperature {
+            params.set_temperature(temperature.clamp(0.0, 1.0));
+        }
+        if let Some(entropy) = self.entropy_thold {
+            params.set_entropy_thold(entropy);
+        }
+        if let Some(logprob) = self.logprob_thold {
+            params.set_logprob_thold(logprob);
+        }
+    }
+}
+
+/// Everything besides language and task that shapes a live Whisper run.
+#[derive(Debug, Clone, Copy)]
+struct Decoding {
+    preset: TranscriptionPreset,
+    thresholds: DecodingThresholds,
+    low_power: bool,
+}
+
+/// whisper.cpp GGML files start with this magic ("ggml" as a little-endian u32).
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+// The smallest real model (tiny) is ~75 MB; anything far below that is a cut-off download
+const MIN_MODEL_BYTES: u64 = 30 * 1024 * 1024;
+
+/// One file in the models directory and whether it looks loadable.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelFileStatus {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub valid: bool,
+    pub problem: Option<String>,
+}
+
+/// What `get_model_status` reports: where models are looked for and what's there.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStatus {
+    pub models_dir: Option<String>,
+    /// Why no usable models directory was found, if it wasn't
+    pub error: Option<String>,
+    pub models: Vec<ModelFileStatus>,
+    pub loaded_model: Option<String>,
+}
+
+fn check_model_file(path: &Path) -> ModelFileStatus {
+    let file_name = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+    let size_bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut magic = [0u8; 4];
+    let read_magic = std::fs::File::open(path).and_then(|mut f| std::io::Read::read_exact(&mut f, &mut magic));
+    let problem = if file_name.ends_with(".part") {
+        Some("Incomplete download".to_string())
+    } else if let Err(e) = read_magic {
+        Some(format!("Unreadable: {}", e))
+    } else if u32::from_le_bytes(magic) != GGML_MAGIC {
+        Some("Not a GGML model file".to_string())
+    } else if size_bytes < MIN_MODEL_BYTES {
+        Some("File is too small; the download was probably cut off".to_string())
+    } else {
+        None
+    };
+    ModelFileStatus { file_name, size_bytes, valid: problem.is_none(), problem }
+}
 
 pub struct Transcriber {
-    client: Client,
     model_path: Option<PathBuf>,
-    whisper_context: Option<WhisperContext>,
+    whisper_context: Option<Arc<WhisperContext>>,
     whisper_state: Option<WhisperState>,
     model_downloaded: bool,
     last_text: Option<String>,
     last_when: Option<Instant>,
+    language: String,
+    /// Whisper's translate task: English text whatever the spoken language
+    translate: bool,
+    /// The loaded model has run an inference (see `warm_up`)
+    warm: bool,
+    preset: TranscriptionPreset,
+    thresholds: DecodingThresholds,
+    /// `low_power_mode`: smallest model, CPU only, fewer threads
+    low_power: bool,
+    /// The model to go back to when low-power mode is turned off
+    full_power_model: Option<PathBuf>,
+    last_language: Option<String>,
+    last_timing: Option<ChunkTiming>,
+    /// Live audio shorter than this is skipped (see `DEFAULT_MIN_TRANSCRIBE_MS`)
+    min_transcribe_ms: u32,
+    /// Collect word probabilities for each live chunk (`live_confidence`)
+    collect_confidence: bool,
+    last_words: Option<Vec<WordConfidence>>,
+    /// Receives every live chunk exactly as it's passed to Whisper (`debug_save_audio`)
+    debug_recorder: Option<WavRecorder>,
 }
 
 impl Transcriber {
@@ -38,6 +542,19 @@ impl Transcriber {
     }
 
     fn find_models_dir() -> Result<PathBuf, String> {
+        if let Some(dir) = APP_MODELS_DIR.get() {
+            if Self::find_supported_model_in(dir).is_some() {
+                return Ok(dir.clone());
+            }
+        }
+        // Fall back to the old CWD-relative location so existing dev/manual setups keep working
+        Self::find_legacy_models_dir().map_err(|e| match APP_MODELS_DIR.get() {
+            Some(dir) => format!("No model in {} and {}", dir.display(), e),
+            None => e,
+        })
+    }
+
+    fn find_legacy_models_dir() -> Result<PathBuf, String> {
         // Walk up to locate a 'models' directory that actually contains a supported GGML model
         let mut dir = std::env::current_dir().map_err(|e| format!("Failed to get current dir: {}", e))?;
         let mut checked: Vec<String> = Vec::new();
@@ -45,7 +562,7 @@ impl Transcriber {
             let candidate = dir.join("models");
             if candidate.exists() {
                 if let Some(model) = Self::find_supported_model_in(&candidate) {
-                    println!("Models dir '{}' contains supported model: {}", candidate.display(), model.file_name().unwrap().to_string_lossy());
+                    debug!("Models dir '{}' contains supported model: {}", candidate.display(), model.file_name().unwrap().to_string_lossy());
                     return Ok(candidate);
                 } else {
                     checked.push(candidate.display().to_string());
@@ -87,14 +604,141 @@ impl Transcriber {
 
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
             model_path: None,
             whisper_context: None,
             whisper_state: None,
             model_downloaded: false,
             last_text: None,
             last_when: None,
+            language: "en".to_string(),
+            translate: false,
+            warm: false,
+            preset: TranscriptionPreset::Balanced,
+            thresholds: DecodingThresholds::default(),
+            low_power: false,
+            full_power_model: None,
+            last_language: None,
+            last_timing: None,
+            min_transcribe_ms: DEFAULT_MIN_TRANSCRIBE_MS,
+            collect_confidence: false,
+            last_words: None,
+            debug_recorder: None,
+        }
+    }
+
+    /// Language code for live transcription ("auto" detects per chunk).
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    /// Translate live chunks to English instead of transcribing them (`translate_to_english`).
+    pub fn set_translate(&mut self, translate: bool) {
+        self.translate = translate;
+    }
+
+    pub fn is_translating(&self) -> bool {
+        self.translate
+    }
+
+    /// Whisper parameters for live chunks (`transcription_preset`).
+    pub fn set_preset(&mut self, preset: TranscriptionPreset) {
+        self.preset = preset;
+    }
+
+    /// Overrides of the preset's temperature and thresholds (`whisper_*` settings).
+    pub fn set_thresholds(&mut self, thresholds: DecodingThresholds) {
+        self.thresholds = thresholds;
+    }
+
+    fn decoding(&self) -> Decoding {
+        Decoding { preset: self.preset, thresholds: self.thresholds, low_power: self.low_power }
+    }
+
+    /// Turn `low_power_mode` on or off. On, switches to the smallest downloaded model
+    /// (multilingual when `multilingual`, i.e. for another language or translation) and runs
+    /// it on the CPU with `LOW_POWER_THREADS`; off, reloads the model used before. Loads a
+    /// model, so it blocks: call from `spawn_blocking`.
+    pub fn set_low_power(&mut self, enabled: bool, multilingual: bool) -> Result<(), String> {
+        let previous = self.model_path.clone();
+        if !enabled {
+            if !self.low_power {
+                return Ok(());
+            }
+            self.low_power = false;
+            if let Some(model_path) = self.full_power_model.clone().or(previous) {
+                if let Err(e) = self.load_model(model_path) {
+                    self.low_power = true;
+                    return Err(e);
+                }
+            }
+            self.full_power_model = None;
+            info!("Low-power transcription off");
+            return Ok(());
+        }
+
+        // Without a suitable small model, the current one is still run the cheap way
+        let model_path = Self::smallest_model(multilingual).or_else(|| previous.clone());
+        if self.low_power && model_path == previous {
+            return Ok(());
+        }
+        let was_low_power = self.low_power;
+        self.low_power = true;
+        if let Some(model_path) = model_path {
+            if let Err(e) = self.load_model(model_path) {
+                self.low_power = was_low_power;
+                return Err(e);
+            }
         }
+        if !was_low_power {
+            self.full_power_model = previous;
+        }
+        info!("Low-power transcription on");
+        Ok(())
+    }
+
+    /// The downloaded model with the smallest file, which is also the cheapest to run.
+    /// English-only (`*.en`) models are skipped when `multilingual`.
+    fn smallest_model(multilingual: bool) -> Option<PathBuf> {
+        Self::model_dirs()
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok()))
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.starts_with("ggml-") && name.ends_with(".bin") && !(multilingual && name.ends_with(".en.bin"))
+            })
+            .filter_map(|e| e.metadata().ok().map(|m| (m.len(), e.path())))
+            .min_by_key(|(len, _)| *len)
+            .map(|(_, path)| path)
+    }
+
+    /// Off by default: reading every token's probability adds work to each chunk.
+    pub fn set_collect_confidence(&mut self, enabled: bool) {
+        self.collect_confidence = enabled;
+    }
+
+    /// Start (or with `None`, stop) saving what Whisper hears during live transcription.
+    pub fn set_debug_recorder(&mut self, recorder: Option<WavRecorder>) {
+        self.debug_recorder = recorder;
+    }
+
+    /// Word probabilities for the most recent live chunk, if collected; cleared once taken.
+    pub fn take_words(&mut self) -> Option<Vec<WordConfidence>> {
+        self.last_words.take()
+    }
+
+    pub fn set_min_transcribe_ms(&mut self, ms: u32) {
+        self.min_transcribe_ms = ms;
+    }
+
+    /// Timing of the most recent live chunk whisper actually ran on; cleared once taken.
+    pub fn take_timing(&mut self) -> Option<ChunkTiming> {
+        self.last_timing.take()
+    }
+
+    /// Language whisper used for the most recent live chunk.
+    pub fn last_language(&self) -> Option<String> {
+        self.last_language.clone()
     }
 
     pub async fn initialize(&mut self, model_name: Option<&str>) -> Result<(), String> {
@@ -105,11 +749,11 @@ impl Transcriber {
         // Locate models directory robustly
         let models_dir = match Self::find_models_dir() {
             Ok(p) => {
-                println!("Found models directory at: {}", p.display());
+                debug!("Found models directory at: {}", p.display());
                 p
             },
             Err(e) => {
-                eprintln!("Model directory discovery failed: {}", e);
+                error!("Model directory discovery failed: {}", e);
                 return Err(e);
             }
         };
@@ -118,81 +762,276 @@ impl Transcriber {
         let model_path = match Self::pick_model_path(&models_dir, model_name) {
             Ok(p) => p,
             Err(e) => {
-                eprintln!("Model file selection failed: {}", e);
+                error!("Model file selection failed: {}", e);
                 return Err(e);
             }
         };
 
-        // Initialize Whisper context with the local model
-        println!("Loading Whisper model: {}", model_path.display());
-        let ctx_params = WhisperContextParameters::default();
-        let ctx = WhisperContext::new_with_params(
-            model_path.to_str().unwrap(),
-            ctx_params
-        ).map_err(|e| format!("Failed to create whisper context: {:?}", e))?;
-        
-        // Create a whisper state for processing
+        self.load_model(model_path)
+    }
+
+    /// Every directory models are read from: the app-data one, then the legacy CWD one.
+    fn model_dirs() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = APP_MODELS_DIR.get().cloned().into_iter().collect();
+        if let Ok(legacy) = Self::find_legacy_models_dir() {
+            if !dirs.contains(&legacy) {
+                dirs.push(legacy);
+            }
+        }
+        dirs
+    }
+
+    /// Labels ("small.en", "tiny.en", ...) of the downloaded `ggml-*.bin` models.
+    pub fn list_available_models() -> Vec<String> {
+        let mut names: Vec<String> = Self::model_dirs()
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()))
+            .filter(|p| {
+                let name = p.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+                name.starts_with("ggml-") && name.ends_with(".bin")
+            })
+            .map(|p| Self::model_label(&p))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Switch to the downloaded model `name` ("small.en" or its file name). Unlike
+    /// `initialize`, never falls back to a different model.
+    pub async fn set_active_model(&mut self, name: &str) -> Result<(), String> {
+        let file_name = Self::model_file_name(name);
+        let model_path = Self::model_dirs()
+            .into_iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|p| p.exists())
+            .ok_or_else(|| format!("Model '{}' is not downloaded", name))?;
+        self.load_model(model_path)
+    }
+
+    fn load_model(&mut self, model_path: PathBuf) -> Result<(), String> {
+        // On failure the current model stays loaded
+        let ctx = shared_context(&model_path, !self.low_power)?;
+
+        // Create a whisper state for processing; cheap next to loading the model
         let state = ctx.create_state().map_err(|e| format!("Failed to create whisper state: {:?}", e))?;
         
         self.whisper_context = Some(ctx);
         self.whisper_state = Some(state);
         self.model_path = Some(model_path);
         self.model_downloaded = true;
+        self.warm = false;
         
-        println!("✅ Local Whisper model loaded successfully");
+        info!("✅ Local Whisper model loaded successfully");
         Ok(())
     }
 
-    pub async fn download_model_from_hf(&mut self, model_name: &str) -> Result<(), String> {
-        // Download model files from Hugging Face
-        let base_url = format!("https://huggingface.co/{}/resolve/main", model_name);
-        
-        // Create models directory
-        let models_dir = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current dir: {}", e))?
-            .join("models");
-        
+    /// Normalize "small.en", "ggml-small.en" or "ggml-small.en.bin" to the GGML file name.
+    pub fn model_file_name(name: &str) -> String {
+        let name = name.trim();
+        let base = name.strip_suffix(".bin").unwrap_or(name);
+        if base.starts_with("ggml-") || base.starts_with("whisper-") {
+            format!("{}.bin", base)
+        } else {
+            format!("ggml-{}.bin", base)
+        }
+    }
+
+    /// Short model name for display and bookkeeping: "ggml-small.en.bin" -> "small.en".
+    pub fn model_label(path: &Path) -> String {
+        let file = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+        let base = file.strip_suffix(".bin").unwrap_or(&file);
+        base.strip_prefix("ggml-").unwrap_or(base).to_string()
+    }
+
+    /// Label of the currently loaded model, if any.
+    pub fn model_name(&self) -> Option<String> {
+        self.model_path.as_deref().map(Self::model_label)
+    }
+
+    fn download_dir() -> Result<PathBuf, String> {
+        if let Some(dir) = APP_MODELS_DIR.get() {
+            return Ok(dir.clone());
+        }
+        Self::find_legacy_models_dir().or_else(|_| {
+            std::env::current_dir()
+                .map(|d| d.join("models"))
+                .map_err(|e| format!("Failed to get current dir: {}", e))
+        })
+    }
+
+    /// Path to a local copy of `model_name`, if one exists.
+    pub fn local_model_path(model_name: &str) -> Option<PathBuf> {
+        let path = Self::download_dir().ok()?.join(Self::model_file_name(model_name));
+        if path.exists() { Some(path) } else { None }
+    }
+
+    /// Download a GGML model from the whisper.cpp Hugging Face repo unless it's already
+    /// present. Streams to a `.part` file and renames on completion so an interrupted
+    /// download never looks like a valid model. `on_progress(downloaded, total)`.
+    pub async fn download_model<F>(model_name: &str, on_progress: F) -> Result<PathBuf, String>
+    where
+        F: Fn(u64, Option<u64>),
+    {
+        let file_name = Self::model_file_name(model_name);
+        let models_dir = Self::download_dir()?;
         std::fs::create_dir_all(&models_dir)
             .map_err(|e| format!("Failed to create models dir: {}", e))?;
+        let path = models_dir.join(&file_name);
+        if path.exists() {
+            return Ok(path);
+        }
 
-        // Download model files
-        let files = vec![
-            "config.json",
-            "tokenizer.json", 
-            "model.safetensors",
-        ];
+        let download = ActiveDownload::start(&file_name)?;
+        let url = format!("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}", file_name);
+        info!("Downloading {} ...", url);
+        let mut response = Client::new()
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", file_name, e))?;
+        if download.is_cancelled() {
+            return Err(DOWNLOAD_CANCELLED.to_string());
+        }
+        if !response.status().is_success() {
+            return Err(format!("Failed to download {}: HTTP {}", file_name, response.status()));
+        }
 
-        for file in files {
-            let url = format!("{}/{}", base_url, file);
-            let file_path = models_dir.join(file);
-            
-            if file_path.exists() {
-                println!("File {} already exists, skipping download", file);
-                continue;
+        let total = response.content_length();
+        let part_path = models_dir.join(format!("{}.part", file_name));
+        let mut file = std::fs::File::create(&part_path)
+            .map_err(|e| format!("Failed to create {}: {}", part_path.display(), e))?;
+        let mut downloaded = 0u64;
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = std::fs::remove_file(&part_path);
+                    return Err(format!("Download of {} interrupted: {}", file_name, e));
+                }
+            };
+            std::io::Write::write_all(&mut file, &chunk)
+                .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+            downloaded += chunk.len() as u64;
+            on_progress(downloaded, total);
+            if download.is_cancelled() {
+                // Dropping the response closes the connection; there's no resume, so the
+                // partial file is only clutter
+                drop(file);
+                let _ = std::fs::remove_file(&part_path);
+                info!("Download of {} cancelled after {} bytes", file_name, downloaded);
+                return Err(DOWNLOAD_CANCELLED.to_string());
             }
-            
-            println!("Downloading {} from Hugging Face...", file);
-            
-            let response = self.client.get(&url)
-                .send()
-                .await
-                .map_err(|e| format!("Failed to download {}: {}", file, e))?;
+        }
+        drop(file);
+        std::fs::rename(&part_path, &path)
+            .map_err(|e| format!("Failed to move {} into place: {}", file_name, e))?;
+        info!("✅ Downloaded {}", path.display());
+        Ok(path)
+    }
 
-            if !response.status().is_success() {
-                return Err(format!("Failed to download {}: HTTP {}", file, response.status()));
-            }
+    /// Resolve the models directory the same way `initialize` does and check every model
+    /// file in it, so the UI can say exactly why initialization failed.
+    pub fn model_status(&self) -> ModelStatus {
+        let (models_dir, error) = match Self::find_models_dir() {
+            Ok(dir) => (Some(dir), None),
+            // Still list the download location, which may hold only broken files
+            Err(e) => (Self::download_dir().ok().filter(|d| d.exists()), Some(e)),
+        };
+        let mut models: Vec<ModelFileStatus> = models_dir
+            .as_deref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .map(|entries| entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .filter(|p| {
+                    let name = p.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+                    name.ends_with(".bin") || name.ends_with(".bin.part")
+                })
+                .map(|p| check_model_file(&p))
+                .collect())
+            .unwrap_or_default();
+        models.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        ModelStatus {
+            models_dir: models_dir.map(|d| d.display().to_string()),
+            error,
+            models,
+            loaded_model: self.model_name(),
+        }
+    }
 
-            let bytes = response.bytes()
-                .await
-                .map_err(|e| format!("Failed to read {}: {}", file, e))?;
+    /// Transcribe a whole recording with a dedicated context for `model_path`, leaving the
+    /// live model untouched. Audio is processed in 30 s windows; `on_progress` gets 0.0..=1.0.
+    /// `language` is a whisper code or "auto" to detect per window; with `translate` the
+    /// segments are English whatever was spoken. Blocking: call from `spawn_blocking`.
+    pub fn transcribe_file<F>(model_path: &Path, audio_16k: &[f32], language: &str, translate: bool, on_progress: F) -> Result<Vec<TranscriptSegment>, String>
+    where
+        F: Fn(f32),
+    {
+        const WINDOW: usize = 30 * 16_000;
+        let cancel = CancelCheck::new();
 
-            std::fs::write(&file_path, bytes)
-                .map_err(|e| format!("Failed to write {}: {}", file, e))?;
-        }
+        let ctx = WhisperContext::new_with_params(
+            model_path.to_str().ok_or("Model path is not valid UTF-8")?,
+            WhisperContextParameters::default(),
+        ).map_err(|e| format!("Failed to load {}: {:?}", model_path.display(), e))?;
+        let mut state = ctx.create_state().map_err(|e| format!("Failed to create whisper state: {:?}", e))?;
 
-        self.model_path = Some(models_dir);
-        self.model_downloaded = true;
-        Ok(())
+        let windows = ((audio_16k.len() + WINDOW - 1) / WINDOW).max(1);
+        let mut segments = Vec::new();
+        for (index, window) in audio_16k.chunks(WINDOW).enumerate() {
+            if cancel.is_cancelled() {
+                return Err("Transcription cancelled".to_string());
+            }
+            let offset_ms = (index * WINDOW / 16) as u64;
+            let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+            cancel.attach(&mut params);
+            params.set_n_threads(4);
+            params.set_translate(translate);
+            params.set_language(whisper_language(language));
+            params.set_print_special(false);
+            params.set_print_progress(false);
+            params.set_print_realtime(false);
+            params.set_print_timestamps(false);
+            params.set_suppress_blank(true);
+            params.set_suppress_non_speech_tokens(true);
+
+            let result = state.full(params, window);
+            if cancel.is_cancelled() {
+                return Err("Transcription cancelled".to_string());
+            }
+            result.map_err(|e| format!("Whisper transcription failed: {:?}", e))?;
+            let window_language = detected_language(&state);
+            let n = state.full_n_segments()
+                .map_err(|e| format!("Failed to get segments: {:?}", e))?;
+            for i in 0..n {
+                let text = state.full_get_segment_text(i)
+                    .map_err(|e| format!("Failed to get segment text: {:?}", e))?;
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                // Whisper timestamps are in 10 ms units
+                let t0 = state.full_get_segment_t0(i).unwrap_or(0).max(0) as u64 * 10;
+                let t1 = state.full_get_segment_t1(i).unwrap_or(0).max(0) as u64 * 10;
+                segments.push(TranscriptSegment {
+                    start_ms: offset_ms + t0,
+                    end_ms: offset_ms + t1,
+                    text: text.to_string(),
+                    source: None,
+                    speaker: None,
+                    language: window_language.clone(),
+                    confidence: None,
+                    t_ms: None,
+                    words: None,
+                });
+            }
+            on_progress((index + 1) as f32 / windows as f32);
+        }
+        Ok(segments)
     }
 
     fn resample_to_16k(input: &[f32], src_sr: u32) -> Vec<f32> {
@@ -234,37 +1073,53 @@ impl Transcriber {
     }
 
     pub async fn transcribe_audio_data(&mut self, audio_data: &[f32], sample_rate: u32) -> Result<String, String> {
+        self.last_timing = None;
+        self.last_words = None;
         if !self.model_downloaded {
             return Err("Model not initialized. Call initialize() first.".to_string());
         }
 
-        // Check if we have enough audio data
-        if audio_data.len() < 1000 {
+        // Too short to transcribe reliably; measured in time so it doesn't depend on the rate
+        let min_samples = sample_rate as u64 * self.min_transcribe_ms as u64 / 1000;
+        if (audio_data.len() as u64) < min_samples.max(1) {
             return Ok("".to_string());
         }
         
         // Resample to 16 kHz for whisper
         let audio_16k = Self::resample_to_16k(audio_data, sample_rate);
 
-        // Calculate audio energy for voice activity detection on resampled signal
-        let energy: f32 = audio_16k.iter().map(|&x| x * x).sum::<f32>() / audio_16k.len() as f32;
-        let energy_db = 10.0 * energy.log10();
-        
         // Only transcribe if there's sufficient audio energy
-        if energy_db <= -50.0 { // slightly more permissive
+        if is_silent(&audio_16k) {
             return Ok("".to_string());
         }
 
         // Use local Whisper model (no API costs!)
         if self.whisper_state.is_some() {
+            if let Some(recorder) = self.debug_recorder.as_mut() {
+                recorder.write(&audio_16k);
+                // Keep the file playable mid-recording; it's never explicitly finalized
+                recorder.flush();
+            }
+            let cancel = CancelCheck::new();
+            let decoding = self.decoding();
             let result = {
                 let state = self.whisper_state.as_mut().unwrap();
-                Self::transcribe_with_whisper_static(state, &audio_16k).await
+                let result = Self::transcribe_with_whisper_static(state, &audio_16k, &self.language, self.translate, decoding, cancel).await;
+                self.last_language = detected_language(state);
+                if self.collect_confidence && result.is_ok() {
+                    self.last_words = word_confidences(state)
+                        .map_err(|e| error!("Failed to read word confidence: {}", e))
+                        .ok();
+                }
+                result.map(|(text, elapsed)| {
+                    self.last_timing = Some(ChunkTiming::new(audio_16k.len(), elapsed));
+                    text
+                })
             };
             
             match result {
                 Ok(text) if !text.trim().is_empty() => {
-                    println!("🎤 Local Whisper transcribed: {}", text);
+                    trace!("🎤 Local Whisper transcribed: {}", text);
                     return Ok(text);
                 },
                 Ok(_) => {
@@ -272,7 +1127,7 @@ impl Transcriber {
                     return Ok("".to_string());
                 }, 
                 Err(e) => {
-                    println!("⚠️ Local Whisper failed: {}", e);
+                    warn!("⚠️ Local Whisper failed: {}", e);
                 }
             }
         }
@@ -280,15 +1135,36 @@ impl Transcriber {
         Ok(String::new())
     }
 
-    async fn transcribe_with_whisper_static(state: &mut WhisperState, audio_data: &[f32]) -> Result<String, String> {
+    /// Run one throwaway inference on a second of silence so the first real chunk doesn't
+    /// pay for Whisper's first-run setup. Returns false without doing anything when the
+    /// loaded model is already warm.
+    pub async fn warm_up(&mut self) -> Result<bool, String> {
+        if self.warm {
+            return Ok(false);
+        }
+        let decoding = self.decoding();
+        let state = self.whisper_state.as_mut().ok_or("Transcriber is not initialized")?;
+        let silence = vec![0.0f32; 16_000];
+        let (_, elapsed) =
+            Self::transcribe_with_whisper_static(state, &silence, &self.language, self.translate, decoding, CancelCheck::new()).await?;
+        self.warm = true;
+        info!("Whisper warmed up in {} ms", elapsed.as_millis());
+        Ok(true)
+    }
+
+    /// Returns the text and the wall time spent inside whisper.
+    async fn transcribe_with_whisper_static(state: &mut WhisperState, audio_data: &[f32], language: &str, translate: bool, decoding: Decoding, cancel: CancelCheck) -> Result<(String, Duration), String> {
         // Set up transcription parameters suitable for short live chunks
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        params.set_n_threads(4);
-        params.set_translate(false);
-        params.set_language(Some("en"));
+        let mut params = FullParams::new(decoding.preset.strategy());
+        cancel.attach(&mut params);
+        decoding.preset.apply(&mut params);
+        decoding.thresholds.apply(&mut params);
+        if decoding.low_power {
+            params.set_n_threads(LOW_POWER_THREADS);
+        }
+        params.set_translate(translate);
+        params.set_language(whisper_language(language));
         params.set_no_timestamps(true);
-        params.set_single_segment(true);
-        params.set_no_context(true);
         params.set_max_len(64);
         params.set_print_special(false);
         params.set_print_progress(false);
@@ -296,14 +1172,15 @@ impl Transcriber {
         params.set_print_timestamps(false);
         params.set_suppress_blank(true);
         params.set_suppress_non_speech_tokens(true);
-        params.set_temperature(0.2);
-        params.set_temperature_inc(0.2);
-        params.set_entropy_thold(2.4);
-        params.set_logprob_thold(-1.5);
 
         // Run local Whisper transcription
-        state.full(params, audio_data)
-            .map_err(|e| format!("Whisper transcription failed: {:?}", e))?;
+        let started = Instant::now();
+        let result = state.full(params, audio_data);
+        let elapsed = started.elapsed();
+        if cancel.is_cancelled() {
+            return Err("Transcription cancelled".to_string());
+        }
+        result.map_err(|e| format!("Whisper transcription failed: {:?}", e))?;
 
         // Extract text from segments
         let num_segments = state.full_n_segments()
@@ -311,6 +1188,9 @@ impl Transcriber {
         
         let mut result = String::new();
         for i in 0..num_segments {
+            if cancel.is_cancelled() {
+                return Err("Transcription cancelled".to_string());
+            }
             let segment_text = state.full_get_segment_text(i)
                 .map_err(|e| format!("Failed to get segment text: {:?}", e))?;
             result.push_str(&segment_text);
@@ -327,9 +1207,9 @@ impl Transcriber {
             .chunks(5)
             .all(|chunk| chunk.iter().collect::<String>().contains("check") );
         if cleaned.len() < 3 || is_repetitive_check {
-            return Ok(String::new());
+            return Ok((String::new(), elapsed));
         }
-        Ok(cleaned)
+        Ok((cleaned, elapsed))
     }
 
     fn mock_transcription(&mut self, audio_data: &[f32]) -> Result<String, String> {
@@ -377,56 +1257,6 @@ impl Transcriber {
         Ok(chosen)
     }
 
-    async fn transcribe_via_openai(&self, audio_data: &[f32]) -> Result<String, String> {
-        let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| "OPENAI_API_KEY not set".to_string())?;
-
-        // Encode to 16-bit mono WAV in-memory
-        let sample_rate = 16000u32;
-        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
-        let spec = hound::WavSpec {
-            channels: 1,
-            sample_rate,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-        let mut writer = hound::WavWriter::new(&mut cursor, spec)
-            .map_err(|e| format!("Failed to init WAV writer: {}", e))?;
-        for &s in audio_data {
-            // clamp and convert to i16
-            let v = (s.max(-1.0).min(1.0) * i16::MAX as f32) as i16;
-            writer.write_sample(v).map_err(|e| format!("WAV write failed: {}", e))?;
-        }
-        writer.finalize().map_err(|e| format!("WAV finalize failed: {}", e))?;
-        let wav_bytes = cursor.into_inner();
-
-        // Build multipart form
-        let part = reqwest::multipart::Part::bytes(wav_bytes)
-            .file_name("audio.wav")
-            .mime_str("audio/wav").unwrap();
-        let form = reqwest::multipart::Form::new()
-            .text("model", "gpt-4o-mini-transcribe")
-            .part("file", part);
-
-        let url = "https://api.openai.com/v1/audio/transcriptions";
-        let resp = self.client
-            .post(url)
-            .bearer_auth(api_key)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| format!("OpenAI request failed: {}", e))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(format!("OpenAI error ({}): {}", status, body));
-        }
-
-        let json: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid OpenAI response: {}", e))?;
-        let text = json.get("text").and_then(|v| v.as_str()).unwrap_or("").to_string();
-        Ok(text)
-    }
-
     pub fn is_ready(&self) -> bool {
         self.model_downloaded
     }
@@ -0,0 +1,434 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use tokio::sync::{Mutex, Notify};
+use log::warn;
+
+// ~20s of backlog at the default 2.5s chunks; beyond that captions are too stale to be useful
+const QUEUE_CAPACITY: usize = 8;
+
+/// One chunk of captured audio waiting to be transcribed.
+pub struct AudioChunk {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub source: Option<String>,
+    /// Position in the recording when the chunk ended, if a recording is running
+    pub end_ms: Option<u64>,
+    /// Start time of the recording the chunk belongs to; a change resets boundary context
+    pub recording_start: Option<u64>,
+    /// Audio captured on this stream before the chunk, in ms: where it starts in the saved
+    /// WAV. Filled in by `TranscriptionQueue::push` for chunks of a running recording
+    pub t_ms: Option<u64>,
+}
+
+/// Bounded FIFO between the `transcribe_audio` command and the transcription worker.
+/// When full, the oldest chunk is dropped so live captions catch up instead of lagging.
+#[derive(Default)]
+pub struct TranscriptionQueue {
+    chunks: Mutex<VecDeque<AudioChunk>>,
+    ready: Notify,
+    /// Per stream: the recording it belongs to and microseconds of audio pushed so far.
+    /// Counted on push, so chunks dropped from a full queue still move the clock on
+    captured: Mutex<HashMap<Option<String>, (Option<u64>, u64)>>,
+}
+
+impl TranscriptionQueue {
+    pub async fn push(&self, mut chunk: AudioChunk) {
+        if chunk.recording_start.is_some() {
+            let mut captured = self.captured.lock().await;
+            let stream = captured.entry(chunk.source.clone()).or_insert((chunk.recording_start, 0));
+            if stream.0 != chunk.recording_start {
+                *stream = (chunk.recording_start, 0);
+            }
+            chunk.t_ms = Some(stream.1 / 1000);
+            stream.1 += chunk.samples.len() as u64 * 1_000_000 / chunk.sample_rate.max(1) as u64;
+        }
+        let mut chunks = self.chunks.lock().await;
+        if chunks.len() >= QUEUE_CAPACITY {
+            if let Some(dropped) = chunks.pop_front() {
+                warn!(
+                    "⚠️ Transcription backlog full; dropping oldest chunk ({} ms of audio)",
+                    dropped.samples.len() as u64 * 1000 / dropped.sample_rate.max(1) as u64
+                );
+            }
+        }
+        chunks.push_back(chunk);
+        drop(chunks);
+        self.ready.notify_one();
+    }
+
+    /// Drop everything still waiting; returns how many chunks were discarded.
+    pub async fn clear(&self) -> usize {
+        let mut chunks = self.chunks.lock().await;
+        let count = chunks.len();
+        chunks.clear();
+        count
+    }
+
+    /// Wait for the next chunk.
+    pub async fn pop(&self) -> AudioChunk {
+        loop {
+            if let Some(chunk) = self.chunks.lock().await.pop_front() {
+                return chunk;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// Like `pop`, but the next queued chunks from the same stream and recording are
+    /// appended to it, up to `max` chunks in all. Chunks of other streams in between (mic
+    /// and system alternate when captured separately) stay queued in order.
+    pub async fn pop_merged(&self, max: usize) -> AudioChunk {
+        let mut chunk = self.pop().await;
+        let mut chunks = self.chunks.lock().await;
+        let mut merged = 1;
+        let mut index = 0;
+        while merged < max && index < chunks.len() {
+            let same_stream = chunks[index].source == chunk.source
+                && chunks[index].recording_start == chunk.recording_start
+                && chunks[index].sample_rate == chunk.sample_rate;
+            if !same_stream {
+                index += 1;
+                continue;
+            }
+            if let Some(next) = chunks.remove(index) {
+                chunk.samples.extend_from_slice(&next.samples);
+                chunk.end_ms = next.end_ms;
+                merged += 1;
+            }
+        }
+        chunk
+    }
+
+    /// Chunks waiting to be transcribed.
+    pub async fn backlog(&self) -> usize {
+        self.chunks.lock().await.len()
+    }
+}
+
+// Chunks transcribed in one go at each adaptive level
+const MERGE_LEVELS: [usize; 3] = [1, 2, 4];
+// A backlog this long (in chunks) means Whisper isn't keeping up
+const BEHIND_BACKLOG: usize = 2;
+// Chunks in a row with an empty queue and spare time before stepping back down a level
+const CALM_CHUNKS_TO_RELAX: u32 = 3;
+const RELAX_RTF: f32 = 0.6;
+// While catching up, chunks quieter than this are skipped; quiet speech can still get
+// through at -50 dB (`transcribe::SILENCE_DB`) but it rarely carries much
+pub const CATCH_UP_QUIET_DB: f32 = -40.0;
+
+/// How the worker is currently batching live chunks, reported in `transcript:adapted`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AdaptiveLevel {
+    /// Chunks merged into each Whisper call
+    pub merge_chunks: usize,
+    /// Quiet chunks are dropped instead of transcribed
+    pub skip_quiet: bool,
+}
+
+/// Opt-in catch-up for machines where Whisper runs slower than real time
+/// (`adaptive_chunking`): while the queue backs up, chunks are merged into longer
+/// ones, which Whisper handles far more efficiently, and quiet ones are skipped. Once
+/// the queue stays empty it steps back down to one chunk at a time.
+#[derive(Default)]
+pub struct AdaptiveChunking {
+    level: usize,
+    calm_chunks: u32,
+}
+
+impl AdaptiveChunking {
+    pub fn reset(&mut self) {
+        self.level = 0;
+        self.calm_chunks = 0;
+    }
+
+    pub fn current(&self) -> AdaptiveLevel {
+        AdaptiveLevel { merge_chunks: MERGE_LEVELS[self.level], skip_quiet: self.level > 0 }
+    }
+
+    /// Update after a chunk with the queue's `backlog` and the chunk's real-time factor
+    /// (None when it was skipped). Returns the new level when it changed.
+    pub fn observe(&mut self, backlog: usize, rtf: Option<f32>) -> Option<AdaptiveLevel> {
+        let before = self.level;
+        if backlog >= BEHIND_BACKLOG || rtf.map_or(false, |rtf| rtf > 1.0) {
+            self.calm_chunks = 0;
+            self.level = (self.level + 1).min(MERGE_LEVELS.len() - 1);
+        } else if backlog == 0 && rtf.map_or(true, |rtf| rtf < RELAX_RTF) {
+            self.calm_chunks += 1;
+            if self.calm_chunks >= CALM_CHUNKS_TO_RELAX && self.level > 0 {
+                self.calm_chunks = 0;
+                self.level -= 1;
+            }
+        } else {
+            self.calm_chunks = 0;
+        }
+        if self.level != before {
+            Some(self.current())
+        } else {
+            None
+        }
+    }
+}
+
+// Boundary repeats longer than this are more likely real repetition than overlap
+const MAX_OVERLAP_WORDS: usize = 12;
+// A single repeated word ("the the") is too often genuine speech to drop
+const MIN_OVERLAP_WORDS: usize = 2;
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// Drop the words at the start of `next` that repeat the last words of `prev`, comparing
+/// case- and punctuation-insensitively and preferring the longest match.
+pub fn dedupe_boundary(prev: &str, next: &str) -> String {
+    let prev_words: Vec<String> = prev.split_whitespace().map(normalize_word).collect();
+    let next_raw: Vec<&str> = next.split_whitespace().collect();
+    let next_words: Vec<String> = next_raw.iter().map(|w| normalize_word(w)).collect();
+    let max = MAX_OVERLAP_WORDS.min(prev_words.len()).min(next_words.len());
+    for n in (MIN_OVERLAP_WORDS..=max).rev() {
+        let tail = &prev_words[prev_words.len() - n..];
+        if tail == &next_words[..n] && tail.iter().any(|w| !w.is_empty()) {
+            return next_raw[n..].join(" ");
+        }
+    }
+    next.trim().to_string()
+}
+
+struct StreamContext {
+    tail: Vec<f32>,
+    sample_rate: u32,
+    last_text: String,
+}
+
+/// Stitches consecutive live chunks of each stream together: the last `overlap_ms` of
+/// audio is prepended to the next chunk so Whisper has context at the boundary, and the
+/// words that overlap then produces are trimmed from the new text.
+#[derive(Default)]
+pub struct ChunkAssembler {
+    overlap_ms: u32,
+    normalize_numbers: bool,
+    streams: HashMap<Option<String>, StreamContext>,
+}
+
+impl ChunkAssembler {
+    /// Forget all context, e.g. when a new recording starts.
+    pub fn reset(&mut self, overlap_ms: u32, normalize_numbers: bool) {
+        self.overlap_ms = overlap_ms;
+        self.normalize_numbers = normalize_numbers;
+        self.streams.clear();
+    }
+
+    /// A chunk of `source` was dropped untranscribed: its tail and text no longer lead into
+    /// the next chunk, so forget them.
+    pub fn skip(&mut self, source: &Option<String>) {
+        if let Some(context) = self.streams.get_mut(source) {
+            context.tail.clear();
+            context.last_text.clear();
+        }
+    }
+
+    /// Audio to transcribe for `chunk`: the previous tail followed by the chunk itself.
+    pub fn with_context(&mut self, chunk: &AudioChunk) -> Vec<f32> {
+        let tail_len = (chunk.sample_rate as u64 * self.overlap_ms as u64 / 1000) as usize;
+        let context = self.streams.entry(chunk.source.clone()).or_insert_with(|| StreamContext {
+            tail: Vec::new(),
+            sample_rate: chunk.sample_rate,
+            last_text: String::new(),
+        });
+        let mut samples = if context.sample_rate == chunk.sample_rate {
+            std::mem::take(&mut context.tail)
+        } else {
+            Vec::new()
+        };
+        samples.extend_from_slice(&chunk.samples);
+        let keep_from = chunk.samples.len().saturating_sub(tail_len);
+        context.tail = chunk.samples[keep_from..].to_vec();
+        context.sample_rate = chunk.sample_rate;
+        samples
+    }
+
+    /// New text for the stream with any boundary repeat removed and, if enabled, numbers
+    /// written as digits.
+    pub fn merge_text(&mut self, source: &Option<String>, text: &str) -> String {
+        let merged = self.dedupe(source, text);
+        // After de-duplication, so boundary matching still sees Whisper's own words
+        if self.normalize_numbers {
+            crate::numbers::normalize_numbers(&merged)
+        } else {
+            merged
+        }
+    }
+
+    fn dedupe(&mut self, source: &Option<String>, text: &str) -> String {
+        match self.streams.get_mut(source) {
+            Some(context) => {
+                let merged = dedupe_boundary(&context.last_text, text);
+                context.last_text = text.to_string();
+                merged
+            }
+            None => text.trim().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `seconds` of constant `value` at 1 kHz, for `source` in recording `recording_start`.
+    fn chunk(source: &str, value: f32, seconds: usize, recording_start: Option<u64>) -> AudioChunk {
+        AudioChunk {
+            samples: vec![value; seconds * 1000],
+            sample_rate: 1000,
+            source: Some(source.to_string()),
+            end_ms: recording_start.map(|_| 0),
+            recording_start,
+            t_ms: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_the_oldest_chunk() {
+        let queue = TranscriptionQueue::default();
+        for i in 0..=QUEUE_CAPACITY {
+            queue.push(chunk("mic", i as f32, 1, None)).await;
+        }
+        assert_eq!(queue.backlog().await, QUEUE_CAPACITY);
+        assert_eq!(queue.pop().await.samples[0], 1.0);
+    }
+
+    #[tokio::test]
+    async fn t_ms_counts_dropped_chunks_and_restarts_with_the_recording() {
+        let queue = TranscriptionQueue::default();
+        for _ in 0..QUEUE_CAPACITY + 2 {
+            queue.push(chunk("mic", 0.1, 1, Some(1))).await;
+        }
+        // The two oldest were dropped, but their audio is still in the saved WAV
+        assert_eq!(queue.pop().await.t_ms, Some(2000));
+        queue.clear().await;
+
+        queue.push(chunk("mic", 0.1, 1, Some(2))).await;
+        queue.push(chunk("mic", 0.1, 1, Some(2))).await;
+        queue.push(chunk("system", 0.1, 1, Some(2))).await;
+        queue.push(chunk("mic", 0.1, 1, None)).await;
+        let mut t_ms = Vec::new();
+        for _ in 0..4 {
+            t_ms.push(queue.pop().await.t_ms);
+        }
+        assert_eq!(t_ms, vec![Some(0), Some(1000), Some(0), None]);
+    }
+
+    #[tokio::test]
+    async fn pop_merged_joins_one_stream_across_interleaved_chunks() {
+        let queue = TranscriptionQueue::default();
+        for (i, source) in ["mic", "system", "mic", "system", "mic"].iter().enumerate() {
+            let mut next = chunk(source, i as f32, 1, Some(1));
+            next.end_ms = Some((i as u64 + 1) * 1000);
+            queue.push(next).await;
+        }
+
+        let mic = queue.pop_merged(4).await;
+        assert_eq!(mic.source.as_deref(), Some("mic"));
+        assert_eq!(mic.samples.len(), 3000);
+        assert_eq!((mic.samples[0], mic.samples[1000], mic.samples[2000]), (0.0, 2.0, 4.0));
+        assert_eq!(mic.end_ms, Some(5000));
+
+        let system = queue.pop_merged(1).await;
+        assert_eq!((system.source.as_deref(), system.samples.len()), (Some("system"), 1000));
+        assert_eq!(queue.backlog().await, 1);
+    }
+
+    #[tokio::test]
+    async fn pop_merged_keeps_recordings_and_rates_apart() {
+        let queue = TranscriptionQueue::default();
+        queue.push(chunk("mic", 0.0, 1, Some(1))).await;
+        queue.push(chunk("mic", 1.0, 1, Some(2))).await;
+        let mut resampled = chunk("mic", 2.0, 1, Some(1));
+        resampled.sample_rate = 2000;
+        queue.push(resampled).await;
+        queue.push(chunk("mic", 3.0, 1, Some(1))).await;
+
+        let merged = queue.pop_merged(4).await;
+        assert_eq!(merged.samples.len(), 2000);
+        assert_eq!(merged.samples[1000], 3.0);
+        assert_eq!(queue.backlog().await, 2);
+    }
+
+    #[test]
+    fn adaptive_steps_up_when_behind_and_stops_at_the_top() {
+        let mut adaptive = AdaptiveChunking::default();
+        assert_eq!(adaptive.observe(BEHIND_BACKLOG, Some(0.5)).map(|l| l.merge_chunks), Some(2));
+        let top = adaptive.observe(0, Some(1.5)).unwrap();
+        assert_eq!(top, AdaptiveLevel { merge_chunks: 4, skip_quiet: true });
+        assert_eq!(adaptive.observe(BEHIND_BACKLOG + 3, None), None);
+        assert_eq!(adaptive.current(), top);
+    }
+
+    #[test]
+    fn adaptive_relaxes_only_after_calm_chunks_in_a_row() {
+        let mut adaptive = AdaptiveChunking::default();
+        adaptive.observe(BEHIND_BACKLOG, None);
+        for _ in 1..CALM_CHUNKS_TO_RELAX {
+            assert_eq!(adaptive.observe(0, Some(0.2)), None);
+        }
+        // Neither behind nor calm: the run starts over
+        assert_eq!(adaptive.observe(1, Some(0.2)), None);
+        for _ in 1..CALM_CHUNKS_TO_RELAX {
+            assert_eq!(adaptive.observe(0, None), None);
+        }
+        assert_eq!(adaptive.observe(0, Some(0.2)), Some(AdaptiveLevel { merge_chunks: 1, skip_quiet: false }));
+        // Spare time but not much of it doesn't count as calm
+        assert_eq!(adaptive.observe(0, Some(0.9)), None);
+    }
+
+    #[test]
+    fn dedupe_boundary_drops_repeated_words_ignoring_case_and_punctuation() {
+        assert_eq!(dedupe_boundary("and then we shipped it.", "Shipped it, on Friday"), "on Friday");
+        // The longest repeat wins over a shorter one
+        assert_eq!(dedupe_boundary("we will go, we will", "we will go, we will stay"), "stay");
+        assert_eq!(dedupe_boundary("", "hello there"), "hello there");
+    }
+
+    #[test]
+    fn dedupe_boundary_keeps_single_word_and_overlong_repeats() {
+        assert_eq!(dedupe_boundary("over to the", "the the plan"), "the the plan");
+        let sentence = "one two three four five six seven eight nine ten eleven twelve thirteen";
+        assert_eq!(dedupe_boundary(sentence, &format!("{} and on", sentence)), format!("{} and on", sentence));
+        // Punctuation alone never counts as a match
+        assert_eq!(dedupe_boundary("wait - -", "- - really"), "- - really");
+    }
+
+    fn assembler_chunk(source: &str, samples: Vec<f32>, sample_rate: u32) -> AudioChunk {
+        AudioChunk { samples, sample_rate, source: Some(source.to_string()), end_ms: None, recording_start: None, t_ms: None }
+    }
+
+    #[test]
+    fn assembler_prepends_the_previous_tail_per_stream() {
+        let mut assembler = ChunkAssembler::default();
+        assembler.reset(500, false);
+        let first = assembler.with_context(&assembler_chunk("mic", vec![1.0; 1000], 1000));
+        assert_eq!(first.len(), 1000);
+        assembler.with_context(&assembler_chunk("system", vec![3.0; 1000], 1000));
+        let second = assembler.with_context(&assembler_chunk("mic", vec![2.0; 1000], 1000));
+        assert_eq!(second.len(), 1500);
+        assert_eq!((second[0], second[499], second[500]), (1.0, 1.0, 2.0));
+    }
+
+    #[test]
+    fn assembler_drops_the_tail_on_a_rate_change_or_skip() {
+        let mut assembler = ChunkAssembler::default();
+        assembler.reset(500, false);
+        assembler.with_context(&assembler_chunk("mic", vec![1.0; 1000], 1000));
+        let resampled = assembler.with_context(&assembler_chunk("mic", vec![2.0; 2000], 2000));
+        assert_eq!(resampled, vec![2.0; 2000]);
+
+        let source = Some("mic".to_string());
+        assembler.merge_text(&source, "see you on Friday");
+        assembler.skip(&source);
+        let after_skip = assembler.with_context(&assembler_chunk("mic", vec![3.0; 2000], 2000));
+        assert_eq!(after_skip, vec![3.0; 2000]);
+        assert_eq!(assembler.merge_text(&source, "on Friday then"), "on Friday then");
+        assert_eq!(assembler.merge_text(&source, "Friday then, bye"), "bye");
+    }
+}
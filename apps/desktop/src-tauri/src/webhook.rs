@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use reqwest::Client;
+
+use crate::database::SessionRecord;
+use log::warn;
+
+const MAX_ATTEMPTS: u32 = 3;
+
+fn payload(event: &str, session: &SessionRecord) -> serde_json::Value {
+    serde_json::json!({
+        "event": event,
+        "session": {
+            "id": session.id,
+            "title": session.title,
+            "date": session.date,
+            "duration": session.duration,
+            "summary": session.summary,
+        },
+    })
+}
+
+async fn post_with_retry(url: &str, body: &serde_json::Value) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to build webhook client: {}", e))?;
+
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        match client.post(url).json(body).send().await {
+            Ok(resp) if resp.status().is_success() => return Ok(()),
+            Ok(resp) => last_error = format!("HTTP {}", resp.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+        if attempt < MAX_ATTEMPTS {
+            // 1s, then 2s
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+    }
+    Err(format!("Webhook to {} failed after {} attempts: {}", url, MAX_ATTEMPTS, last_error))
+}
+
+/// Fire-and-forget POST of session metadata to the configured webhook.
+/// Errors are only logged so saving and summarizing never wait on it.
+pub fn notify(url: &str, event: &str, session: &SessionRecord) {
+    let url = url.trim();
+    if url.is_empty() {
+        return;
+    }
+    let url = url.to_string();
+    let body = payload(event, session);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = post_with_retry(&url, &body).await {
+            warn!("{}", e);
+        }
+    });
+}
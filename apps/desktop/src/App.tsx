@@ -1,5 +1,6 @@
-import { useState, useEffect } from 'react';
+import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
 import RecorderPanel from './components/RecorderPanel';
 import LiveNotes from './components/LiveNotes';
 import SettingsPanel from './components/SettingsPanel';
@@ -8,6 +9,7 @@ import SessionsHistory from './components/SessionsHistory';
 import { useAudio } from './hooks/useAudio';
 import { Button, Pill } from '@oatmeal/ui';
 import { Settings } from 'lucide-react';
+import { isAppError } from './errors';
 
 export type AppState = 'idle' | 'recording' | 'processing' | 'post-call';
 
@@ -15,11 +17,15 @@ function App() {
   const [appState, setAppState] = useState<AppState>('idle');
   const [showSettings, setShowSettings] = useState(false);
   const [showHistory, setShowHistory] = useState(false);
-  const { isRecording, setIsRecording, transcript, setTranscript, frameCount, resetAudio, startRecording, getRecordingDuration, levels } = useAudio();
+  const { isRecording, setIsRecording, transcript, setTranscript, frameCount, resetAudio, startRecording, getRecordingDuration, levels, droppedChunks, transcriptionLagging } = useAudio();
   const [lastSessionId, setLastSessionId] = useState<string | null>(null);
+  // Chunk length for the next recording only; null uses the setting
+  const [chunkOverride, setChunkOverride] = useState<number | null>(null);
   const [permissionStatus, setPermissionStatus] = useState<'granted' | 'denied' | 'undetermined' | 'unknown'>('unknown');
   const [screenPerm, setScreenPerm] = useState<'granted' | 'denied' | 'unknown'>('unknown');
   const [showPermissionDialog, setShowPermissionDialog] = useState(false);
+  const [deviceNotice, setDeviceNotice] = useState<string | null>(null);
+  const [transcriberReady, setTranscriberReady] = useState(false);
 
   // Enable dark mode by default
   useEffect(() => {
@@ -30,6 +36,21 @@ function App() {
     const initializeApp = async () => {
       try {
         await invoke('initialize_app');
+
+        // Offer to recover recordings that were interrupted before they were saved
+        try {
+          const drafts = await invoke<Array<{ id: string; duration: number }>>('list_recoverable_sessions');
+          if (drafts.length > 0) {
+            const recover = window.confirm(
+              `Oatmeal found ${drafts.length} unsaved recording${drafts.length === 1 ? '' : 's'} from a previous run. Recover ${drafts.length === 1 ? 'it' : 'them'} into your history?`
+            );
+            for (const draft of drafts) {
+              await invoke(recover ? 'recover_session' : 'discard_recoverable_session', { sessionId: draft.id });
+            }
+          }
+        } catch (error) {
+          console.warn('Could not check for unsaved recordings:', error);
+        }
         
         // Check microphone permissions
         try {
@@ -44,6 +65,7 @@ function App() {
         console.log('Initializing local Whisper transcriber...');
         await invoke('initialize_transcriber');
         console.log('Transcriber ready!');
+        setTranscriberReady(true);
 
         // Check Screen Recording (system audio) permission on macOS
         try {
@@ -60,20 +82,28 @@ function App() {
     initializeApp();
   }, []);
 
+  // Prime Whisper while the record screen is up so the first chunk isn't slowed by model setup
+  useEffect(() => {
+    if (appState !== 'idle' || !transcriberReady) return;
+    invoke<boolean>('warm_up_transcriber').catch((e) => console.warn('Transcriber warm-up failed:', e));
+  }, [appState, transcriberReady]);
+
+  // Set once the current recording is being stopped and saved, so it happens only once
+  const stoppingRef = useRef(false);
+
   const handleStartRecording = async () => {
+    stoppingRef.current = false;
     try {
       setIsRecording(true);
       setAppState('recording');
       resetAudio();
-      await startRecording();
+      await startRecording({ chunkSeconds: chunkOverride ?? undefined });
     } catch (error) {
       console.error('Failed to start recording:', error);
       setIsRecording(false);
       setAppState('idle');
       
-      // Check if it's a permission error
-      const errorMessage = error?.toString() || '';
-      if (errorMessage.includes('permission')) {
+      if (isAppError(error) && error.code === 'permission') {
         setShowPermissionDialog(true);
         // Re-check permission status
         try {
@@ -86,9 +116,12 @@ function App() {
     }
   };
 
-  const handleStopRecording = async () => {
+  // `captureStopped`: the backend already stopped capture itself (auto-stop)
+  const handleStopRecording = async ({ captureStopped = false }: { captureStopped?: boolean } = {}) => {
+    if (stoppingRef.current) return;
+    stoppingRef.current = true;
     try {
-      await invoke('stop_recording');
+      if (!captureStopped) await invoke('stop_recording');
       setIsRecording(false);
       setAppState('processing');
       
@@ -113,12 +146,67 @@ function App() {
       setTimeout(() => setAppState('post-call'), 2000);
     } catch (error) {
       console.error('Failed to stop recording:', error);
+      stoppingRef.current = false;
     }
   };
+  // Backend event listeners are registered once; they reach the current handler through this
+  const stopRecordingRef = useRef(handleStopRecording);
+  stopRecordingRef.current = handleStopRecording;
+
+  const handleDiscardRecording = async () => {
+    if (!window.confirm('Discard this recording? Nothing will be saved.')) return;
+    try {
+      await invoke('discard_recording');
+    } catch (error) {
+      console.error('Failed to discard recording:', error);
+    }
+    setIsRecording(false);
+    resetAudio();
+    setAppState('idle');
+  };
+
+  // Input device unplugged mid-recording: the backend either switched devices or gave up
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<{ device: string; recovered: boolean; new_device?: string; capturing: boolean }>('recording:device-lost', (event) => {
+      const { device, recovered, new_device, capturing } = event.payload;
+      if (recovered) {
+        setDeviceNotice(`${device} disconnected — now recording from ${new_device}.`);
+      } else if (capturing) {
+        setDeviceNotice(`${device} disconnected — recording system audio only.`);
+      } else {
+        setDeviceNotice(`${device} disconnected and no other microphone was found. Recording stopped.`);
+        stopRecordingRef.current();
+      }
+    }).then(fn => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  // Recording fell back to mic-only; the hint says how to get the other side captured
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<{ reason: string; hint: string; url: string }>('recording:system-audio-unavailable', (event) => {
+      setDeviceNotice(event.payload.hint);
+    }).then(fn => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  // Backend stopped capture after sustained silence; save the session like a manual stop
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<{ silence_seconds: number }>('recording:auto-stop', (event) => {
+      setDeviceNotice(`Recording stopped after ${Math.round(event.payload.silence_seconds)}s of silence.`);
+      stopRecordingRef.current({ captureStopped: true });
+    }).then(fn => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
 
   const handleQuickNote = async () => {
+    const text = window.prompt('Quick note');
+    if (!text || !text.trim()) return;
     try {
-      await invoke('create_quick_note');
+      // While recording, the backend attaches the note to the session once it's saved
+      await invoke('create_quick_note', { text, sessionId: null });
     } catch (error) {
       console.error('Failed to create quick note:', error);
     }
@@ -243,6 +331,14 @@ function App() {
           </div>
         </div>
       )}
+      {deviceNotice && (
+        <div className="w-full bg-amber-50 dark:bg-amber-900/20 text-amber-800 dark:text-amber-300 border-b border-amber-200/60 dark:border-amber-800/50">
+          <div className="max-w-5xl mx-auto px-4 py-2 text-sm flex items-center justify-between gap-3">
+            <div>{deviceNotice}</div>
+            <Button size="sm" variant="outline" onClick={() => setDeviceNotice(null)}>Dismiss</Button>
+          </div>
+        </div>
+      )}
       {/* Oatmeal Header */}
       <header className="flex items-center justify-between p-6 border-b border-black/10 dark:border-white/10">
         <div className="flex items-center gap-3">
@@ -284,7 +380,12 @@ function App() {
             </Button>
           )}
           {isRecording && (
-            <Button variant="destructive" onClick={handleStopRecording}>
+            <Button variant="outline" onClick={handleDiscardRecording}>
+              Discard
+            </Button>
+          )}
+          {isRecording && (
+            <Button variant="destructive" onClick={() => handleStopRecording()}>
               Stop Recording
             </Button>
           )}
@@ -294,9 +395,24 @@ function App() {
 
       {appState === 'idle' && (
         <div className="flex-1 flex items-center justify-center">
-          <Button onClick={handleStartRecording} size="lg">
-            Start Recording (⌘⇧R)
-          </Button>
+          <div className="flex flex-col items-center gap-3">
+            <Button onClick={handleStartRecording} size="lg">
+              Start Recording (⌘⇧R)
+            </Button>
+            <label className="flex items-center gap-2 text-sm text-muted-foreground">
+              Transcription
+              <select
+                value={chunkOverride ?? ''}
+                onChange={(e) => setChunkOverride(e.target.value ? Number(e.target.value) : null)}
+                className="px-2 py-1 border border-border rounded-md bg-background"
+              >
+                <option value="">Default chunks</option>
+                <option value="1">Low latency (1s)</option>
+                <option value="5">More accurate (5s)</option>
+                <option value="10">Most accurate (10s)</option>
+              </select>
+            </label>
+          </div>
         </div>
       )}
       
@@ -305,8 +421,10 @@ function App() {
           <div className="grid grid-cols-1 md:grid-cols-[24rem_1fr] gap-6">
             <RecorderPanel 
               isRecording={isRecording}
-              onStop={handleStopRecording}
+              onStop={() => handleStopRecording()}
               levels={levels}
+              droppedChunks={droppedChunks}
+              transcriptionLagging={transcriptionLagging}
             />
             <LiveNotes 
               transcript={transcript}
@@ -205,6 +205,19 @@ export default function PostCallScreen({ transcript, sessionId, onClose }: PostC
     console.log('Exporting session as ZIP...');
   };
 
+  // Saved sessions get the backend's paste-ready layout (title, date, action items)
+  const copySummary = async () => {
+    let text = summaryMd;
+    if (sessionId) {
+      try {
+        text = await invoke<string>('get_shareable_summary', { sessionId, format: 'markdown' });
+      } catch (err) {
+        console.error('Failed to format summary:', err);
+      }
+    }
+    await navigator.clipboard.writeText(text);
+  };
+
   return (
     <div className="min-h-screen bg-background p-6">
       <div className="max-w-4xl mx-auto">
@@ -284,7 +297,7 @@ export default function PostCallScreen({ transcript, sessionId, onClose }: PostC
                 <pre className="whitespace-pre-wrap text-sm">{summaryMd}</pre>
               </div>
               <div className="flex gap-2 mt-4">
-                <Button size="sm" variant="outline" onClick={() => navigator.clipboard.writeText(summaryMd)}>
+                <Button size="sm" variant="outline" onClick={copySummary}>
                   <Copy className="w-4 h-4 mr-2" />
                   Copy Summary
                 </Button>
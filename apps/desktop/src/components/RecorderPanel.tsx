@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react';
 import { Button } from '@oatmeal/ui';
-import { Square, Bookmark } from 'lucide-react';
+import { Square, Bookmark, Pause, Play, Save } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/tauri';
 import Waveform from './Waveform';
 
@@ -8,10 +8,14 @@ interface RecorderPanelProps {
   isRecording: boolean;
   onStop: () => void;
   levels?: number[];
+  droppedChunks?: number;
+  transcriptionLagging?: boolean;
 }
 
-export default function RecorderPanel({ isRecording, onStop, levels = [] }: RecorderPanelProps) {
+export default function RecorderPanel({ isRecording, onStop, levels = [], droppedChunks = 0, transcriptionLagging = false }: RecorderPanelProps) {
   const [duration, setDuration] = useState(0);
+  const [paused, setPaused] = useState(false);
+  const [checkpointNote, setCheckpointNote] = useState<string | null>(null);
 
   useEffect(() => {
     let interval: ReturnType<typeof setInterval>;
@@ -31,6 +35,8 @@ export default function RecorderPanel({ isRecording, onStop, levels = [] }: Reco
       interval = setInterval(updateDuration, 1000); // Update every second
     } else {
       setDuration(0);
+      setPaused(false);
+      setCheckpointNote(null);
     }
 
     return () => {
@@ -44,6 +50,25 @@ export default function RecorderPanel({ isRecording, onStop, levels = [] }: Reco
     return `${mins.toString().padStart(2, '0')}:${secs.toString().padStart(2, '0')}`;
   };
 
+  const handleTogglePause = async () => {
+    try {
+      await invoke(paused ? 'resume_recording' : 'pause_recording');
+      setPaused(!paused);
+    } catch (error) {
+      console.error('Failed to toggle pause:', error);
+    }
+  };
+
+  const handleCheckpoint = async () => {
+    try {
+      const sessionId = await invoke<string | null>('checkpoint_session');
+      setCheckpointNote(sessionId ? `Saved at ${formatTime(duration)}` : 'Nothing transcribed yet');
+    } catch (error) {
+      console.error('Failed to save checkpoint:', error);
+      setCheckpointNote('Checkpoint failed');
+    }
+  };
+
   const handleMarkMoment = () => {
     // This will insert a [[MARK]] token into the transcript
     console.log('Mark moment at', formatTime(duration));
@@ -54,8 +79,8 @@ export default function RecorderPanel({ isRecording, onStop, levels = [] }: Reco
   return (
     <div className="bg-card border border-border rounded-2xl p-6 w-96">
       <div className="text-center mb-6">
-        <div className="w-3 h-3 bg-red-500 rounded-full animate-pulse mx-auto mb-2"></div>
-        <p className="text-sm text-muted-foreground">Recording in progress</p>
+        <div className={`w-3 h-3 rounded-full mx-auto mb-2 ${paused ? 'bg-amber-500' : 'bg-red-500 animate-pulse'}`}></div>
+        <p className="text-sm text-muted-foreground">{paused ? 'Recording paused' : 'Recording in progress'}</p>
       </div>
       
       <div className="text-3xl font-mono text-center mb-6">
@@ -63,6 +88,17 @@ export default function RecorderPanel({ isRecording, onStop, levels = [] }: Reco
       </div>
 
       <Waveform levels={levels} className="mb-6" />
+
+      {droppedChunks > 0 && (
+        <p className="text-xs text-amber-600 dark:text-amber-400 text-center -mt-4 mb-4">
+          Your machine is struggling to keep up; some audio was dropped ({droppedChunks} chunks)
+        </p>
+      )}
+      {transcriptionLagging && (
+        <p className="text-xs text-amber-600 dark:text-amber-400 text-center -mt-4 mb-4">
+          Captions are falling behind; try a smaller Whisper model
+        </p>
+      )}
       
       <div className="space-y-3">
         <Button
@@ -74,6 +110,27 @@ export default function RecorderPanel({ isRecording, onStop, levels = [] }: Reco
           Mark Moment
         </Button>
         
+        <Button
+          variant="outline"
+          onClick={handleTogglePause}
+          className="w-full flex items-center gap-2"
+        >
+          {paused ? <Play className="w-4 h-4" /> : <Pause className="w-4 h-4" />}
+          {paused ? 'Resume' : 'Pause'}
+        </Button>
+
+        <Button
+          variant="outline"
+          onClick={handleCheckpoint}
+          className="w-full flex items-center gap-2"
+        >
+          <Save className="w-4 h-4" />
+          Save Checkpoint
+        </Button>
+        {checkpointNote && (
+          <p className="text-xs text-muted-foreground text-center">{checkpointNote}</p>
+        )}
+
         <Button
           variant="destructive"
           onClick={onStop}
@@ -1,7 +1,8 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { Button } from '@oatmeal/ui';
-import { Clock, FileText } from 'lucide-react';
+import { Clock, FileText, Star } from 'lucide-react';
+import { errorMessage } from '../errors';
 
 interface SessionRecord {
   id: string;
@@ -10,12 +11,41 @@ interface SessionRecord {
   duration: number;
   transcript?: string;
   summary?: string;
-  artifacts?: string;
+  artifacts?: Artifacts;
   folder_id?: string;
+  audio_path?: string;
+  summary_engine?: string;
+  summary_model?: string;
+  transcription_model?: string;
+  language?: string;
+  translated?: boolean;
+  chapters?: string;
+  is_favorite?: boolean;
+  notes?: string;
+  deleted_at?: string;
+  is_draft?: boolean;
   created_at: string;
   updated_at: string;
 }
 
+interface ActionItem {
+  text: string;
+  owner?: string;
+  due?: string;
+}
+
+interface Artifacts {
+  action_items: ActionItem[];
+  decisions: string[];
+  questions: string[];
+}
+
+interface Chapter {
+  start_ms: number;
+  end_ms: number;
+  title: string;
+}
+
 interface SessionsHistoryProps {
   onClose: () => void;
 }
@@ -25,6 +55,9 @@ export default function SessionsHistory({ onClose }: SessionsHistoryProps) {
   const [selectedSession, setSelectedSession] = useState<SessionRecord | null>(null);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [editingTranscript, setEditingTranscript] = useState<string | null>(null);
+  const [chaptersStatus, setChaptersStatus] = useState<string | null>(null);
+  const [summaryStatus, setSummaryStatus] = useState<string | null>(null);
 
   useEffect(() => {
     loadSessions();
@@ -33,16 +66,108 @@ export default function SessionsHistory({ onClose }: SessionsHistoryProps) {
   const loadSessions = async () => {
     try {
       setLoading(true);
-      const sessionList = await invoke<SessionRecord[]>('list_sessions', { limit: 20 });
+      const sessionList = await invoke<SessionRecord[]>('list_sessions', { limit: 20, favoritesFirst: true });
       setSessions(sessionList);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       console.error('Failed to load sessions:', err);
     } finally {
       setLoading(false);
     }
   };
 
+  const saveTranscript = async () => {
+    if (!selectedSession || editingTranscript === null) return;
+    try {
+      await invoke('edit_transcript', { sessionId: selectedSession.id, transcript: editingTranscript });
+      const updated = { ...selectedSession, transcript: editingTranscript };
+      setSelectedSession(updated);
+      setSessions(prev => prev.map(s => (s.id === updated.id ? updated : s)));
+      setEditingTranscript(null);
+    } catch (err) {
+      console.error('Failed to save transcript:', err);
+    }
+  };
+
+  const saveNotes = async (notes: string) => {
+    if (!selectedSession || notes === (selectedSession.notes ?? '')) return;
+    try {
+      await invoke('update_session_notes', { sessionId: selectedSession.id, notes });
+      const updated = { ...selectedSession, notes: notes.trim() ? notes : undefined };
+      setSelectedSession(updated);
+      setSessions(prev => prev.map(s => (s.id === updated.id ? updated : s)));
+    } catch (err) {
+      console.error('Failed to save notes:', err);
+    }
+  };
+
+  const toggleFavorite = async (session: SessionRecord) => {
+    const isFavorite = !session.is_favorite;
+    try {
+      await invoke('set_session_favorite', { sessionId: session.id, isFavorite });
+      const updated = { ...session, is_favorite: isFavorite };
+      setSelectedSession(prev => (prev?.id === updated.id ? updated : prev));
+      // Re-sort so favorites stay on top
+      setSessions(prev =>
+        prev
+          .map(s => (s.id === updated.id ? updated : s))
+          .sort((a, b) => Number(!!b.is_favorite) - Number(!!a.is_favorite) || b.created_at.localeCompare(a.created_at))
+      );
+    } catch (err) {
+      console.error('Failed to update favorite:', err);
+    }
+  };
+
+  const generateChapters = async () => {
+    if (!selectedSession) return;
+    setChaptersStatus('Generating…');
+    try {
+      const chapters = await invoke<Chapter[]>('generate_chapters', { sessionId: selectedSession.id });
+      const updated = { ...selectedSession, chapters: JSON.stringify(chapters) };
+      setSelectedSession(updated);
+      setSessions(prev => prev.map(s => (s.id === updated.id ? updated : s)));
+      setChaptersStatus(null);
+    } catch (err) {
+      setChaptersStatus(errorMessage(err));
+    }
+  };
+
+  const regenerateSummary = async () => {
+    if (!selectedSession) return;
+    setSummaryStatus('Summarizing…');
+    try {
+      await invoke<string>('regenerate_summary', { sessionId: selectedSession.id });
+      // Reload to pick up the new engine/model metadata along with the summary
+      const updated = await invoke<SessionRecord | null>('get_session', { sessionId: selectedSession.id });
+      if (updated) {
+        setSelectedSession(updated);
+        setSessions(prev => prev.map(s => (s.id === updated.id ? updated : s)));
+      }
+      setSummaryStatus(null);
+    } catch (err) {
+      setSummaryStatus(errorMessage(err));
+    }
+  };
+
+  const addToReminders = async () => {
+    if (!selectedSession) return;
+    setSummaryStatus('Adding to Reminders…');
+    try {
+      const added = await invoke<number>('export_action_items_to_reminders', { sessionId: selectedSession.id });
+      setSummaryStatus(`Added ${added} reminder${added === 1 ? '' : 's'}`);
+    } catch (err) {
+      setSummaryStatus(errorMessage(err));
+    }
+  };
+
+  const parseChapters = (session: SessionRecord): Chapter[] => {
+    try {
+      return session.chapters ? JSON.parse(session.chapters) : [];
+    } catch {
+      return [];
+    }
+  };
+
   const formatDuration = (seconds: number) => {
     const minutes = Math.floor(seconds / 60);
     const remainingSeconds = seconds % 60;
@@ -115,10 +240,19 @@ export default function SessionsHistory({ onClose }: SessionsHistoryProps) {
                         ? 'bg-primary/10 border-primary'
                         : 'hover:bg-muted/50'
                     }`}
-                    onClick={() => setSelectedSession(session)}
+                    onClick={() => { setSelectedSession(session); setEditingTranscript(null); }}
                   >
                     <div className="flex items-center justify-between">
-                      <h3 className="font-medium truncate">{session.title}</h3>
+                      <div className="flex items-center gap-2 min-w-0">
+                        <button
+                          className="text-muted-foreground hover:text-foreground"
+                          title={session.is_favorite ? 'Remove from favorites' : 'Add to favorites'}
+                          onClick={(e) => { e.stopPropagation(); toggleFavorite(session); }}
+                        >
+                          <Star size={14} fill={session.is_favorite ? 'currentColor' : 'none'} />
+                        </button>
+                        <h3 className="font-medium truncate">{session.title}</h3>
+                      </div>
                       <div className="flex items-center gap-2 text-sm text-muted-foreground">
                         <Clock size={14} />
                         {formatDuration(session.duration)}
@@ -146,23 +280,112 @@ export default function SessionsHistory({ onClose }: SessionsHistoryProps) {
                   <div className="space-y-2 text-sm text-muted-foreground mb-4">
                     <div>Duration: {formatDuration(selectedSession.duration)}</div>
                     <div>Date: {formatDate(selectedSession.created_at)}</div>
+                    {selectedSession.language && <div>Language: {selectedSession.language}{selectedSession.translated ? ' (translated to English)' : ''}</div>}
+                    {selectedSession.audio_path && (
+                      <div>
+                        <Button
+                          variant="ghost"
+                          size="sm"
+                          onClick={() => invoke('reveal_audio_in_file_manager', { sessionId: selectedSession.id }).catch((err) => setError(errorMessage(err)))}
+                        >
+                          Show audio file
+                        </Button>
+                      </div>
+                    )}
                   </div>
                   
+                  <div className="space-y-2 mb-4">
+                    <h4 className="font-medium">My notes</h4>
+                    <textarea
+                      key={selectedSession.id}
+                      defaultValue={selectedSession.notes ?? ''}
+                      onBlur={(e) => saveNotes(e.target.value)}
+                      placeholder="Takeaways, follow-ups, anything to remember"
+                      className="w-full h-24 p-3 rounded text-sm border border-border bg-background"
+                    />
+                  </div>
+
+                  {selectedSession.transcript && (
+                    <div className="space-y-2 mb-4">
+                      <div className="flex items-center justify-between">
+                        <h4 className="font-medium">Chapters</h4>
+                        <Button variant="ghost" size="sm" onClick={generateChapters} disabled={chaptersStatus === 'Generating…'}>
+                          {parseChapters(selectedSession).length ? 'Regenerate' : 'Generate'}
+                        </Button>
+                      </div>
+                      {chaptersStatus && <p className="text-xs text-muted-foreground">{chaptersStatus}</p>}
+                      <ol className="text-sm space-y-1">
+                        {parseChapters(selectedSession).map((chapter) => (
+                          <li key={chapter.start_ms} className="flex gap-2">
+                            <span className="font-mono text-muted-foreground">{formatDuration(Math.floor(chapter.start_ms / 1000))}</span>
+                            <span>{chapter.title}</span>
+                          </li>
+                        ))}
+                      </ol>
+                    </div>
+                  )}
+
                   {selectedSession.transcript && (
                     <div className="space-y-2">
-                      <h4 className="font-medium">Transcript</h4>
-                      <div className="bg-muted/30 p-3 rounded text-sm max-h-64 overflow-y-auto whitespace-pre-wrap">
-                        {selectedSession.transcript}
+                      <div className="flex items-center justify-between">
+                        <h4 className="font-medium">Transcript</h4>
+                        {editingTranscript === null ? (
+                          <Button variant="ghost" size="sm" onClick={() => setEditingTranscript(selectedSession.transcript ?? '')}>
+                            Edit
+                          </Button>
+                        ) : (
+                          <div className="flex gap-2">
+                            <Button variant="ghost" size="sm" onClick={() => setEditingTranscript(null)}>
+                              Cancel
+                            </Button>
+                            <Button size="sm" onClick={saveTranscript}>
+                              Save
+                            </Button>
+                          </div>
+                        )}
                       </div>
+                      {editingTranscript === null ? (
+                        <div className="bg-muted/30 p-3 rounded text-sm max-h-64 overflow-y-auto whitespace-pre-wrap">
+                          {selectedSession.transcript}
+                        </div>
+                      ) : (
+                        <textarea
+                          value={editingTranscript}
+                          onChange={(e) => setEditingTranscript(e.target.value)}
+                          className="w-full h-64 p-3 rounded text-sm border border-border bg-background"
+                        />
+                      )}
                     </div>
                   )}
                   
-                  {selectedSession.summary && (
+                  {(selectedSession.summary || selectedSession.transcript) && (
                     <div className="space-y-2 mt-4">
-                      <h4 className="font-medium">Summary</h4>
-                      <div className="bg-muted/30 p-3 rounded text-sm">
-                        {selectedSession.summary}
+                      <div className="flex items-center justify-between">
+                        <h4 className="font-medium">Summary</h4>
+                        <div className="flex items-center gap-1">
+                          {selectedSession.summary && (
+                            <Button variant="ghost" size="sm" onClick={addToReminders} disabled={summaryStatus === 'Adding to Reminders…'}>
+                              Add to Reminders
+                            </Button>
+                          )}
+                          {selectedSession.transcript && (
+                            <Button variant="ghost" size="sm" onClick={regenerateSummary} disabled={summaryStatus === 'Summarizing…'}>
+                              {selectedSession.summary ? 'Regenerate' : 'Generate'}
+                            </Button>
+                          )}
+                        </div>
                       </div>
+                      {summaryStatus && <p className="text-xs text-muted-foreground">{summaryStatus}</p>}
+                      {selectedSession.summary && (
+                        <div className="bg-muted/30 p-3 rounded text-sm">
+                          {selectedSession.summary}
+                        </div>
+                      )}
+                      {selectedSession.summary_engine && (
+                        <p className="text-xs text-muted-foreground">
+                          Summarized with {selectedSession.summary_engine}{selectedSession.summary_model ? ` (${selectedSession.summary_model})` : ''}
+                        </p>
+                      )}
                     </div>
                   )}
                 </div>
@@ -1,8 +1,10 @@
 import { useEffect, useState } from 'react';
 import { emit } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/tauri';
 import { Button } from '@oatmeal/ui';
 import { X, Cpu, Bot } from 'lucide-react';
 import { useSettings, type Settings as BackendSettings } from '../hooks/useSettings';
+import { errorMessage } from '../errors';
 
 interface SettingsPanelProps {
   onClose: () => void;
@@ -14,6 +16,41 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
   const [savedFlash, setSavedFlash] = useState(false);
   const [initialized, setInitialized] = useState(false);
   const [dirty, setDirty] = useState(false);
+  const [usage, setUsage] = useState<{ input_tokens: number; output_tokens: number; estimated_cost_usd: number } | null>(null);
+  const [indexStatus, setIndexStatus] = useState<string | null>(null);
+  const [whisperModels, setWhisperModels] = useState<{ available: string[]; active: string | null; error?: string } | null>(null);
+  const [capturableApps, setCapturableApps] = useState<{ bundle_id: string; name: string }[] | null>(null);
+  const [micCheck, setMicCheck] = useState<{ running: boolean; result?: string } | null>(null);
+  const [audioCheck, setAudioCheck] = useState<{ running: boolean; result?: string } | null>(null);
+  const [transcriptionCheck, setTranscriptionCheck] = useState<{ running: boolean; result?: string } | null>(null);
+  const [inputForgotten, setInputForgotten] = useState<string | null>(null);
+  const [inputDevices, setInputDevices] = useState<string[]>([]);
+  const [ollamaStatus, setOllamaStatus] = useState<{ reachable: boolean; model_available: boolean; available_models: string[]; error?: string | null } | null>(null);
+
+  useEffect(() => {
+    invoke<{ input_tokens: number; output_tokens: number; estimated_cost_usd: number }>('get_usage_summary')
+      .then(setUsage)
+      .catch((e) => console.error('Failed to load usage', e));
+  }, []);
+
+  useEffect(() => {
+    Promise.all([invoke<string[]>('list_available_models'), invoke<string | null>('get_active_model')])
+      .then(([available, active]) => setWhisperModels({ available, active }))
+      .catch((e) => console.error('Failed to list Whisper models', e));
+  }, []);
+
+  useEffect(() => {
+    // Errors on platforms without per-app capture; the setting stays hidden there
+    invoke<{ bundle_id: string; name: string }[]>('list_capturable_apps')
+      .then(setCapturableApps)
+      .catch(() => setCapturableApps(null));
+  }, []);
+
+  useEffect(() => {
+    invoke<string[]>('list_input_devices')
+      .then(setInputDevices)
+      .catch((e) => console.error('Failed to list input devices', e));
+  }, []);
 
   const normalize = (s: BackendSettings): BackendSettings => ({
     ...s,
@@ -121,6 +158,406 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
                 />
               </div>
 
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Live transcription</p>
+                  <p className="text-sm text-muted-foreground">"Off" only records audio, to re-transcribe later with a larger model</p>
+                </div>
+                <select
+                  value={draft?.transcription_engine ?? 'local'}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), transcription_engine: e.target.value as any })), setDirty(true))}
+                  className="px-3 py-1 border border-border rounded-md bg-background"
+                  disabled={!draft}
+                >
+                  <option value="local">Local Whisper</option>
+                  <option value="none">Off (record only)</option>
+                </select>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Transcription quality</p>
+                  <p className="text-sm text-muted-foreground">Fast keeps captions snappy on slower Macs; Accurate uses beam search and earlier context but lags more</p>
+                </div>
+                <select
+                  value={draft?.transcription_preset ?? 'balanced'}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), transcription_preset: (e.target as HTMLSelectElement).value as BackendSettings['transcription_preset'] })), setDirty(true))}
+                  className="px-3 py-1 border border-border rounded-md bg-background"
+                  disabled={!draft}
+                >
+                  <option value="fast">Fast</option>
+                  <option value="balanced">Balanced</option>
+                  <option value="accurate">Accurate</option>
+                </select>
+              </div>
+
+              <div>
+                <p className="font-medium">Advanced decoding</p>
+                <p className="text-sm text-muted-foreground">Override the quality preset when diagnosing hallucinations or dropped speech. Temperature adds randomness to decoding; text above the entropy threshold or below the log-probability threshold is retried. Leave empty to use the preset's values.</p>
+                <div className="mt-2 grid grid-cols-2 gap-2">
+                  {([
+                    ['whisper_temperature', 'Temperature', 0, 1, 0.1],
+                    ['whisper_entropy_thold', 'Entropy threshold', 0, 10, 0.1],
+                    ['whisper_logprob_thold', 'Log-prob threshold', -5, 0, 0.1],
+                  ] as const).map(([key, label, min, max, step]) => (
+                    <label key={key} className="flex items-center justify-between text-sm">
+                      <span>{label}</span>
+                      <input
+                        type="number"
+                        min={min}
+                        max={max}
+                        step={step}
+                        value={draft?.[key] ?? ''}
+                        placeholder="preset"
+                        onChange={(e) => {
+                          const raw = (e.target as HTMLInputElement).valueAsNumber;
+                          if (!draft) return;
+                          const val = Number.isNaN(raw) ? null : Math.max(min, Math.min(max, raw));
+                          setDraft(prev => ({ ...(prev as BackendSettings), [key]: val }));
+                          setDirty(true);
+                        }}
+                        className="w-24 px-3 py-1 border border-border rounded-md bg-background text-right"
+                        disabled={!draft}
+                      />
+                    </label>
+                  ))}
+                </div>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Transcription language</p>
+                  <p className="text-sm text-muted-foreground">Whisper language code (e.g. en, es), or "auto" to detect. Needs a multilingual model.</p>
+                </div>
+                <input
+                  type="text"
+                  value={draft?.language ?? 'en'}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), language: e.target.value.trim() })), setDirty(true))}
+                  className="w-24 px-3 py-1 border border-border rounded-md bg-background text-right"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Translate to English</p>
+                  <p className="text-sm text-muted-foreground">Write the transcript in English whatever language is spoken. Needs a multilingual model.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.translate_to_english}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), translate_to_english: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Low-power mode</p>
+                  <p className="text-sm text-muted-foreground">Save battery while transcribing: smallest downloaded model, CPU only, fewer threads and longer chunks. Applies from the next recording.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.low_power_mode}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), low_power_mode: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Input gain</p>
+                  <p className="text-sm text-muted-foreground">Boost quiet voices before transcription (1.0 = unchanged)</p>
+                </div>
+                <input
+                  type="number"
+                  min={0.5}
+                  max={4}
+                  step={0.25}
+                  value={draft?.input_gain ?? 1}
+                  onChange={(e) => {
+                    const raw = (e.target as HTMLInputElement).valueAsNumber;
+                    if (!draft) return;
+                    if (Number.isNaN(raw)) return; // ignore transient empty state
+                    const val = Math.max(0.5, Math.min(4, raw));
+                    setDraft(prev => ({ ...(prev as BackendSettings), input_gain: val }));
+                    setDirty(true);
+                  }}
+                  className="w-24 px-3 py-1 border border-border rounded-md bg-background text-right"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Stop after silence</p>
+                  <p className="text-sm text-muted-foreground">Seconds of continuous silence before recording stops on its own (0 = never, minimum 15)</p>
+                </div>
+                <input
+                  type="number"
+                  min={0}
+                  max={3600}
+                  step={15}
+                  value={draft?.auto_stop_silence_seconds ?? 0}
+                  onChange={(e) => {
+                    const raw = (e.target as HTMLInputElement).valueAsNumber;
+                    if (!draft) return;
+                    if (Number.isNaN(raw)) return; // ignore transient empty state
+                    const val = Math.max(0, Math.min(3600, raw));
+                    setDraft(prev => ({ ...(prev as BackendSettings), auto_stop_silence_seconds: val }));
+                    setDirty(true);
+                  }}
+                  className="w-24 px-3 py-1 border border-border rounded-md bg-background text-right"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Minimum audio length</p>
+                  <p className="text-sm text-muted-foreground">Live audio shorter than this many ms is skipped; Whisper tends to invent words on tiny fragments</p>
+                </div>
+                <input
+                  type="number"
+                  min={0}
+                  max={2000}
+                  step={50}
+                  value={draft?.min_transcribe_ms ?? 300}
+                  onChange={(e) => {
+                    const raw = (e.target as HTMLInputElement).valueAsNumber;
+                    if (!draft) return;
+                    if (Number.isNaN(raw)) return; // ignore transient empty state
+                    const val = Math.round(Math.max(0, Math.min(2000, raw)));
+                    setDraft(prev => ({ ...(prev as BackendSettings), min_transcribe_ms: val }));
+                    setDirty(true);
+                  }}
+                  className="w-24 px-3 py-1 border border-border rounded-md bg-background text-right"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div>
+                <p className="font-medium">Mixed audio processing</p>
+                <p className="text-sm text-muted-foreground">Levels, noise gate, compressor and limiter used when mixing mic and system audio. Raise the mic level if call audio drowns out your voice; defaults match the original behavior.</p>
+                <div className="mt-2 grid grid-cols-2 gap-2">
+                  {([
+                    ['mic_mix_level', 'Mic level', 0, 2, 0.1],
+                    ['system_mix_level', 'System level', 0, 2, 0.1],
+                    ['audio_gate_threshold', 'Gate threshold', 0, 0.1, 0.001],
+                    ['audio_attack_ms', 'Attack (ms)', 0, 1000, 5],
+                    ['audio_release_ms', 'Release (ms)', 0, 5000, 50],
+                    ['audio_makeup_gain', 'Makeup gain', 0.25, 4, 0.25],
+                    ['audio_limiter_ceiling', 'Limiter ceiling', 0.5, 1, 0.01],
+                  ] as const).map(([key, label, min, max, step]) => (
+                    <label key={key} className="flex items-center justify-between text-sm">
+                      <span>{label}</span>
+                      <input
+                        type="number"
+                        min={min}
+                        max={max}
+                        step={step}
+                        value={draft?.[key] ?? ''}
+                        onChange={(e) => {
+                          const raw = (e.target as HTMLInputElement).valueAsNumber;
+                          if (!draft || Number.isNaN(raw)) return;
+                          const val = Math.max(min, Math.min(max, raw));
+                          setDraft(prev => ({ ...(prev as BackendSettings), [key]: val }));
+                          setDirty(true);
+                        }}
+                        className="w-24 px-3 py-1 border border-border rounded-md bg-background text-right"
+                        disabled={!draft}
+                      />
+                    </label>
+                  ))}
+                  <label className="flex items-center justify-between text-sm">
+                    <span>Auto gain</span>
+                    <input
+                      type="checkbox"
+                      checked={!!draft?.mix_auto_gain}
+                      onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), mix_auto_gain: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                      className="h-4 w-4"
+                      disabled={!draft}
+                    />
+                  </label>
+                </div>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Whisper model</p>
+                  <p className="text-sm text-muted-foreground">
+                    {whisperModels?.error ?? 'Larger models are more accurate but slower. Takes effect immediately.'}
+                  </p>
+                </div>
+                <select
+                  value={whisperModels?.active ?? ''}
+                  onChange={async (e) => {
+                    const name = (e.target as HTMLSelectElement).value;
+                    try {
+                      const active = await invoke<string>('set_active_model', { name });
+                      setWhisperModels(prev => ({ available: prev?.available ?? [], active }));
+                    } catch (err) {
+                      setWhisperModels(prev => ({ available: prev?.available ?? [], active: prev?.active ?? null, error: errorMessage(err) }));
+                    }
+                  }}
+                  className="px-3 py-1 border border-border rounded-md bg-background"
+                  disabled={!whisperModels || whisperModels.available.length === 0}
+                >
+                  {!whisperModels?.active && <option value="">Not loaded</option>}
+                  {whisperModels?.available.map((m) => (
+                    <option key={m} value={m}>{m}</option>
+                  ))}
+                </select>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Write numbers as digits</p>
+                  <p className="text-sm text-muted-foreground">"Three hundred dollars" becomes "$300" in live transcripts. English only; may occasionally misfire.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.normalize_numbers}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), normalize_numbers: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Word confidence for captions</p>
+                  <p className="text-sm text-muted-foreground">Include how sure Whisper is about each word in live transcript events, so captions can dim uncertain words. Adds a little work per chunk.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.live_confidence}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), live_confidence: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Catch up when transcription falls behind</p>
+                  <p className="text-sm text-muted-foreground">If Whisper can't keep up, transcribe several chunks at once and skip quiet ones until captions are back to real time.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.adaptive_chunking}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), adaptive_chunking: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Mic check</p>
+                  <p className="text-sm text-muted-foreground">
+                    {micCheck?.result ?? 'Record 3 seconds from your microphone and show the levels'}
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="outline"
+                  disabled={micCheck?.running}
+                  onClick={async () => {
+                    setMicCheck({ running: true, result: 'Listening… say something' });
+                    try {
+                      const r = await invoke<{ device_name: string; peak: number; rms: number; clipped_samples: number }>('mic_check', { seconds: 3 });
+                      const verdict = r.peak < 0.02 ? 'very quiet — check the device' : r.clipped_samples > 0 ? 'clipping — lower the gain' : 'sounds good';
+                      setMicCheck({ running: false, result: `${r.device_name}: peak ${r.peak.toFixed(2)}, level ${r.rms.toFixed(3)} (${verdict})` });
+                    } catch (e) {
+                      setMicCheck({ running: false, result: errorMessage(e) });
+                    }
+                  }}
+                >
+                  {micCheck?.running ? 'Testing…' : 'Test mic'}
+                </Button>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Transcription check</p>
+                  <p className="text-sm text-muted-foreground">
+                    {transcriptionCheck?.result ?? 'Run Whisper on a sample clip to check the model works, without the mic'}
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="outline"
+                  disabled={transcriptionCheck?.running}
+                  onClick={async () => {
+                    setTranscriptionCheck({ running: true, result: 'Transcribing sample…' });
+                    try {
+                      const r = await invoke<{ model: string | null; text: string; timing: { audio_ms: number; process_ms: number; rtf: number } | null }>('test_transcription');
+                      const speed = r.timing ? ` in ${r.timing.process_ms} ms (${r.timing.rtf.toFixed(2)}× real time)` : '';
+                      setTranscriptionCheck({ running: false, result: `${r.model ?? 'Whisper'}: "${r.text}"${speed}` });
+                    } catch (e) {
+                      setTranscriptionCheck({ running: false, result: errorMessage(e) });
+                    }
+                  }}
+                >
+                  {transcriptionCheck?.running ? 'Testing…' : 'Test transcription'}
+                </Button>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Pre-call check</p>
+                  <p className="text-sm text-muted-foreground whitespace-pre-line">
+                    {audioCheck?.result ?? 'Listen to the mic and system audio together for 2 seconds. Play something and talk.'}
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="outline"
+                  disabled={audioCheck?.running}
+                  onClick={async () => {
+                    setAudioCheck({ running: true, result: 'Listening… play some audio and say something' });
+                    type Levels = { peak: number; rms: number };
+                    const describe = (levels: Levels | null, error: string | null) =>
+                      levels ? `peak ${levels.peak.toFixed(2)}, level ${levels.rms.toFixed(3)}${levels.peak < 0.02 ? ' (silent)' : ''}` : (error ?? 'not checked');
+                    try {
+                      const r = await invoke<{ mic: Levels | null; mic_error: string | null; system: Levels | null; system_error: string | null; system_supported: boolean }>('diagnose_audio', { seconds: 2 });
+                      const system = r.system_supported ? describe(r.system, r.system_error) : 'not supported on this platform';
+                      setAudioCheck({ running: false, result: `Mic: ${describe(r.mic, r.mic_error)}\nSystem audio: ${system}` });
+                    } catch (e) {
+                      setAudioCheck({ running: false, result: errorMessage(e) });
+                    }
+                  }}
+                >
+                  {audioCheck?.running ? 'Checking…' : 'Check audio'}
+                </Button>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Remembered input device</p>
+                  <p className="text-sm text-muted-foreground">
+                    {inputForgotten ?? 'Recordings reuse the input device from the last one while it is connected'}
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="outline"
+                  onClick={async () => {
+                    try {
+                      const forgot = await invoke<boolean>('forget_input_device');
+                      setInputForgotten(forgot ? 'Forgotten; the next recording picks a device from scratch' : 'No device was remembered');
+                    } catch (e) {
+                      setInputForgotten(errorMessage(e));
+                    }
+                  }}
+                >
+                  Forget
+                </Button>
+              </div>
+
               <div className="flex items-center justify-between">
                 <div>
                   <p className="font-medium">Force microphone input</p>
@@ -135,6 +572,294 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
                 />
               </div>
 
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Prefer built-in mic with headphones</p>
+                  <p className="text-sm text-muted-foreground">When capturing system audio, record your voice from the built-in mic so AirPods and other Bluetooth headphones keep full audio quality. The built-in mic picks up more room noise.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.prefer_builtin_mic}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), prefer_builtin_mic: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              {capturableApps && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">System audio source</p>
+                    <p className="text-sm text-muted-foreground">Record only your meeting app so music and notifications stay out of the transcript</p>
+                  </div>
+                  <select
+                    value={draft?.system_audio_app ?? ''}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), system_audio_app: (e.target as HTMLSelectElement).value })), setDirty(true))}
+                    className="px-3 py-1 border border-border rounded-md bg-background max-w-[14rem]"
+                    disabled={!draft}
+                  >
+                    <option value="">All system audio</option>
+                    {draft?.system_audio_app && !capturableApps.some((a) => a.bundle_id === draft.system_audio_app) && (
+                      <option value={draft.system_audio_app}>{draft.system_audio_app} (not running)</option>
+                    )}
+                    {capturableApps.map((a) => (
+                      <option key={a.bundle_id} value={a.bundle_id}>{a.name}</option>
+                    ))}
+                  </select>
+                </div>
+              )}
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Compact audio frames</p>
+                  <p className="text-sm text-muted-foreground">Send raw samples as base64 instead of JSON arrays (lower CPU in long meetings)</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={draft?.audio_frame_encoding === 'base64'}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), audio_frame_encoding: (e.target as HTMLInputElement).checked ? 'base64' : 'json' })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Capture mode</p>
+                  <p className="text-sm text-muted-foreground">Transcribe your mic and call audio separately to tag "Me" vs "Them", or record two inputs as separate tracks</p>
+                </div>
+                <select
+                  value={draft?.capture_mode ?? 'mixed'}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), capture_mode: (e.target as HTMLSelectElement).value as BackendSettings['capture_mode'] })), setDirty(true))}
+                  className="px-3 py-1 border border-border rounded-md bg-background"
+                  disabled={!draft}
+                >
+                  <option value="mixed">Mixed</option>
+                  <option value="separate">Label speakers</option>
+                  <option value="tracks">Two tracks</option>
+                </select>
+              </div>
+
+              {draft?.capture_mode === 'tracks' && (['track1_device', 'track2_device'] as const).map((key, i) => (
+                <div key={key} className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">Track {i + 1} input</p>
+                    <p className="text-sm text-muted-foreground">{i === 0 ? 'Required: the first microphone or interface' : 'Required: the second microphone or interface'}</p>
+                  </div>
+                  <select
+                    value={draft[key] ?? ''}
+                    onChange={(e) => (setDraft(prev => ({ ...(prev as BackendSettings), [key]: (e.target as HTMLSelectElement).value })), setDirty(true))}
+                    className="px-3 py-1 border border-border rounded-md bg-background max-w-[14rem]"
+                  >
+                    <option value="">Choose an input</option>
+                    {draft[key] && !inputDevices.includes(draft[key]) && (
+                      <option value={draft[key]}>{draft[key]} (not connected)</option>
+                    )}
+                    {inputDevices.map((name) => (
+                      <option key={name} value={name}>{name}</option>
+                    ))}
+                  </select>
+                </div>
+              ))}
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Speaker diarization (experimental)</p>
+                  <p className="text-sm text-muted-foreground">Group in-room voices into S1/S2. Adds ~10 ms of CPU per transcribed chunk</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.enable_diarization}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), enable_diarization: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Save audio</p>
+                  <p className="text-sm text-muted-foreground">Keep a WAV of each recording so it can be re-transcribed with a larger model</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.save_audio}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), save_audio: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Save debug audio</p>
+                  <p className="text-sm text-muted-foreground">Also keep the raw microphone and the exact 16 kHz audio Whisper received, to tell bad audio from bad transcription. Uses extra disk space.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.debug_save_audio}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), debug_save_audio: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Encrypt database</p>
+                  <p className="text-sm text-muted-foreground">Encrypt sessions at rest with a key kept in your system keychain. If that key is lost, your sessions cannot be recovered.</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.encrypt_database}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), encrypt_database: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Redact personal details</p>
+                  <p className="text-sm text-muted-foreground">Replace emails, phone numbers and card numbers in saved transcripts with [REDACTED]</p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!draft?.redact_transcript}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), redact_transcript: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                  className="h-4 w-4"
+                  disabled={!draft}
+                />
+              </div>
+
+              {draft?.redact_transcript && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">Keep unredacted copy</p>
+                    <p className="text-sm text-muted-foreground">Also store the original transcript on this machine. Leave off if the originals must not be retained.</p>
+                  </div>
+                  <input
+                    type="checkbox"
+                    checked={!!draft?.keep_raw_transcript}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), keep_raw_transcript: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                    className="h-4 w-4"
+                    disabled={!draft}
+                  />
+                </div>
+              )}
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Local API</p>
+                  <p className="text-sm text-muted-foreground">Serve sessions on 127.0.0.1 for scripts and other local tools (token required)</p>
+                </div>
+                <div className="flex items-center gap-2">
+                  <input
+                    type="number"
+                    min={1024}
+                    max={65535}
+                    value={draft?.api_port ?? 8765}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), api_port: Number((e.target as HTMLInputElement).value) })), setDirty(true))}
+                    className="w-24 px-2 py-1 rounded-md border border-border bg-background text-sm"
+                    disabled={!draft}
+                  />
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={async () => {
+                      try {
+                        const token = await invoke<string>('get_api_token');
+                        await navigator.clipboard.writeText(token);
+                      } catch (e) {
+                        console.error('Failed to copy API token:', e);
+                      }
+                    }}
+                  >
+                    Copy token
+                  </Button>
+                  <input
+                    type="checkbox"
+                    checked={!!draft?.api_enabled}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), api_enabled: (e.target as HTMLInputElement).checked })), setDirty(true))}
+                    className="h-4 w-4"
+                    disabled={!draft}
+                  />
+                </div>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Webhook URL</p>
+                  <p className="text-sm text-muted-foreground">POST session details here when a session is saved or summarized</p>
+                </div>
+                <input
+                  type="text"
+                  placeholder="https://"
+                  value={draft?.webhook_url ?? ''}
+                  onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), webhook_url: e.target.value })), setDirty(true))}
+                  className="w-72 px-3 py-1 border border-border rounded-md bg-background"
+                  disabled={!draft}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Logging</p>
+                  <p className="text-sm text-muted-foreground">How much goes to the log file. Use Debug or Trace while reproducing a problem, then attach the file to your report.</p>
+                </div>
+                <div className="flex items-center gap-2">
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={async () => {
+                      try {
+                        await navigator.clipboard.writeText(await invoke<string>('get_log_path'));
+                      } catch (e) {
+                        window.alert(errorMessage(e));
+                      }
+                    }}
+                  >
+                    Copy log path
+                  </Button>
+                  <select
+                    value={draft?.log_level ?? 'info'}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), log_level: e.target.value })), setDirty(true))}
+                    className="px-3 py-1 border border-border rounded-md bg-background"
+                    disabled={!draft}
+                  >
+                    <option value="error">Errors</option>
+                    <option value="warn">Warnings</option>
+                    <option value="info">Info</option>
+                    <option value="debug">Debug</option>
+                    <option value="trace">Trace</option>
+                  </select>
+                </div>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Erase all data</p>
+                  <p className="text-sm text-muted-foreground">Delete every session, note, setting and saved recording. This can't be undone.</p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="outline"
+                  onClick={async () => {
+                    if (!window.confirm('Delete every session, setting and recording? This cannot be undone.')) return;
+                    try {
+                      const removed = await invoke<number>('reset_database', { confirm: true });
+                      window.alert(`Removed ${removed} session${removed === 1 ? '' : 's'}.`);
+                      setDirty(false);
+                      await reloadSettings();
+                    } catch (e) {
+                      window.alert(errorMessage(e));
+                    }
+                  }}
+                >
+                  Erase…
+                </Button>
+              </div>
+
             </div>
           </section>
 
@@ -162,6 +887,143 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
                 </select>
               </div>
 
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Semantic search</p>
+                  <p className="text-sm text-muted-foreground">
+                    {indexStatus ?? 'Embed transcripts so search finds meetings by meaning, not just exact words'}
+                  </p>
+                </div>
+                <div className="flex items-center gap-2">
+                  <select
+                    value={draft?.embedding_engine ?? 'none'}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), embedding_engine: e.target.value as BackendSettings['embedding_engine'] })), setDirty(true))}
+                    className="px-3 py-1 border border-border rounded-md bg-background"
+                    disabled={!draft}
+                  >
+                    <option value="none">Off</option>
+                    <option value="ollama">Local (Ollama)</option>
+                    <option value="openai">OpenAI (cloud)</option>
+                  </select>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    disabled={!settings || settings.embedding_engine === 'none'}
+                    onClick={async () => {
+                      setIndexStatus('Indexing…');
+                      try {
+                        const count = await invoke<number>('rebuild_semantic_index');
+                        setIndexStatus(`Indexed ${count} session${count === 1 ? '' : 's'}`);
+                      } catch (e) {
+                        setIndexStatus(errorMessage(e));
+                      }
+                    }}
+                  >
+                    Index existing
+                  </Button>
+                </div>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">Usage</p>
+                  <p className="text-sm text-muted-foreground">
+                    {usage
+                      ? `${usage.input_tokens.toLocaleString()} tokens in, ${usage.output_tokens.toLocaleString()} out · about $${usage.estimated_cost_usd.toFixed(2)} on cloud engines`
+                      : 'Token counts for summaries'}
+                  </p>
+                </div>
+                <div className="flex items-center gap-2 text-sm">
+                  <span className="text-muted-foreground">$/1K in</span>
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.0005}
+                    value={draft?.usage_price_input_per_1k ?? 0}
+                    onChange={(e) => {
+                      const raw = (e.target as HTMLInputElement).valueAsNumber;
+                      if (!draft || Number.isNaN(raw)) return;
+                      setDraft(prev => ({ ...(prev as BackendSettings), usage_price_input_per_1k: Math.max(0, raw) }));
+                      setDirty(true);
+                    }}
+                    className="w-20 px-2 py-1 border border-border rounded-md bg-background text-right"
+                    disabled={!draft}
+                  />
+                  <span className="text-muted-foreground">out</span>
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.0005}
+                    value={draft?.usage_price_output_per_1k ?? 0}
+                    onChange={(e) => {
+                      const raw = (e.target as HTMLInputElement).valueAsNumber;
+                      if (!draft || Number.isNaN(raw)) return;
+                      setDraft(prev => ({ ...(prev as BackendSettings), usage_price_output_per_1k: Math.max(0, raw) }));
+                      setDirty(true);
+                    }}
+                    className="w-20 px-2 py-1 border border-border rounded-md bg-background text-right"
+                    disabled={!draft}
+                  />
+                </div>
+              </div>
+
+              {draft?.summary_engine !== 'none' && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">Fallback engine</p>
+                    <p className="text-sm text-muted-foreground">Used only when the main engine can't be reached</p>
+                  </div>
+                  <select
+                    value={draft?.summary_fallback_engine ?? ''}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), summary_fallback_engine: e.target.value })), setDirty(true))}
+                    className="px-3 py-1 border border-border rounded-md bg-background"
+                    disabled={!draft}
+                  >
+                    <option value="">None</option>
+                    {[['ollama', 'Local (Ollama)'], ['anthropic', 'Claude (cloud)'], ['openai', 'OpenAI (cloud)']]
+                      .filter(([value]) => value !== draft?.summary_engine)
+                      .map(([value, label]) => <option key={value} value={value}>{label}</option>)}
+                  </select>
+                </div>
+              )}
+
+              <div>
+                <div className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">Summary format</p>
+                    <p className="text-sm text-muted-foreground">Custom templates can use {'{{title}}'}, {'{{duration}}'} and must include {'{{transcript}}'}</p>
+                  </div>
+                  <select
+                    value={['', 'bullets', 'executive', 'bant'].includes(draft?.summary_prompt_template ?? '') ? (draft?.summary_prompt_template ?? '') : 'custom'}
+                    onChange={(e) => {
+                      if (!draft) return;
+                      const value = e.target.value === 'custom' ? 'Summarize {{title}} ({{duration}}):\n\n{{transcript}}' : e.target.value;
+                      setDraft(prev => ({ ...(prev as BackendSettings), summary_prompt_template: value }));
+                      setDirty(true);
+                    }}
+                    className="px-3 py-1 border border-border rounded-md bg-background"
+                    disabled={!draft}
+                  >
+                    <option value="">Default</option>
+                    <option value="bullets">Bullet points</option>
+                    <option value="executive">Executive summary</option>
+                    <option value="bant">Sales call (BANT)</option>
+                    <option value="custom">Custom…</option>
+                  </select>
+                </div>
+                {draft && !['', 'bullets', 'executive', 'bant'].includes(draft.summary_prompt_template) && (
+                  <textarea
+                    value={draft.summary_prompt_template}
+                    onChange={(e) => { setDraft(prev => ({ ...(prev as BackendSettings), summary_prompt_template: e.target.value })); setDirty(true); }}
+                    rows={4}
+                    className="mt-2 w-full px-3 py-2 border border-border rounded-md bg-background text-sm font-mono"
+                  />
+                )}
+                {draft && !['', 'bullets', 'executive', 'bant'].includes(draft.summary_prompt_template) && !draft.summary_prompt_template.includes('{{transcript}}') && (
+                  <p className="mt-1 text-sm text-red-600">The template must include {'{{transcript}}'}</p>
+                )}
+              </div>
+
               {draft?.summary_engine === 'ollama' && (
                 <>
                   <div className="flex items-center justify-between">
@@ -191,8 +1053,68 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
                       disabled={!draft}
                     />
                   </div>
+
+                  <div className="flex items-center justify-between">
+                    <div className="flex items-center gap-2 text-sm">
+                      {ollamaStatus && (
+                        <span className={`h-2 w-2 rounded-full ${ollamaStatus.reachable && ollamaStatus.model_available ? 'bg-green-500' : 'bg-red-500'}`} />
+                      )}
+                      <span className="text-muted-foreground">
+                        {ollamaStatus
+                          ? (ollamaStatus.error ?? `Connected · ${ollamaStatus.available_models.length} models`)
+                          : 'Checks the saved host and model'}
+                      </span>
+                    </div>
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={async () => {
+                        try {
+                          setOllamaStatus(await invoke('test_ollama_connection'));
+                        } catch (e) {
+                          setOllamaStatus({ reachable: false, model_available: false, available_models: [], error: errorMessage(e) });
+                        }
+                      }}
+                    >
+                      Test connection
+                    </Button>
+                  </div>
                 </>
               )}
+
+              {[draft?.summary_engine, draft?.summary_fallback_engine, draft?.embedding_engine].includes('openai') && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">OpenAI base URL</p>
+                    <p className="text-sm text-muted-foreground">Point at a gateway or proxy (Azure OpenAI, LiteLLM) instead of api.openai.com</p>
+                  </div>
+                  <input
+                    type="text"
+                    placeholder="https://api.openai.com/v1"
+                    value={draft?.openai_base_url ?? ''}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), openai_base_url: e.target.value })), setDirty(true))}
+                    className="w-72 px-3 py-1 border border-border rounded-md bg-background"
+                    disabled={!draft}
+                  />
+                </div>
+              )}
+
+              {[draft?.summary_engine, draft?.summary_fallback_engine].includes('anthropic') && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <p className="font-medium">Anthropic base URL</p>
+                    <p className="text-sm text-muted-foreground">Point at a gateway or proxy instead of api.anthropic.com</p>
+                  </div>
+                  <input
+                    type="text"
+                    placeholder="https://api.anthropic.com/v1"
+                    value={draft?.anthropic_base_url ?? ''}
+                    onChange={(e) => draft && (setDraft(prev => ({ ...(prev as BackendSettings), anthropic_base_url: e.target.value })), setDirty(true))}
+                    className="w-72 px-3 py-1 border border-border rounded-md bg-background"
+                    disabled={!draft}
+                  />
+                </div>
+              )}
             </div>
           </section>
 
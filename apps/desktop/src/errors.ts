@@ -0,0 +1,26 @@
+// Every backend command rejects with `{ code, message }` (see src-tauri/src/error.rs)
+export type AppErrorCode =
+  | 'database'
+  | 'audio'
+  | 'permission'
+  | 'transcription'
+  | 'network'
+  | 'not_found'
+  | 'invalid_input'
+  | 'cancelled'
+  | 'internal';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/** Text to show for a failed `invoke`, whatever it rejected with. */
+export function errorMessage(error: unknown): string {
+  if (isAppError(error) || error instanceof Error) return error.message;
+  return String(error);
+}
@@ -4,26 +4,63 @@ import { listen } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/tauri';
 
 export interface AudioFrame {
-  data: number[];
+  data: Float32Array | number[]; // Float32Array when sent as base64, read it without copying
   timestamp: number;
   sample_rate: number;
+  source?: 'mic' | 'system' | 'track1' | 'track2'; // only set when capture_mode is 'separate' or 'tracks'
+  track?: 1 | 2;
 }
 
+// Wire format: either `data` (JSON floats) or `data_b64` (base64 f32le), per the audio_frame_encoding setting
+interface RawAudioFrame {
+  data?: number[];
+  data_b64?: string;
+  encoding?: 'f32le';
+  timestamp: number;
+  sample_rate: number;
+  source?: 'mic' | 'system' | 'track1' | 'track2';
+  track?: 1 | 2;
+}
+
+function decodeFrame(raw: RawAudioFrame): AudioFrame {
+  if (raw.data_b64) {
+    const bin = atob(raw.data_b64);
+    const bytes = new Uint8Array(bin.length);
+    for (let i = 0; i < bin.length; i++) bytes[i] = bin.charCodeAt(i);
+    const samples = new Float32Array(bytes.buffer, 0, Math.floor(bytes.length / 4));
+    return { data: samples, timestamp: raw.timestamp, sample_rate: raw.sample_rate, source: raw.source, track: raw.track };
+  }
+  return { data: raw.data ?? [], timestamp: raw.timestamp, sample_rate: raw.sample_rate, source: raw.source, track: raw.track };
+}
+
+// Per-stream chunking state; the single mixed stream uses the 'mixed' key
+interface StreamState {
+  buffer: number[];
+  speaking: boolean;
+  lastVoiceMs: number | null;
+  transcribing: boolean;
+}
+
+export type RecordingState = 'idle' | 'starting' | 'recording' | 'paused' | 'stopping';
+
+const newStream = (): StreamState => ({ buffer: [], speaking: false, lastVoiceMs: null, transcribing: false });
+
 export function useAudio() {
   const { settings } = useSettings();
   const [chunkSeconds, setChunkSeconds] = useState<number>(2.5);
+  // Per-recording override returned by start_recording; wins over the setting until reset
+  const [sessionChunkSeconds, setSessionChunkSeconds] = useState<number | null>(null);
+  const liveTranscriptionRef = useRef(true);
   const [isRecording, setIsRecording] = useState(false);
   const [frameCount, setFrameCount] = useState(0);
   const [transcript, setTranscript] = useState('');
-  const audioBufferRef = useRef<number[]>([]);
+  const streamsRef = useRef<Record<string, StreamState>>({});
   const [levels, setLevels] = useState<number[]>([]); // recent normalized RMS levels
   const [sampleRate, setSampleRate] = useState<number | null>(null);
   const [recordingStartTime, setRecordingStartTime] = useState<number | null>(null);
-  const lastSnippetRef = useRef<string>("");
-  const speakingRef = useRef<boolean>(false);
-  const lastVoiceMsRef = useRef<number | null>(null);
+  const [droppedChunks, setDroppedChunks] = useState(0);
+  const [transcriptionLagging, setTranscriptionLagging] = useState(false);
   const sampleRateRef = useRef<number | null>(null);
-  const transcribingRef = useRef<boolean>(false);
 
   // Check recording status from backend on mount to restore state
   useEffect(() => {
@@ -40,38 +77,37 @@ export function useAudio() {
   }, []);
 
   const resetHotRefs = () => {
-    audioBufferRef.current = [];
-    speakingRef.current = false;
-    lastVoiceMsRef.current = null;
+    streamsRef.current = {};
     sampleRateRef.current = null;
-    transcribingRef.current = false;
   };
 
-  const flushTranscription = useCallback(async () => {
-    if (transcribingRef.current) return;
-    const sr = sampleRateRef.current;
-    if (!sr || audioBufferRef.current.length === 0) return;
-    transcribingRef.current = true;
-    const chunk = audioBufferRef.current;
-    audioBufferRef.current = [];
+  const flushTranscription = useCallback(async (key: string, sr: number) => {
+    const stream = streamsRef.current[key];
+    if (!stream || stream.transcribing || stream.buffer.length === 0) return;
+    if (!liveTranscriptionRef.current) {
+      // Record-only mode: nothing to transcribe, just don't let the buffer grow
+      stream.buffer = [];
+      return;
+    }
+    stream.transcribing = true;
+    const chunk = stream.buffer;
+    stream.buffer = [];
+    const source = key === 'mixed' ? null : key;
     try {
-      const transcriptionResult = await invoke<string>('transcribe_audio', {
+      // Queued on the backend; the running transcript comes back as transcript:updated
+      await invoke('transcribe_audio', {
         audioFrames: chunk,
         audio_frames: chunk,
         sampleRate: sr,
         sample_rate: sr,
+        source,
       });
-      const cleaned = (transcriptionResult || '').trim();
-      if (cleaned && cleaned !== lastSnippetRef.current) {
-        setTranscript(prev => prev + (prev ? ' ' : '') + cleaned);
-        lastSnippetRef.current = cleaned;
-      }
     } catch (error) {
-      console.error('Transcription failed:', error);
+      console.error('Failed to queue transcription:', error);
     } finally {
-      speakingRef.current = false;
-      lastVoiceMsRef.current = null;
-      transcribingRef.current = false;
+      stream.speaking = false;
+      stream.lastVoiceMs = null;
+      stream.transcribing = false;
     }
   }, []);
 
@@ -81,14 +117,16 @@ export function useAudio() {
       sampleRateRef.current = frame.sample_rate;
       setSampleRate(frame.sample_rate);
     }
+    const key = frame.source ?? 'mixed';
+    const stream = streamsRef.current[key] ?? (streamsRef.current[key] = newStream());
 
     // Accumulate audio data
-    audioBufferRef.current.push(...frame.data);
+    stream.buffer.push(...frame.data);
 
     // Compute a simple RMS level for visualization
-    const rms = Math.sqrt(
-      frame.data.reduce((acc, v) => acc + (v * v), 0) / Math.max(1, frame.data.length)
-    );
+    let sumSquares = 0;
+    for (let i = 0; i < frame.data.length; i++) sumSquares += frame.data[i] * frame.data[i];
+    const rms = Math.sqrt(sumSquares / Math.max(1, frame.data.length));
     // Normalize: if input is float -1..1 then rms ~0..1; if i16, approximate normalization
     const normalized = rms > 1 ? Math.min(1, rms / 32767) : Math.min(1, rms);
     setLevels(prev => {
@@ -101,30 +139,30 @@ export function useAudio() {
     const nowMs = frame.timestamp;
     const vadOn = 0.03;   // start speaking threshold
     const vadOff = 0.02;  // stop speaking threshold (hysteresis)
-    if (!speakingRef.current && normalized >= vadOn) {
-      speakingRef.current = true;
+    if (!stream.speaking && normalized >= vadOn) {
+      stream.speaking = true;
     }
-    if (speakingRef.current) {
+    if (stream.speaking) {
       if (normalized >= vadOff) {
-        lastVoiceMsRef.current = nowMs;
-      } else if (lastVoiceMsRef.current == null) {
-        lastVoiceMsRef.current = nowMs;
+        stream.lastVoiceMs = nowMs;
+      } else if (stream.lastVoiceMs == null) {
+        stream.lastVoiceMs = nowMs;
       }
     }
 
     // Transcribe when either: chunk length reached OR we detect a pause after speech
-    const cs = Math.max(1, Math.min(6, Number(chunkSeconds ?? 2.5)));
-    const sr = sampleRateRef.current;
-    const neededSamples = sr ? Math.floor(sr * cs) : 0;
-    const enoughForChunk = sr ? audioBufferRef.current.length >= neededSamples : false;
-    const silenceGapMs = lastVoiceMsRef.current ? (nowMs - lastVoiceMsRef.current) : Infinity;
-    const minUtteranceSamples = sr ? Math.floor(sr * Math.min(1.0, cs)) : 0; // at least ~1s
-    const pauseDetected = speakingRef.current && silenceGapMs >= 450 && audioBufferRef.current.length >= minUtteranceSamples;
-
-    if ((enoughForChunk || pauseDetected) && !transcribingRef.current) {
-      flushTranscription();
+    const cs = Math.max(0.5, Math.min(10, Number(sessionChunkSeconds ?? chunkSeconds ?? 2.5)));
+    const sr = frame.sample_rate;
+    const neededSamples = Math.floor(sr * cs);
+    const enoughForChunk = stream.buffer.length >= neededSamples;
+    const silenceGapMs = stream.lastVoiceMs ? (nowMs - stream.lastVoiceMs) : Infinity;
+    const minUtteranceSamples = Math.floor(sr * Math.min(1.0, cs)); // at least ~1s
+    const pauseDetected = stream.speaking && silenceGapMs >= 450 && stream.buffer.length >= minUtteranceSamples;
+
+    if ((enoughForChunk || pauseDetected) && !stream.transcribing) {
+      flushTranscription(key, sr);
     }
-  }, [frameCount, chunkSeconds, flushTranscription]);
+  }, [frameCount, chunkSeconds, sessionChunkSeconds, flushTranscription]);
 
   // Stable event subscription to avoid resubscribe storms
   const handlerRef = useRef<(f: AudioFrame) => void>();
@@ -132,28 +170,72 @@ export function useAudio() {
   useEffect(() => {
     let active = true;
     let unlistenFn: (() => void) | null = null;
-    listen<AudioFrame>('audio:frame', (event) => {
-      if (active) handlerRef.current?.(event.payload);
+    listen<RawAudioFrame>('audio:frame', (event) => {
+      if (active) handlerRef.current?.(decodeFrame(event.payload));
     }).then(fn => { unlistenFn = fn; });
     return () => { active = false; if (unlistenFn) unlistenFn(); };
   }, []);
 
-  // hydrate chunkSeconds and listen for runtime updates from Settings
+  // The backend assembles the running transcript (de-duplicated, speaker-labelled) and
+  // sends the whole thing after every chunk
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let updated = false;
+    listen<{ text: string; delta: string; source?: string | null }>('transcript:updated', (e) => {
+      updated = true;
+      setTranscript(e.payload.text);
+    }).then(fn => { unlisten = fn; });
+    // Catch up on a recording that was already in progress when this window loaded
+    invoke<string>('get_live_transcript')
+      .then(text => { if (!updated && text) setTranscript(text); })
+      .catch(() => {});
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  // Backend reports chunks it had to drop because capture couldn't keep up
   useEffect(() => {
-    invoke<any>('get_settings').then(s => {
-      if (s && typeof s.chunk_seconds === 'number') setChunkSeconds(s.chunk_seconds);
-    }).catch(() => {});
     let unlisten: (() => void) | null = null;
-    listen<any>('settings:updated', (e) => {
-      const s = e.payload as any;
-      if (s && typeof s.chunk_seconds === 'number') setChunkSeconds(s.chunk_seconds);
+    listen<{ dropped: number; total: number }>('audio:dropped', (e) => {
+      setDroppedChunks(e.payload.total);
     }).then(fn => { unlisten = fn; });
     return () => { if (unlisten) unlisten(); };
   }, []);
 
-  const startRecording = async () => {
-    await invoke('start_recording');
-    // Start time is now managed by the backend
+  // The backend owns recording state; follow its transitions (e.g. a start that failed)
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<{ state: RecordingState; previous: RecordingState }>('recording:state', (e) => {
+      setIsRecording(e.payload.state === 'recording' || e.payload.state === 'paused');
+    }).then(fn => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  // Backend warns once Whisper has been slower than real time for several chunks in a row
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<{ rtf: number; model?: string | null }>('transcript:lagging', () => {
+      setTranscriptionLagging(true);
+    }).then(fn => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  // hydrate chunkSeconds/transcription mode and listen for runtime updates from Settings
+  useEffect(() => {
+    const apply = (s: any) => {
+      if (!s) return;
+      if (typeof s.chunk_seconds === 'number') setChunkSeconds(s.chunk_seconds);
+      if (typeof s.transcription_engine === 'string') liveTranscriptionRef.current = s.transcription_engine !== 'none';
+    };
+    invoke<any>('get_settings').then(apply).catch(() => {});
+    let unlisten: (() => void) | null = null;
+    listen<any>('settings:updated', (e) => apply(e.payload)).then(fn => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  const startRecording = async (options: { chunkSeconds?: number } = {}) => {
+    // Start time is managed by the backend; it also resolves the chunk length for this session
+    const effective = await invoke<number>('start_recording', { chunkSeconds: options.chunkSeconds ?? null });
+    setSessionChunkSeconds(effective);
   };
 
   const getRecordingDuration = async () => {
@@ -170,8 +252,11 @@ export function useAudio() {
     setTranscript('');
     setLevels([]);
     setSampleRate(null);
+    setDroppedChunks(0);
+    setTranscriptionLagging(false);
     resetHotRefs();
     setRecordingStartTime(null);
+    setSessionChunkSeconds(null);
   };
 
   return {
@@ -184,6 +269,8 @@ export function useAudio() {
     startRecording,
     getRecordingDuration,
     levels,
-    sampleRate
+    sampleRate,
+    droppedChunks,
+    transcriptionLagging
   };
 }
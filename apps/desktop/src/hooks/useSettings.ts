@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { errorMessage } from '../errors';
 
 export interface Settings {
   enable_telemetry: boolean;
@@ -13,6 +14,57 @@ export interface Settings {
   ollama_model: string;
   ollama_host: string;
   force_microphone: boolean;
+  audio_frame_encoding: 'json' | 'base64'; // base64 carries raw f32le samples
+  capture_mode: 'mixed' | 'separate' | 'tracks'; // separate: mic and system audio transcribed independently; tracks: two inputs recorded side by side
+  enable_diarization: boolean;
+  save_audio: boolean;
+  api_enabled: boolean;
+  api_port: number;
+  webhook_url: string;
+  frame_ms: number;
+  chunk_overlap_ms: number;
+  language: string;
+  encrypt_database: boolean;
+  input_gain: number;
+  transcription_engine: 'local' | 'none';
+  prefer_builtin_mic: boolean;
+  audio_gate_threshold: number;
+  audio_attack_ms: number;
+  audio_release_ms: number;
+  audio_makeup_gain: number;
+  audio_limiter_ceiling: number;
+  summary_prompt_template: string;
+  summary_fallback_engine: string;
+  usage_price_input_per_1k: number;
+  usage_price_output_per_1k: number;
+  embedding_engine: 'none' | 'ollama' | 'openai';
+  embedding_model: string;
+  whisper_model: string;
+  redact_transcript: boolean;
+  keep_raw_transcript: boolean;
+  normalize_numbers: boolean;
+  system_audio_app: string;
+  system_audio_sample_rate: number;
+  system_audio_channels: number;
+  auto_stop_silence_seconds: number;
+  openai_base_url: string;
+  anthropic_base_url: string;
+  min_transcribe_ms: number;
+  live_confidence: boolean;
+  log_level: string;
+  debug_save_audio: boolean;
+  adaptive_chunking: boolean;
+  track1_device: string; // '' uses the default input
+  track2_device: string;
+  translate_to_english: boolean;
+  mic_mix_level: number;
+  system_mix_level: number;
+  mix_auto_gain: boolean;
+  transcription_preset: 'fast' | 'balanced' | 'accurate'; // live Whisper speed/accuracy trade-off
+  low_power_mode: boolean;
+  whisper_temperature: number | null; // advanced; null = the preset's value
+  whisper_entropy_thold: number | null; // advanced; null = the preset's value
+  whisper_logprob_thold: number | null; // advanced; null = the preset's value
 }
 
 export function useSettings() {
@@ -27,20 +79,28 @@ export function useSettings() {
       setSettings(result);
       setError(null);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
   };
 
+  // Only fields that differ from what was last loaded are sent, so values another screen saved
+  // in the meantime aren't overwritten with this screen's stale copy
   const saveSettings = async (newSettings: Settings): Promise<Settings> => {
+    const changes = (Object.keys(newSettings) as (keyof Settings)[]).reduce<Partial<Settings>>((acc, key) => {
+      if (!settings || JSON.stringify(newSettings[key]) !== JSON.stringify(settings[key])) {
+        (acc as Record<string, unknown>)[key] = newSettings[key];
+      }
+      return acc;
+    }, {});
     try {
-      const saved = await invoke<Settings>('update_settings', { settings: newSettings });
+      const saved = await invoke<Settings>('update_settings', { settings: changes });
       setSettings(saved);
       setError(null);
       return saved;
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       throw err;
     }
   };